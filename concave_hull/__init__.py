@@ -3,7 +3,78 @@ Concave Hull
 
 The concave hull core is written in Rust for performance.
 """
-from concave_hull.concave_hull import concave_hull_2d
+from concave_hull.concave_hull import (
+    __build_info__,
+    ball_pivoting,
+    boundary_band,
+    buffer,
+    buffer_rings,
+    concave_hull_2d,
+    concave_hull_aggregated,
+    concave_hull_antimeridian,
+    concave_hull_batch,
+    concave_hull_iter,
+    concave_hull_periodic,
+    concave_hull_polylines,
+    concave_hull_tiled,
+    core_area_hull,
+    dataset_stats,
+    edge_length_histogram,
+    evaluate,
+    get_defaults,
+    grid_hull,
+    hull_edge_list,
+    hulls,
+    kde_contour,
+    knn_distance_histogram,
+    orthogonalize,
+    sample_polygon_boundary_and_interior,
+    set_defaults,
+    simplify,
+    smooth,
+    suggest_thresholds,
+    triangulate,
+    window_hulls,
+    Dataset,
+    HullIter,
+    PreparedHull,
+)
+
+__all__ = [
+    "ball_pivoting",
+    "boundary_band",
+    "buffer",
+    "buffer_rings",
+    "concave_hull_2d",
+    "concave_hull_aggregated",
+    "concave_hull_antimeridian",
+    "concave_hull_batch",
+    "concave_hull_iter",
+    "concave_hull_periodic",
+    "concave_hull_polylines",
+    "concave_hull_tiled",
+    "core_area_hull",
+    "dataset_stats",
+    "edge_length_histogram",
+    "evaluate",
+    "get_defaults",
+    "grid_hull",
+    "hull_edge_list",
+    "hulls",
+    "kde_contour",
+    "knn_distance_histogram",
+    "orthogonalize",
+    "sample_polygon_boundary_and_interior",
+    "set_defaults",
+    "simplify",
+    "smooth",
+    "suggest_thresholds",
+    "triangulate",
+    "window_hulls",
+    "Dataset",
+    "HullIter",
+    "PreparedHull",
+]
 
 __author__ = "Ethan Lew"
 __copyright__ = "Copyright 2024"
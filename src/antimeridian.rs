@@ -0,0 +1,182 @@
+/// Hulls of Longitude/Latitude Data Across the Antimeridian
+use crate::error::ConcaveHullError;
+use crate::periodic::PeriodicDomain;
+use crate::point::Point;
+
+/// Shifts longitudes by whole turns so that points close across the antimeridian are close
+/// in degrees too.
+///
+/// The longitudes are cut at their widest gap, see [`PeriodicDomain::unwrap`], and then
+/// moved by a whole number of turns so that the westernmost lies in `[-180, 180)`. Data that
+/// doesn't straddle the antimeridian comes back unchanged; data that does extends past 180.
+///
+/// # Arguments
+///
+/// * `points` - longitude (x) / latitude (y) points in degrees.
+///
+/// # Returns
+///
+/// Returns the shifted points in input order.
+pub fn unwrap_longitudes(points: &[Point]) -> Vec<Point> {
+    let domain = PeriodicDomain {
+        x_period: Some(360.0),
+        y_period: None,
+    };
+    let unwrapped = domain.unwrap(points);
+    let west = unwrapped.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+    let turns = ((west + 180.0) / 360.0).floor();
+    // shift the original longitudes, so that those that don't move stay exact
+    points
+        .iter()
+        .zip(unwrapped.iter())
+        .map(|(p, u)| Point {
+            x: p.x + (((u.x - p.x) / 360.0).round() - turns) * 360.0,
+            ..p.clone()
+        })
+        .collect()
+}
+
+/// Whether the concave hull of `points` straddles the antimeridian, i.e. whether
+/// [`unwrap_longitudes`] moves any point past 180.
+pub fn crosses_antimeridian(points: &[Point]) -> bool {
+    unwrap_longitudes(points).iter().any(|p| p.x >= 180.0)
+}
+
+/// Computes the concave hull of longitude/latitude points that may straddle the
+/// antimeridian.
+///
+/// The longitudes are unwrapped first, see [`unwrap_longitudes`], so a hull of points on
+/// either side of ±180 encloses just them instead of crossing the whole map. Otherwise this
+/// is [`crate::concave_hull_geographic`].
+///
+/// # Arguments
+///
+/// * `dataset` -  longitude/latitude point cloud, with unique ids.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull as a closed ring in unwrapped longitudes, which exceed 180 east of the
+/// antimeridian; [`split_at_antimeridian`] turns it into rings within `[-180, 180]`. Fails
+/// as [`crate::concave_hull_geographic`] does.
+pub fn concave_hull_antimeridian(
+    dataset: &[Point],
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    crate::concave_hull_geographic(&mut unwrap_longitudes(dataset), k, iterate)
+}
+
+/// Splits a ring in unwrapped longitudes at the antimeridian, as GeoJSON (RFC 7946) asks
+/// of polygons crossing it.
+///
+/// The part east of 180 is shifted back by 360 degrees. Edges are cut where they meet the
+/// antimeridian, interpolating the latitude linearly in degrees like the edges of the
+/// hull. A ring crossing the antimeridian more than twice keeps each side as one ring,
+/// joined along the antimeridian by edges that enclose no area.
+///
+/// # Arguments
+///
+/// * `ring` - a ring, open or closed, with longitudes in `[-180, 540)`, such as the output
+///   of [`concave_hull_antimeridian`].
+///
+/// # Returns
+///
+/// Returns the closed western ring, then the closed eastern ring if the ring crosses the
+/// antimeridian. Vertices inserted on the antimeridian have id 0.
+pub fn split_at_antimeridian(ring: &[Point]) -> Vec<Vec<Point>> {
+    let ring = crate::open_ring(ring);
+    let west = clip(ring, |x| x <= 180.0);
+    let mut east = clip(ring, |x| x >= 180.0);
+    for p in east.iter_mut() {
+        p.x -= 360.0;
+    }
+
+    [west, east]
+        .into_iter()
+        .filter(|part| part.len() >= 3)
+        .map(|mut part| {
+            part.push(part[0].clone());
+            part
+        })
+        .collect()
+}
+
+/// The part of `ring` on the side of x = 180 where `inside` holds (Sutherland-Hodgman)
+fn clip(ring: &[Point], inside: impl Fn(f64) -> bool) -> Vec<Point> {
+    let mut clipped = Vec::with_capacity(ring.len() + 2);
+    for (i, b) in ring.iter().enumerate() {
+        let a = &ring[(i + ring.len() - 1) % ring.len()];
+        if inside(b.x) {
+            if !inside(a.x) {
+                clipped.push(crossing(a, b));
+            }
+            clipped.push(b.clone());
+        } else if inside(a.x) {
+            clipped.push(crossing(a, b));
+        }
+    }
+    clipped.dedup_by(|a, b| a.x == b.x && a.y == b.y);
+    clipped
+}
+
+/// where the edge from `a` to `b` meets the antimeridian
+fn crossing(a: &Point, b: &Point) -> Point {
+    let t = (180.0 - a.x) / (b.x - a.x);
+    Point {
+        x: 180.0,
+        y: a.y + t * (b.y - a.y),
+        id: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_concave_hull_antimeridian() {
+        // a patch of Fiji, from 175 E to 175 W
+        let mut rng = Rng::new(4);
+        let points: Vec<Point> = (0..300)
+            .map(|id| {
+                let lon = 175.0 + 10.0 * rng.uniform();
+                Point {
+                    x: if lon >= 180.0 { lon - 360.0 } else { lon },
+                    y: -20.0 + 5.0 * rng.uniform(),
+                    id: id + 1,
+                }
+            })
+            .collect();
+        assert!(crosses_antimeridian(&points));
+        assert!(!crosses_antimeridian(&[]));
+
+        let hull = concave_hull_antimeridian(&points, 3, true).unwrap();
+        assert!(!hull.is_empty());
+        assert!(hull.iter().all(|p| (175.0..185.0).contains(&p.x)));
+
+        let parts = split_at_antimeridian(&hull);
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].iter().all(|p| (175.0..=180.0).contains(&p.x)));
+        assert!(parts[1].iter().all(|p| (-180.0..-175.0).contains(&p.x)));
+        // the two parts add up to the unwrapped hull
+        let area = |ring: &[Point]| crate::buffer::signed_area(ring);
+        assert!((area(&parts[0]) + area(&parts[1]) - area(&hull)).abs() < 1E-9);
+
+        // data away from the antimeridian is left alone
+        let moved: Vec<Point> = points
+            .iter()
+            .map(|p| Point {
+                x: (p.x + 360.0) % 360.0 - 180.0,
+                ..p.clone()
+            })
+            .collect();
+        assert!(!crosses_antimeridian(&moved));
+        assert_eq!(unwrap_longitudes(&moved), moved);
+        let hull = concave_hull_antimeridian(&moved, 3, true).unwrap();
+        assert_eq!(split_at_antimeridian(&hull), vec![hull]);
+    }
+}
@@ -0,0 +1,142 @@
+/// Approximate Comparison of Coordinates and Hulls
+///
+/// Hulls that reach the same vertices by different arithmetic, e.g. through shifted,
+/// wrapped or reprojected coordinates, agree only up to rounding, so tests against them
+/// compare coordinates within a few units in the last place (ULPs) rather than with `==`:
+///
+/// ```
+/// use concave_hull::assert_hull_approx_eq;
+///
+/// assert_hull_approx_eq!(hull, expected);
+/// assert_hull_approx_eq!(hull, expected, ulps = 64);
+/// ```
+///
+/// ULPs scale with the magnitude of the values, so a coordinate that should be 0 but comes
+/// out as 1E-17 is far from equal; compare such values with an absolute tolerance.
+use crate::point::Point;
+
+/// ULPs [`assert_hull_approx_eq!`] allows unless told otherwise
+pub const DEFAULT_ULPS: u64 = 4;
+
+/// The number of representable `f64` values from `a` to `b`, `None` if either is NaN.
+///
+/// `0.0` and `-0.0` are 0 apart, and the smallest subnormals on either side of zero 2.
+pub fn ulps_between(a: f64, b: f64) -> Option<u64> {
+    if a.is_nan() || b.is_nan() {
+        return None;
+    }
+    // the bit patterns of finite floats ordered like the values
+    let ordered = |x: f64| {
+        let bits = x.to_bits() as i64;
+        if bits < 0 {
+            i64::MIN.wrapping_sub(bits)
+        } else {
+            bits
+        }
+    };
+    Some(ordered(a).abs_diff(ordered(b)))
+}
+
+/// Whether `a` and `b` are at most `max_ulps` ULPs apart; NaN equals nothing.
+pub fn ulps_eq(a: f64, b: f64, max_ulps: u64) -> bool {
+    ulps_between(a, b).is_some_and(|ulps| ulps <= max_ulps)
+}
+
+/// Whether `a` and `b` have the same id and coordinates at most `max_ulps` ULPs apart.
+pub fn point_approx_eq(a: &Point, b: &Point, max_ulps: u64) -> bool {
+    a.id == b.id && ulps_eq(a.x, b.x, max_ulps) && ulps_eq(a.y, b.y, max_ulps)
+}
+
+/// How `left` differs from `right` beyond `max_ulps`, vertex by vertex.
+///
+/// # Arguments
+///
+/// * `left`, `right` - the hulls to compare, in the same vertex order.
+/// * `max_ulps` - the largest distance of equal coordinates.
+///
+/// # Returns
+///
+/// Returns `None` if the hulls are equal, or else a description of the first difference:
+/// a different vertex count, or the first vertex that differs.
+pub fn hull_mismatch(left: &[Point], right: &[Point], max_ulps: u64) -> Option<String> {
+    if left.len() != right.len() {
+        return Some(format!(
+            "{} vertices against {} vertices",
+            left.len(),
+            right.len()
+        ));
+    }
+    let (i, (a, b)) = left
+        .iter()
+        .zip(right)
+        .enumerate()
+        .find(|(_, (a, b))| !point_approx_eq(a, b, max_ulps))?;
+    let ulps = |a: f64, b: f64| ulps_between(a, b).map_or("NaN".to_string(), |u| u.to_string());
+    Some(format!(
+        "vertex {}: {:?} against {:?} ({} and {} ULPs apart)",
+        i,
+        a,
+        b,
+        ulps(a.x, b.x),
+        ulps(a.y, b.y)
+    ))
+}
+
+/// Asserts that two hulls have the same vertices in the same order, with coordinates within
+/// [`DEFAULT_ULPS`] ULPs, or the given `ulps`, of each other.
+///
+/// Either side may be anything that slices into `[Point]`.
+///
+/// # Panics
+///
+/// Panics with the first difference found by [`hull_mismatch`].
+#[macro_export]
+macro_rules! assert_hull_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_hull_approx_eq!($left, $right, ulps = $crate::approx::DEFAULT_ULPS)
+    };
+    ($left:expr, $right:expr, ulps = $ulps:expr $(,)?) => {
+        if let Some(mismatch) = $crate::approx::hull_mismatch(&$left[..], &$right[..], $ulps) {
+            panic!("assertion `left ≈ right` failed: {}", mismatch);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approx_eq() {
+        assert_eq!(ulps_between(1.0, 1.0f64.next_up()), Some(1));
+        assert_eq!(ulps_between(0.0, -0.0), Some(0));
+        assert_eq!(
+            ulps_between(f64::MIN_POSITIVE, -f64::MIN_POSITIVE),
+            Some(2 << 52)
+        );
+        assert_eq!(ulps_between(f64::NAN, 1.0), None);
+        assert!(ulps_eq(0.1 + 0.2, 0.3, 1));
+        assert!(!ulps_eq(0.1 + 0.2, 0.3, 0));
+
+        let point = |x: f64, y: f64, id: u64| Point { x, y, id };
+        let hull = vec![point(0.1 + 0.2, 1.0, 0), point(2.0, 3.0, 1)];
+        let expected = vec![point(0.3, 1.0, 0), point(2.0, 3.0, 1)];
+        assert_hull_approx_eq!(hull, expected);
+        assert!(hull_mismatch(&hull, &expected, 0)
+            .unwrap()
+            .starts_with("vertex 0"));
+        assert!(hull_mismatch(&hull[..1], &expected, 4).is_some());
+        assert!(hull_mismatch(&[point(2.0, 3.0, 2)], &expected[1..], 4).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "2 vertices against 1 vertices")]
+    fn test_assert_hull_approx_eq() {
+        let point = Point {
+            x: 0.0,
+            y: 0.0,
+            id: 0,
+        };
+        assert_hull_approx_eq!(vec![point.clone(), point.clone()], [point]);
+    }
+}
@@ -1,8 +1,42 @@
+// arrays are built through the safe numpy constructors, see `vec_points_to_numpy`
+#![deny(unsafe_code)]
 /// Python Bindings for Fast Concave Hull Algorithm
+use crate::error::ConcaveHullError;
+use crate::index::Dataset;
+use crate::metric::Metric;
+use crate::multi::MultiHull;
 use crate::point::Point;
+use crate::prepared::PreparedHull;
 
-use numpy::{PyArray2, PyReadonlyArray2};
+use numpy::{PyArray1, PyArray2, PyArray3, PyReadonlyArray1, PyReadonlyArray2};
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+
+/// Raises why no hull was found: a `ValueError` for unusable input, a `RuntimeError` if no
+/// `k` gave a hull, a `KeyboardInterrupt` for a cancelled one and a `TimeoutError`.
+impl From<ConcaveHullError> for PyErr {
+    fn from(error: ConcaveHullError) -> Self {
+        let message = error.to_string();
+        match error {
+            ConcaveHullError::InsufficientPoints { .. }
+            | ConcaveHullError::InvalidCoordinate { .. } => {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(message)
+            }
+            ConcaveHullError::NoValidHullFound { .. } => {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(message)
+            }
+            ConcaveHullError::Cancelled => {
+                PyErr::new::<pyo3::exceptions::PyKeyboardInterrupt, _>(message)
+            }
+            ConcaveHullError::TimedOut => {
+                PyErr::new::<pyo3::exceptions::PyTimeoutError, _>(message)
+            }
+        }
+    }
+}
 
 /// Converts a 2D NumPy array to a vector of `Point` objects.
 ///
@@ -46,53 +80,1401 @@ fn numpy_to_vec_points(array: PyReadonlyArray2<f64>) -> PyResult<Vec<Point>> {
     Ok(points)
 }
 
+/// Converts two columns of a 2D NumPy array to a vector of `Point` objects.
+///
+/// Only the selected columns are read, so a wide feature matrix is hulled without copying
+/// the columns out in Python first.
+///
+/// # Arguments
+///
+/// * `array`: PyReadonlyArray2<f64> - A readonly 2D NumPy array of any number of columns.
+/// * `axes`: (usize, usize) - The columns holding the x and y coordinates.
+///
+/// # Returns
+///
+/// * `PyResult<Vec<Point>>` - A vector of `Point` objects with the row as id on success, or a
+///   Python error if a column is out of range or both are the same.
+fn numpy_columns_to_vec_points(
+    array: PyReadonlyArray2<f64>,
+    axes: (usize, usize),
+) -> PyResult<Vec<Point>> {
+    let array = array.as_array();
+    let (x, y) = axes;
+    if x >= array.ncols() || y >= array.ncols() {
+        return Err(PyErr::new::<pyo3::exceptions::PyIndexError, _>(format!(
+            "axes ({}, {}) out of range for an array with {} columns",
+            x,
+            y,
+            array.ncols()
+        )));
+    }
+    if x == y {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "axes must be two different columns",
+        ));
+    }
+
+    Ok(array
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| Point {
+            x: row[x],
+            y: row[y],
+            id: i as u64,
+        })
+        .collect())
+}
+
+/// Converts a hull array (as returned by `concave_hull_2d`) to a vector of `Point` objects.
+///
+/// The array may have 2 columns (x, y) or 3 columns (x, y, id).
+fn numpy_to_hull_points(array: PyReadonlyArray2<f64>) -> PyResult<Vec<Point>> {
+    let array = array.as_array();
+    if array.ncols() != 2 && array.ncols() != 3 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "Hull array must have 2 or 3 columns",
+        ));
+    }
+
+    Ok(array
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| Point {
+            x: row[0],
+            y: row[1],
+            id: row.get(2).map_or(i as u64, |&id| id as u64),
+        })
+        .collect())
+}
+
+/// Converts `Point` objects to an (n, 3) NumPy array of x, y and id columns.
+fn vec_points_to_numpy<'py>(py: Python<'py>, points: &[Point]) -> PyResult<&'py PyArray2<f64>> {
+    let flat: Vec<f64> = points
+        .iter()
+        .flat_map(|p| [p.x, p.y, p.id as f64])
+        .collect();
+    PyArray1::from_vec(py, flat).reshape([points.len(), 3])
+}
+
+/// Converts a `MultiHull` to a list of polygons, each a list of (n, 3) arrays of x, y and id:
+/// the outer ring first, then the holes, all open.
+fn multi_hull_to_python(py: Python<'_>, multi: &MultiHull) -> PyResult<PyObject> {
+    let polygons = multi
+        .iter()
+        .map(|polygon| {
+            std::iter::once(&polygon.exterior)
+                .chain(polygon.holes.iter())
+                .map(|ring| vec_points_to_numpy(py, ring))
+                .collect::<PyResult<Vec<_>>>()
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(polygons.into_py(py))
+}
+
+/// A concave hull prepared for repeated membership and distance queries.
+///
+/// The edge index is built once in the constructor, so dashboards querying millions of
+/// points against a fixed hull don't pay the preparation cost on every call.
+#[pyclass(name = "PreparedHull")]
+pub struct PyPreparedHull {
+    inner: PreparedHull,
+}
+
+#[pymethods]
+impl PyPreparedHull {
+    /// Prepares a hull array with 2 (x, y) or 3 (x, y, id) columns.
+    #[new]
+    fn new(hull: PyReadonlyArray2<f64>) -> PyResult<Self> {
+        Ok(PyPreparedHull {
+            inner: PreparedHull::new(&numpy_to_hull_points(hull)?),
+        })
+    }
+
+    /// Returns a boolean array telling which of the (n, 2) `points` lie inside the hull.
+    fn contains<'py>(
+        &self,
+        py: Python<'py>,
+        points: PyReadonlyArray2<f64>,
+    ) -> PyResult<&'py PyArray1<bool>> {
+        let points = numpy_to_vec_points(points)?;
+        let inside = points.iter().map(|p| self.inner.contains(p)).collect();
+        Ok(PyArray1::from_vec(py, inside))
+    }
+
+    /// Returns the distance from each of the (n, 2) `points` to the hull boundary.
+    fn distance<'py>(
+        &self,
+        py: Python<'py>,
+        points: PyReadonlyArray2<f64>,
+    ) -> PyResult<&'py PyArray1<f64>> {
+        let points = numpy_to_vec_points(points)?;
+        let distances = points.iter().map(|p| self.inner.distance(p)).collect();
+        Ok(PyArray1::from_vec(py, distances))
+    }
+}
+
+/// Points shared between hulls and neighbour queries without copying.
+///
+/// The array is converted once in the constructor, and the neighbour index built by the
+/// first hull or query serves every later one, so a pipeline asking many questions of one
+/// large point set holds a single copy of it.
+#[pyclass(name = "Dataset")]
+pub struct PyDataset {
+    inner: Dataset,
+}
+
+#[pymethods]
+impl PyDataset {
+    /// Takes a dataset array with 2 (x, y) columns.
+    #[new]
+    fn new(dataset: PyReadonlyArray2<f64>) -> PyResult<Self> {
+        Ok(PyDataset {
+            inner: Dataset::new(numpy_to_vec_points(dataset)?),
+        })
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns the concave hull as `concave_hull_2d` does, raising its errors, without
+    /// holding the GIL.
+    #[pyo3(signature = (k = None, iterate = None))]
+    fn concave_hull<'py>(
+        &self,
+        py: Python<'py>,
+        k: Option<usize>,
+        iterate: Option<bool>,
+    ) -> PyResult<&'py PyArray2<f64>> {
+        let defaults = defaults();
+        let (k, iterate) = defaults.resolve(k, iterate);
+        let hull = py.allow_threads(|| self.inner.concave_hull(k, iterate))?;
+        vec_points_to_numpy(py, &finish(hull, &defaults))
+    }
+
+    /// Returns the `k` points nearest to `(x, y)`, closest first, as an (n, 3) array of x, y
+    /// and row.
+    fn nearest<'py>(
+        &self,
+        py: Python<'py>,
+        point: (f64, f64),
+        k: usize,
+    ) -> PyResult<&'py PyArray2<f64>> {
+        let query = Point {
+            x: point.0,
+            y: point.1,
+            id: 0,
+        };
+        let nearest: Vec<Point> =
+            py.allow_threads(|| self.inner.nearest(&query, k).into_iter().cloned().collect());
+        vec_points_to_numpy(py, &nearest)
+    }
+}
+
+/// a `(progress_fraction, partial_hull)` snapshot, or why the computation found no hull
+type Snapshot = Result<(f64, Vec<Point>), ConcaveHullError>;
+
+/// Iterator over the partial hulls of a running computation, see `concave_hull_iter`.
+#[pyclass(name = "HullIter")]
+pub struct PyHullIter {
+    receiver: Mutex<Receiver<Snapshot>>,
+}
+
+#[pymethods]
+impl PyHullIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Waits for the next `(progress_fraction, partial_hull)` tuple, without holding the GIL,
+    /// and raises the error of a computation that found no hull.
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        match py.allow_threads(|| self.receiver.lock().unwrap().recv().ok()) {
+            Some(Ok((progress, hull))) => Ok(Some(
+                (progress, vec_points_to_numpy(py, &hull)?).into_py(py),
+            )),
+            Some(Err(error)) => Err(error.into()),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Winding order of returned hulls.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Orientation {
+    /// as the algorithm traced it
+    Any,
+    /// counter-clockwise
+    Ccw,
+    /// clockwise
+    Cw,
+}
+
+/// Whether returned hulls repeat their first vertex at the end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ring {
+    /// as the algorithm traced it, which is closed unless the walk used up every point
+    Any,
+    /// the first vertex repeated at the end
+    Closed,
+    /// every vertex once
+    Open,
+}
+
+/// Parameter defaults of the Python functions, set with `set_defaults`.
+#[derive(Debug, Clone, Copy)]
+struct Defaults {
+    /// number of nearest neighbours
+    k: usize,
+    /// whether to raise `k` until the hull succeeds
+    iterate: bool,
+    /// winding order of returned hulls
+    orientation: Orientation,
+    /// whether returned hulls are closed rings
+    ring: Ring,
+    /// threads of `concave_hull_batch` and the long scans of `concave_hull_2d`, 0 for one
+    /// per core
+    threads: usize,
+}
+
+static DEFAULTS: Mutex<Defaults> = Mutex::new(Defaults {
+    k: 3,
+    iterate: true,
+    orientation: Orientation::Any,
+    ring: Ring::Any,
+    threads: 0,
+});
+
+fn defaults() -> Defaults {
+    *DEFAULTS.lock().unwrap()
+}
+
+impl Defaults {
+    /// `k` and `iterate` of a call, with these defaults for the omitted ones
+    fn resolve(&self, k: Option<usize>, iterate: Option<bool>) -> (usize, bool) {
+        (k.unwrap_or(self.k), iterate.unwrap_or(self.iterate))
+    }
+}
+
+/// A `ValueError` for a `k` too small to walk a hull with.
+fn check_k(k: usize) -> PyResult<()> {
+    if k < 3 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "k must be at least 3",
+        ));
+    }
+    Ok(())
+}
+
+/// Reverses a hull if it doesn't run in the configured orientation, and opens or closes it
+/// as configured.
+fn finish(mut hull: Vec<Point>, defaults: &Defaults) -> Vec<Point> {
+    hull = match defaults.orientation {
+        Orientation::Any => hull,
+        Orientation::Ccw => crate::orient(hull, crate::Orientation::CounterClockwise),
+        Orientation::Cw => crate::orient(hull, crate::Orientation::Clockwise),
+    };
+    match defaults.ring {
+        Ring::Any => hull,
+        Ring::Closed => crate::close_ring(hull),
+        Ring::Open => {
+            let open = crate::open_ring(&hull).len();
+            hull.truncate(open);
+            hull
+        }
+    }
+}
+
+/// Sets the defaults used when a parameter is omitted, for the rest of the process.
+///
+/// Parameters left as `None` keep their current default.
+///
+/// # Arguments
+///
+/// * `k`: Option<usize> - The number of neighbours, initially 3.
+/// * `iterate`: Option<bool> - Whether to raise `k` until the hull succeeds, initially true.
+/// * `orientation`: Option<&str> - Winding order of returned hulls: `"ccw"`, `"cw"`, or
+///   `"any"` (initially) for the order the algorithm traced, which is clockwise.
+/// * `ring`: Option<&str> - Whether returned hulls repeat their first vertex at the end:
+///   `"closed"`, as GeoJSON, WKT and shapely expect, `"open"`, or `"any"` (initially) for
+///   the ring as traced, which is closed unless the walk used up every point.
+/// * `threads`: Option<usize> - Threads of `concave_hull_batch`, and with the `parallel`
+///   feature of the crossing and containment scans of `concave_hull_2d` against long hulls,
+///   0 (initially) for one per core.
+///
+/// # Returns
+///
+/// * `PyResult<()>` - A `ValueError` for an unknown orientation or ring, or a `k` below 3.
+#[pyfunction]
+#[pyo3(signature = (k = None, iterate = None, orientation = None, threads = None, ring = None))]
+pub fn set_defaults(
+    k: Option<usize>,
+    iterate: Option<bool>,
+    orientation: Option<&str>,
+    threads: Option<usize>,
+    ring: Option<&str>,
+) -> PyResult<()> {
+    let orientation = match orientation {
+        None => None,
+        Some("any") => Some(Orientation::Any),
+        Some("ccw") => Some(Orientation::Ccw),
+        Some("cw") => Some(Orientation::Cw),
+        Some(other) => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "orientation must be 'ccw', 'cw' or 'any', not {:?}",
+                other
+            )))
+        }
+    };
+    let ring = match ring {
+        None => None,
+        Some("any") => Some(Ring::Any),
+        Some("closed") => Some(Ring::Closed),
+        Some("open") => Some(Ring::Open),
+        Some(other) => {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "ring must be 'closed', 'open' or 'any', not {:?}",
+                other
+            )))
+        }
+    };
+    if let Some(k) = k {
+        check_k(k)?;
+    }
+
+    let mut defaults = DEFAULTS.lock().unwrap();
+    defaults.k = k.unwrap_or(defaults.k);
+    defaults.iterate = iterate.unwrap_or(defaults.iterate);
+    defaults.orientation = orientation.unwrap_or(defaults.orientation);
+    defaults.ring = ring.unwrap_or(defaults.ring);
+    defaults.threads = threads.unwrap_or(defaults.threads);
+    Ok(())
+}
+
+/// Returns the current defaults set with `set_defaults`.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+///
+/// # Returns
+///
+/// * `PyResult<Py<PyDict>>` - A dict with the keys `k`, `iterate`, `orientation`, `ring` and
+///   `threads`.
+#[pyfunction]
+pub fn get_defaults(py: Python<'_>) -> PyResult<Py<PyDict>> {
+    let defaults = defaults();
+    let dict = PyDict::new(py);
+    dict.set_item("k", defaults.k)?;
+    dict.set_item("iterate", defaults.iterate)?;
+    let orientation = match defaults.orientation {
+        Orientation::Any => "any",
+        Orientation::Ccw => "ccw",
+        Orientation::Cw => "cw",
+    };
+    dict.set_item("orientation", orientation)?;
+    let ring = match defaults.ring {
+        Ring::Any => "any",
+        Ring::Closed => "closed",
+        Ring::Open => "open",
+    };
+    dict.set_item("ring", ring)?;
+    dict.set_item("threads", defaults.threads)?;
+    Ok(dict.into())
+}
+
 /// Calculates the concave hull of a dataset in 2D.
 ///
 /// This function takes a dataset and a parameter `k`, and computes the concave hull.
 /// The `iterate` flag controls the iteration behavior of the algorithm.
 ///
+/// The GIL is released during the computation, which polls for Python signals every few
+/// hundred steps, so Ctrl-C (a `KeyboardInterrupt`) stops long runs on huge datasets.
+///
 /// # Arguments
 ///
 /// * `py`: Python<'_> - Python interpreter context.
 /// * `dataset`: &PyArray2<f64> - Dataset represented as a 2D NumPy array.
-/// * `k`: usize - The number of neighbours to consider for determining the hull smoothness.
-/// * `iterate`: bool - Whether to iteratively refine the hull.
+/// * `k`: Option<usize> - The number of neighbours to consider for determining the hull
+///   smoothness; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+/// * `geographic`: bool - Treat columns as longitude/latitude degrees and use great-circle
+///   distances and bearings.
+/// * `axes`: Option<(usize, usize)> - The columns holding x and y in a wider array, e.g.
+///   `(3, 4)` of a feature matrix; the dataset must have exactly 2 columns if omitted.
 ///
 /// # Returns
 ///
 /// * `PyResult<Py<PyArray2<f64>>>` - A 2D NumPy array representing the concave hull on success,
-///    or a Python error on failure.
+///    or a Python error on failure. It runs in the orientation set with `set_defaults`.
 #[pyfunction]
+#[pyo3(signature = (dataset, k = None, iterate = None, geographic = false, axes = None))]
 pub fn concave_hull_2d(
     py: Python<'_>,
     dataset: &PyArray2<f64>,
-    k: usize,
-    iterate: bool,
+    k: Option<usize>,
+    iterate: Option<bool>,
+    geographic: bool,
+    axes: Option<(usize, usize)>,
 ) -> PyResult<Py<PyArray2<f64>>> {
-    let mut dataset_vec = numpy_to_vec_points(dataset.readonly())?;
-    let result = crate::concave_hull(&mut dataset_vec, k, iterate);
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let mut dataset_vec = match axes {
+        Some(axes) => numpy_columns_to_vec_points(dataset.readonly(), axes)?,
+        None => numpy_to_vec_points(dataset.readonly())?,
+    };
+    let metric = if geographic {
+        Metric::Haversine
+    } else {
+        Metric::Euclidean
+    };
+
+    // the signal check needs the GIL back; its error is the KeyboardInterrupt to raise
+    let signal = Arc::new(Mutex::new(None::<PyErr>));
+    let pending = signal.clone();
+    let interrupt = Box::new(move || {
+        Python::with_gil(|py| match py.check_signals() {
+            Ok(()) => false,
+            Err(err) => {
+                *pending.lock().unwrap() = Some(err);
+                true
+            }
+        })
+    });
+    let result = py.allow_threads(|| {
+        crate::concave_hull_interruptible(
+            &mut dataset_vec,
+            k,
+            iterate,
+            metric,
+            defaults.threads,
+            interrupt,
+        )
+    });
+    let result = match result {
+        Ok(result) => finish(result, &defaults),
+        Err(ConcaveHullError::Cancelled) => {
+            return Err(signal.lock().unwrap().take().unwrap_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyKeyboardInterrupt, _>("interrupted")
+            }))
+        }
+        Err(error) => return Err(error.into()),
+    };
+
+    Ok(vec_points_to_numpy(py, &result)?.into_py(py))
+}
+
+/// Calculates the concave hull of a dataset in 2D step by step, for live plots of the march.
+///
+/// The hull is computed on a background thread, which waits while the consumer falls more
+/// than a few snapshots behind and stops once the iterator is dropped. The progress is the
+/// fraction of a full turn the partial hull has swept around the centroid of the dataset;
+/// when an attempt with one `k` fails and `iterate` is set, it starts over from 0 with the
+/// next `k`.
+///
+/// # Arguments
+///
+/// * `dataset`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+/// * `every`: usize - Yield a snapshot every this many steps of the march.
+///
+/// # Returns
+///
+/// * `PyResult<PyHullIter>` - An iterator of `(progress_fraction, partial_hull)` tuples, each
+///   hull an (n, 3) array of x, y and row, ending with `(1.0, hull)` for the finished hull
+///   in the orientation set with `set_defaults`, or raising as `concave_hull_2d` does if
+///   there is none.
+#[pyfunction]
+#[pyo3(signature = (dataset, k = None, iterate = None, every = 1))]
+pub fn concave_hull_iter(
+    dataset: PyReadonlyArray2<f64>,
+    k: Option<usize>,
+    iterate: Option<bool>,
+    every: usize,
+) -> PyResult<PyHullIter> {
+    let mut points = numpy_to_vec_points(dataset)?;
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let every = every.max(1);
+    let n = points.len().max(1) as f64;
+    let centre = (
+        points.iter().map(|p| p.x).sum::<f64>() / n,
+        points.iter().map(|p| p.y).sum::<f64>() / n,
+    );
+
+    let (sender, receiver) = std::sync::mpsc::sync_channel(16);
+    let snapshots = sender.clone();
+    let observe = Box::new(move |hull: &[Point]| {
+        !hull.len().is_multiple_of(every)
+            || snapshots
+                .send(Ok((swept_fraction(hull, centre), hull.to_vec())))
+                .is_ok()
+    });
+    std::thread::spawn(move || {
+        let result = crate::concave_hull_observed(&mut points, k, iterate, observe)
+            .map(|hull| (1.0, finish(hull, &defaults)));
+        // the iterator may be gone already
+        let _ = sender.send(result);
+    });
+    Ok(PyHullIter {
+        receiver: Mutex::new(receiver),
+    })
+}
+
+/// Fraction of a full turn, at most 1, that the walk along `hull` sweeps around `centre`.
+fn swept_fraction(hull: &[Point], centre: (f64, f64)) -> f64 {
+    let angle = |p: &Point| (p.y - centre.1).atan2(p.x - centre.0);
+    let swept: f64 = hull
+        .windows(2)
+        .map(|pair| {
+            let turn = angle(&pair[1]) - angle(&pair[0]);
+            (turn + std::f64::consts::PI).rem_euclid(2.0 * std::f64::consts::PI)
+                - std::f64::consts::PI
+        })
+        .sum();
+    (swept.abs() / (2.0 * std::f64::consts::PI)).min(1.0)
+}
+
+/// Calculates the concave hulls of many independent point sets, e.g. one per vehicle and day.
+///
+/// With the `parallel` feature the datasets are shared out among the threads set with
+/// `set_defaults`; the hulls are the same either way.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `datasets`: Vec<PyReadonlyArray2<f64>> - The point sets, each a 2D NumPy array with 2
+///   (x, y) columns.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hulls; the default from
+///   `set_defaults` if omitted.
+///
+/// # Returns
+///
+/// * `PyResult<Vec<PyObject>>` - The hull of each dataset, in their order, as an (n, 3) array
+///   of x, y and row, or `None` for a dataset without one.
+#[pyfunction]
+#[pyo3(signature = (datasets, k = None, iterate = None))]
+pub fn concave_hull_batch(
+    py: Python<'_>,
+    datasets: Vec<PyReadonlyArray2<f64>>,
+    k: Option<usize>,
+    iterate: Option<bool>,
+) -> PyResult<Vec<PyObject>> {
+    let datasets = datasets
+        .into_iter()
+        .map(numpy_to_vec_points)
+        .collect::<PyResult<Vec<_>>>()?;
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    #[cfg(feature = "parallel")]
+    let hulls = py.allow_threads(|| {
+        crate::concave_hull_batch_with_threads(&datasets, k, iterate, defaults.threads)
+    });
+    #[cfg(not(feature = "parallel"))]
+    let hulls = py.allow_threads(|| crate::concave_hull_batch(&datasets, k, iterate));
+    hulls
+        .into_iter()
+        .map(|hull| match hull {
+            Ok(hull) => Ok(vec_points_to_numpy(py, &finish(hull, &defaults))?.into_py(py)),
+            Err(_) => Ok(py.None()),
+        })
+        .collect()
+}
+
+/// Calculates the concave hull of the densest points of a dataset, its "core area".
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: PyReadonlyArray2<f64> - Dataset represented as a 2D NumPy array.
+/// * `fraction`: f64 - The share (0 to 1) of the points to keep, densest first, e.g. 0.5 for
+///   the 50% core.
+/// * `density_k`: usize - The neighbour whose distance estimates the density around a point.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The hull as an (n, 3) array of x, y and row.
+#[pyfunction]
+#[pyo3(signature = (dataset, fraction, density_k = 10, k = None, iterate = None))]
+pub fn core_area_hull<'py>(
+    py: Python<'py>,
+    dataset: PyReadonlyArray2<f64>,
+    fraction: f64,
+    density_k: usize,
+    k: Option<usize>,
+    iterate: Option<bool>,
+) -> PyResult<&'py PyArray2<f64>> {
+    let points = numpy_to_vec_points(dataset)?;
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let hull =
+        py.allow_threads(|| crate::concave_hull_core(&points, fraction, density_k, k, iterate))?;
+    vec_points_to_numpy(py, &finish(hull, &defaults))
+}
 
-    // Create a new 2D NumPy array
-    let array = unsafe { PyArray2::<f64>::new(py, [result.len(), 3], false) };
+/// Traces the boundary of a point cloud by rolling a disk around it (2D ball pivoting).
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `radius`: f64 - Radius of the rolling disk.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The closed ring of touched points as an (n, 3) array of x,
+///   y and row; empty if the lowest point has no other point within twice the radius.
+#[pyfunction]
+pub fn ball_pivoting<'py>(
+    py: Python<'py>,
+    dataset: PyReadonlyArray2<f64>,
+    radius: f64,
+) -> PyResult<&'py PyArray2<f64>> {
+    let points = numpy_to_vec_points(dataset)?;
+    let hull = py.allow_threads(|| crate::pivoting::ball_pivoting(&points, radius));
+    vec_points_to_numpy(py, &finish(hull, &defaults()))
+}
+
+/// Calculates an approximate concave hull of a massive dataset by hulling tiles and merging
+/// their hulls.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `tiles`: usize - Number of tiles along each side of the bounding box.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The merged hull as an (n, 3) array of x, y and row.
+#[pyfunction]
+#[pyo3(signature = (dataset, tiles = 8, k = None, iterate = None))]
+pub fn concave_hull_tiled<'py>(
+    py: Python<'py>,
+    dataset: PyReadonlyArray2<f64>,
+    tiles: usize,
+    k: Option<usize>,
+    iterate: Option<bool>,
+) -> PyResult<&'py PyArray2<f64>> {
+    let points = numpy_to_vec_points(dataset)?;
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let hull = py.allow_threads(|| crate::tiles::concave_hull_tiled(&points, tiles, k, iterate))?;
+    vec_points_to_numpy(py, &finish(hull, &defaults))
+}
+
+/// Calculates the concave and the convex hull of a point cloud in one call.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+///
+/// # Returns
+///
+/// * `PyResult<PyObject>` - A tuple of the concave hull, as `concave_hull_2d` returns it, and
+///   the convex hull, each an (n, 3) array of x, y and row.
+#[pyfunction]
+#[pyo3(signature = (dataset, k = None, iterate = None))]
+pub fn hulls(
+    py: Python<'_>,
+    dataset: PyReadonlyArray2<f64>,
+    k: Option<usize>,
+    iterate: Option<bool>,
+) -> PyResult<PyObject> {
+    let points = numpy_to_vec_points(dataset)?;
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let (concave, convex) = py.allow_threads(|| crate::hulls(&points, k, iterate))?;
+    let concave = vec_points_to_numpy(py, &finish(concave, &defaults))?;
+    let convex = vec_points_to_numpy(py, &finish(convex, &defaults))?;
+    Ok((concave, convex).into_py(py))
+}
+
+/// Calculates the concave hull of longitude/latitude points that may straddle the
+/// antimeridian.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (longitude, latitude) columns
+///   in degrees.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+/// * `split`: bool - Split the hull at the antimeridian into rings within [-180, 180].
+///
+/// # Returns
+///
+/// * `PyResult<PyObject>` - The hull as an (n, 3) array of longitude, latitude and row, with
+///   longitudes past 180 east of the antimeridian; with `split`, a list of one or two such
+///   arrays, the second east of the antimeridian, whose inserted vertices have row 0.
+#[pyfunction]
+#[pyo3(signature = (dataset, k = None, iterate = None, split = false))]
+pub fn concave_hull_antimeridian(
+    py: Python<'_>,
+    dataset: PyReadonlyArray2<f64>,
+    k: Option<usize>,
+    iterate: Option<bool>,
+    split: bool,
+) -> PyResult<PyObject> {
+    let points = numpy_to_vec_points(dataset)?;
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let hull =
+        py.allow_threads(|| crate::antimeridian::concave_hull_antimeridian(&points, k, iterate))?;
+    let hull = finish(hull, &defaults);
+    if !split {
+        return Ok(vec_points_to_numpy(py, &hull)?.into_py(py));
+    }
+    let parts = crate::antimeridian::split_at_antimeridian(&hull)
+        .iter()
+        .map(|part| vec_points_to_numpy(py, part))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(parts.into_py(py))
+}
+
+/// Calculates the concave hull of points in a periodic (wrap-around) domain.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `x_period`: Option<f64> - Period along x, e.g. 360 for longitudes; x doesn't wrap if
+///   omitted.
+/// * `y_period`: Option<f64> - Period along y; y doesn't wrap if omitted.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The hull as an (n, 3) array of x, y and row, in one
+///   contiguous frame: vertices across the domain boundary are shifted by whole periods.
+#[pyfunction]
+#[pyo3(signature = (dataset, x_period = None, y_period = None, k = None, iterate = None))]
+pub fn concave_hull_periodic<'py>(
+    py: Python<'py>,
+    dataset: PyReadonlyArray2<f64>,
+    x_period: Option<f64>,
+    y_period: Option<f64>,
+    k: Option<usize>,
+    iterate: Option<bool>,
+) -> PyResult<&'py PyArray2<f64>> {
+    let points = numpy_to_vec_points(dataset)?;
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let domain = crate::periodic::PeriodicDomain { x_period, y_period };
+    let hull =
+        py.allow_threads(|| crate::periodic::concave_hull_periodic(&points, &domain, k, iterate))?;
+    vec_points_to_numpy(py, &finish(hull, &defaults))
+}
+
+/// Calculates the concave hull of polylines, e.g. a road network.
+///
+/// The polylines are densified so that the hull follows their segments, not just their
+/// vertices.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `polylines`: Vec<PyReadonlyArray2<f64>> - The polylines, each an (n, 2) array of vertices.
+///   Vertices at the same coordinates, such as junctions, are merged.
+/// * `spacing`: f64 - The largest gap between samples along a polyline.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The hull as an (n, 3) array of x, y and id. Vertex ids count
+///   the rows of all polylines in order; samples between vertices get ids after the last row.
+#[pyfunction]
+#[pyo3(signature = (polylines, spacing, k = None, iterate = None))]
+pub fn concave_hull_polylines<'py>(
+    py: Python<'py>,
+    polylines: Vec<PyReadonlyArray2<f64>>,
+    spacing: f64,
+    k: Option<usize>,
+    iterate: Option<bool>,
+) -> PyResult<&'py PyArray2<f64>> {
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let mut first_row = 0;
+    let mut ids = HashMap::new();
+    let mut lines = Vec::with_capacity(polylines.len());
+    for polyline in polylines {
+        let mut line = numpy_to_vec_points(polyline)?;
+        for p in line.iter_mut() {
+            p.id = *ids
+                .entry((p.x.to_bits(), p.y.to_bits()))
+                .or_insert(first_row + p.id);
+        }
+        first_row += line.len() as u64;
+        lines.push(line);
+    }
 
-    // Obtain a mutable slice of the entire array
-    let array_slice = unsafe { array.as_slice_mut().unwrap() };
+    let hull =
+        py.allow_threads(|| crate::polyline::concave_hull_polylines(&lines, spacing, k, iterate))?;
+    vec_points_to_numpy(py, &finish(hull, &defaults))
+}
 
-    // Fill the array with data from the Vec<Point>
-    for (i, point) in result.iter().enumerate() {
-        let start_idx = i * 3;
-        array_slice[start_idx] = point.x;
-        array_slice[start_idx + 1] = point.y;
-        array_slice[start_idx + 2] = point.id as f64; // Assuming you want to store the ID as a float
+/// Calculates the hulls of a trajectory over sliding time windows, e.g. home ranges.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `points`: PyReadonlyArray2<f64> - The fixes as an (n, 2) array.
+/// * `times`: PyReadonlyArray1<f64> - The timestamp of each fix.
+/// * `window`: f64 - Length of each window, in the unit of `times`.
+/// * `stride`: f64 - Time between the starts of consecutive windows.
+/// * `k`: Option<usize> - The number of neighbours; the default from `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hulls; the default from
+///   `set_defaults` if omitted.
+/// * `geographic`: bool - Treat columns as longitude/latitude degrees.
+///
+/// # Returns
+///
+/// * `PyResult<Vec<Py<PyDict>>>` - A dict per window, in time order, with the keys `start`,
+///   `end`, `points` (the number of fixes) and `hull`, an (n, 3) array of x, y and row, or
+///   `None` if the window has no hull, e.g. too few fixes.
+#[pyfunction]
+#[pyo3(signature = (points, times, window, stride, k = None, iterate = None, geographic = false))]
+#[allow(clippy::too_many_arguments)]
+pub fn window_hulls(
+    py: Python<'_>,
+    points: PyReadonlyArray2<f64>,
+    times: PyReadonlyArray1<f64>,
+    window: f64,
+    stride: f64,
+    k: Option<usize>,
+    iterate: Option<bool>,
+    geographic: bool,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let points = numpy_to_vec_points(points)?;
+    let times = times.as_array().to_vec();
+    if times.len() != points.len() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "points and times must have the same length",
+        ));
     }
+    if times.iter().any(|t| !t.is_finite()) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "times must be finite",
+        ));
+    }
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let config = crate::pipeline::HullConfig {
+        k,
+        iterate,
+        metric: if geographic {
+            Metric::Haversine
+        } else {
+            Metric::Euclidean
+        },
+    };
+
+    let windows = py.allow_threads(|| {
+        crate::trajectory::window_hulls(&points, &times, window, stride, &config)
+    });
+    windows
+        .into_iter()
+        .map(|w| {
+            let dict = PyDict::new(py);
+            dict.set_item("start", w.start)?;
+            dict.set_item("end", w.end)?;
+            dict.set_item("points", w.points)?;
+            match w.hull {
+                Ok(hull) => {
+                    dict.set_item("hull", vec_points_to_numpy(py, &finish(hull, &defaults))?)?
+                }
+                Err(_) => dict.set_item("hull", py.None())?,
+            }
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Summarises a 2D dataset to guide the choice of `k`.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: &PyArray2<f64> - Dataset represented as a 2D NumPy array.
+///
+/// # Returns
+///
+/// * `PyResult<Py<PyDict>>` - A dict with the keys `count`, `bbox` (min_x, min_y, max_x, max_y),
+///   `mean_nn_distance`, `density`, `duplicates`, `clustering` and `recommended_k`.
+#[pyfunction]
+pub fn dataset_stats(py: Python<'_>, dataset: &PyArray2<f64>) -> PyResult<Py<PyDict>> {
+    let stats = crate::stats::dataset_stats(&numpy_to_vec_points(dataset.readonly())?);
+    let bbox = stats.bbox;
+
+    let dict = PyDict::new(py);
+    dict.set_item("count", stats.count)?;
+    dict.set_item("bbox", (bbox.min_x, bbox.min_y, bbox.max_x, bbox.max_y))?;
+    dict.set_item("mean_nn_distance", stats.mean_nn_distance)?;
+    dict.set_item("density", stats.density)?;
+    dict.set_item("duplicates", stats.duplicates)?;
+    dict.set_item("clustering", stats.clustering)?;
+    dict.set_item("recommended_k", stats.recommended_k)?;
+    Ok(dict.into())
+}
+
+/// Histogram of the lengths of the Delaunay triangulation edges.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: &PyArray2<f64> - Dataset represented as a 2D NumPy array.
+/// * `bins`: usize - Number of equal-width bins.
+///
+/// # Returns
+///
+/// * `PyResult<PyObject>` - A tuple of the bin counts and the bin edges, like `numpy.histogram`.
+#[pyfunction]
+#[pyo3(signature = (dataset, bins = 50))]
+pub fn edge_length_histogram(
+    py: Python<'_>,
+    dataset: &PyArray2<f64>,
+    bins: usize,
+) -> PyResult<PyObject> {
+    let points = numpy_to_vec_points(dataset.readonly())?;
+    let histogram = crate::stats::edge_length_histogram(&points, bins);
+    let counts = PyArray1::from_vec(py, histogram.counts);
+    let edges = PyArray1::from_vec(py, histogram.edges);
+    Ok((counts, edges).into_py(py))
+}
+
+/// Histogram of the distances from every point to its k-th nearest neighbour.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: &PyArray2<f64> - Dataset represented as a 2D NumPy array.
+/// * `k`: usize - Which neighbour to measure the distance to.
+/// * `bins`: usize - Number of equal-width bins.
+///
+/// # Returns
+///
+/// * `PyResult<PyObject>` - A tuple of the bin counts and the bin edges, like `numpy.histogram`.
+#[pyfunction]
+#[pyo3(signature = (dataset, k, bins = 50))]
+pub fn knn_distance_histogram(
+    py: Python<'_>,
+    dataset: &PyArray2<f64>,
+    k: usize,
+    bins: usize,
+) -> PyResult<PyObject> {
+    let points = numpy_to_vec_points(dataset.readonly())?;
+    let histogram = crate::stats::knn_distance_histogram(&points, k, bins);
+    let counts = PyArray1::from_vec(py, histogram.counts);
+    let edges = PyArray1::from_vec(py, histogram.edges);
+    Ok((counts, edges).into_py(py))
+}
+
+/// Splits a hull into triangles by ear clipping, e.g. for rendering or area-weighted sampling.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `hull`: PyReadonlyArray2<f64> - Hull with 2 (x, y) or 3 (x, y, id) columns.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray3<f64>>` - The counter-clockwise triangles as an (m, 3, 3) array of
+///   corners, each x, y and id.
+#[pyfunction]
+pub fn triangulate<'py>(
+    py: Python<'py>,
+    hull: PyReadonlyArray2<f64>,
+) -> PyResult<&'py PyArray3<f64>> {
+    let triangles = crate::triangulation::triangulate(&numpy_to_hull_points(hull)?);
+    let flat: Vec<f64> = triangles
+        .iter()
+        .flatten()
+        .flat_map(|p| [p.x, p.y, p.id as f64])
+        .collect();
+    PyArray1::from_vec(py, flat).reshape([triangles.len(), 3, 3])
+}
+
+/// Suggests alpha, maximum edge length and chi-lambda values at the natural gaps in the
+/// Delaunay edge lengths.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: &PyArray2<f64> - Dataset represented as a 2D NumPy array.
+/// * `count`: usize - The most suggestions to return.
+///
+/// # Returns
+///
+/// * `PyResult<Vec<Py<PyDict>>>` - One dict per suggestion, the most natural gap first, with
+///   keys `max_edge_length`, `alpha`, `chi_lambda`, `gap` and `kept`.
+#[pyfunction]
+#[pyo3(signature = (dataset, count = 3))]
+pub fn suggest_thresholds(
+    py: Python<'_>,
+    dataset: &PyArray2<f64>,
+    count: usize,
+) -> PyResult<Vec<Py<PyDict>>> {
+    let points = numpy_to_vec_points(dataset.readonly())?;
+    crate::stats::suggest_thresholds(&points, count)
+        .into_iter()
+        .map(|suggestion| {
+            let dict = PyDict::new(py);
+            dict.set_item("max_edge_length", suggestion.max_edge_length)?;
+            dict.set_item("alpha", suggestion.alpha)?;
+            dict.set_item("chi_lambda", suggestion.chi_lambda)?;
+            dict.set_item("gap", suggestion.gap)?;
+            dict.set_item("kept", suggestion.kept)?;
+            Ok(dict.into())
+        })
+        .collect()
+}
+
+/// Converts a hull array into an indexed edge list.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `hull`: PyReadonlyArray2<f64> - Hull with 2 (x, y) or 3 (x, y, id) columns.
+///
+/// # Returns
+///
+/// * `PyResult<PyObject>` - A tuple of the (n, 3) vertex array, each vertex stored once, and
+///   the (m, 2) array of vertex index pairs forming the edges in ring order.
+#[pyfunction]
+pub fn hull_edge_list(py: Python<'_>, hull: PyReadonlyArray2<f64>) -> PyResult<PyObject> {
+    let list = crate::export::to_edge_list(&numpy_to_hull_points(hull)?);
+    let vertices = vec_points_to_numpy(py, &list.vertices)?;
+    let flat: Vec<usize> = list.edges.iter().flat_map(|&(a, b)| [a, b]).collect();
+    let edges = PyArray1::from_vec(py, flat).reshape([list.edges.len(), 2])?;
+    Ok((vertices, edges).into_py(py))
+}
+
+/// Approximates the hull of a large point cloud by tracing a grid of occupied cells.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `points`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `resolution`: f64 - Side length of the grid cells.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The closed outline as an (n, 3) array of x, y and id, the
+///   id being the row of a point in the cell each corner bounds.
+#[pyfunction]
+pub fn grid_hull<'py>(
+    py: Python<'py>,
+    points: PyReadonlyArray2<f64>,
+    resolution: f64,
+) -> PyResult<&'py PyArray2<f64>> {
+    let points = numpy_to_vec_points(points)?;
+    let hull = py.allow_threads(|| crate::grid::grid_hull(&points, resolution));
+    vec_points_to_numpy(py, &hull)
+}
+
+/// Calculates the concave hull of the grid cells holding at least `min_count` points.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `points`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `resolution`: f64 - Side length of the grid cells.
+/// * `min_count`: usize - The fewest points a cell needs to be hulled; sparser cells are
+///   dropped as noise.
+/// * `k`: Option<usize> - The number of neighbours among the cells; the default from
+///   `set_defaults` if omitted.
+/// * `iterate`: Option<bool> - Whether to iteratively refine the hull; the default from
+///   `set_defaults` if omitted.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The hull of the cells' mean positions as an (n, 3) array of
+///   x, y and the row of a point in each cell, or raising as `concave_hull_2d` does if no
+///   hull was found.
+#[pyfunction]
+#[pyo3(signature = (points, resolution, min_count = 1, k = None, iterate = None))]
+pub fn concave_hull_aggregated<'py>(
+    py: Python<'py>,
+    points: PyReadonlyArray2<f64>,
+    resolution: f64,
+    min_count: usize,
+    k: Option<usize>,
+    iterate: Option<bool>,
+) -> PyResult<&'py PyArray2<f64>> {
+    let points = numpy_to_vec_points(points)?;
+    let defaults = defaults();
+    let (k, iterate) = defaults.resolve(k, iterate);
+    let hull = py.allow_threads(|| {
+        crate::grid::concave_hull_aggregated(&points, resolution, min_count, k, iterate)
+    })?;
+    vec_points_to_numpy(py, &finish(hull, &defaults))
+}
+
+/// Computes the density contour enclosing a given probability mass of a kernel density
+/// estimate, e.g. the 95% utilization distribution.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `points`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `mass`: f64 - The probability mass (0 to 1) the contour encloses.
+/// * `bandwidth`: Option<f64> - Standard deviation of the Gaussian kernel, by Silverman's
+///   rule if not given.
+/// * `resolution`: usize - Grid cells along the longer side.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The closed contour as an (n, 3) array of x, y and id, the
+///   ids being 0.
+#[pyfunction]
+#[pyo3(signature = (points, mass = 0.95, bandwidth = None, resolution = 256))]
+pub fn kde_contour<'py>(
+    py: Python<'py>,
+    points: PyReadonlyArray2<f64>,
+    mass: f64,
+    bandwidth: Option<f64>,
+    resolution: usize,
+) -> PyResult<&'py PyArray2<f64>> {
+    let points = numpy_to_vec_points(points)?;
+    let contour = py.allow_threads(|| {
+        let bandwidth = bandwidth.unwrap_or_else(|| crate::kde::silverman_bandwidth(&points));
+        crate::kde::kde_contour(&points, mass, bandwidth, resolution)
+    });
+    vec_points_to_numpy(py, &contour)
+}
+
+/// Reduces the vertex count of a hull within a distance tolerance (Douglas-Peucker).
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `hull`: PyReadonlyArray2<f64> - Hull with 2 (x, y) or 3 (x, y, id) columns.
+/// * `tolerance`: f64 - The largest distance a removed vertex may have from the result.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The kept vertices as an (n, 3) array of x, y and id.
+#[pyfunction]
+pub fn simplify<'py>(
+    py: Python<'py>,
+    hull: PyReadonlyArray2<f64>,
+    tolerance: f64,
+) -> PyResult<&'py PyArray2<f64>> {
+    let simplified = crate::simplify::simplify(&numpy_to_hull_points(hull)?, tolerance);
+    vec_points_to_numpy(py, &simplified)
+}
+
+/// Smooths a hull by Chaikin corner cutting.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `hull`: PyReadonlyArray2<f64> - Hull with 2 (x, y) or 3 (x, y, id) columns.
+/// * `iterations`: usize - Number of corner cutting rounds.
+/// * `points`: Option<PyReadonlyArray2<f64>> - If given, the (n, 2) points that must stay on
+///   or inside the smoothed hull; corners whose cut would exclude one are left uncut.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The smoothed ring as an (n, 3) array of x, y and id.
+#[pyfunction]
+#[pyo3(signature = (hull, iterations, points = None))]
+pub fn smooth<'py>(
+    py: Python<'py>,
+    hull: PyReadonlyArray2<f64>,
+    iterations: usize,
+    points: Option<PyReadonlyArray2<f64>>,
+) -> PyResult<&'py PyArray2<f64>> {
+    let hull = numpy_to_hull_points(hull)?;
+    let smoothed = match points {
+        Some(points) => {
+            crate::smooth::smooth_containing(&hull, iterations, &numpy_to_vec_points(points)?)
+        }
+        None => crate::smooth::smooth(&hull, iterations),
+    };
+    vec_points_to_numpy(py, &smoothed)
+}
+
+/// Regularises a hull into a rectilinear outline, such as a building footprint.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `hull`: PyReadonlyArray2<f64> - Hull with 2 (x, y) or 3 (x, y, id) columns.
+/// * `tolerance`: f64 - The largest angle in radians between an edge and the dominant
+///   direction, or its perpendicular, that it is snapped to.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The regularised ring as an (n, 3) array of x, y and id.
+#[pyfunction]
+pub fn orthogonalize<'py>(
+    py: Python<'py>,
+    hull: PyReadonlyArray2<f64>,
+    tolerance: f64,
+) -> PyResult<&'py PyArray2<f64>> {
+    let regularised = crate::regularize::orthogonalize(&numpy_to_hull_points(hull)?, tolerance);
+    vec_points_to_numpy(py, &regularised)
+}
+
+/// Keeps the points within a distance of the boundary of a hull, inside or outside.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `dataset`: PyReadonlyArray2<f64> - A 2D NumPy array with 2 (x, y) columns.
+/// * `hull`: PyReadonlyArray2<f64> - Hull with 2 (x, y) or 3 (x, y, id) columns.
+/// * `width`: f64 - The largest distance from the boundary of a kept point.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The points in the band as an (n, 3) array of x, y and row.
+#[pyfunction]
+pub fn boundary_band<'py>(
+    py: Python<'py>,
+    dataset: PyReadonlyArray2<f64>,
+    hull: PyReadonlyArray2<f64>,
+    width: f64,
+) -> PyResult<&'py PyArray2<f64>> {
+    let points = numpy_to_vec_points(dataset)?;
+    let hull = numpy_to_hull_points(hull)?;
+    let band = py.allow_threads(|| crate::prepared::boundary_band(&points, &hull, width));
+    vec_points_to_numpy(py, &band)
+}
+
+/// Offsets a hull outwards (positive distance) or inwards (negative distance).
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `hull`: PyReadonlyArray2<f64> - Hull with 2 (x, y) or 3 (x, y, id) columns.
+/// * `distance`: f64 - The offset, with round corners where the offset moves away from the hull.
+///
+/// # Returns
+///
+/// * `PyResult<&PyArray2<f64>>` - The largest offset ring as an (n, 3) array of x, y and id.
+#[pyfunction]
+pub fn buffer<'py>(
+    py: Python<'py>,
+    hull: PyReadonlyArray2<f64>,
+    distance: f64,
+) -> PyResult<&'py PyArray2<f64>> {
+    let buffered = crate::buffer::buffer(&numpy_to_hull_points(hull)?, distance);
+    vec_points_to_numpy(py, &buffered)
+}
+
+/// Offsets a hull like `buffer`, returning every polygon of the result.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `hull`: PyReadonlyArray2<f64> - Hull with 2 (x, y) or 3 (x, y, id) columns.
+/// * `distance`: f64 - The offset; positive dilates, negative erodes.
+///
+/// # Returns
+///
+/// * `PyResult<PyObject>` - The polygons, see `multi_hull_to_python`.
+#[pyfunction]
+pub fn buffer_rings(
+    py: Python<'_>,
+    hull: PyReadonlyArray2<f64>,
+    distance: f64,
+) -> PyResult<PyObject> {
+    let rings = crate::buffer::buffer_rings(&numpy_to_hull_points(hull)?, distance);
+    multi_hull_to_python(py, &MultiHull::from_rings(rings))
+}
+
+/// Samples a point cloud whose true boundary is a given polygon.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `polygon`: PyReadonlyArray2<f64> - Ground truth polygon with 2 (x, y) or 3 (x, y, id) columns.
+/// * `n`: usize - Number of points to generate; half lie on the boundary, half inside.
+/// * `noise`: f64 - Standard deviation of the displacement of the boundary points.
+/// * `seed`: u64 - Seed of the generator.
+///
+/// # Returns
+///
+/// * `PyResult<Py<PyArray2<f64>>>` - An (n, 2) NumPy array of the sampled points.
+#[pyfunction]
+#[pyo3(signature = (polygon, n, noise = 0.0, seed = 0))]
+pub fn sample_polygon_boundary_and_interior(
+    py: Python<'_>,
+    polygon: PyReadonlyArray2<f64>,
+    n: usize,
+    noise: f64,
+    seed: u64,
+) -> PyResult<Py<PyArray2<f64>>> {
+    let polygon = numpy_to_hull_points(polygon)?;
+    let points = crate::synthetic::sample_polygon_boundary_and_interior(&polygon, n, noise, seed);
+    let flat: Vec<f64> = points.iter().flat_map(|p| [p.x, p.y]).collect();
+    Ok(PyArray1::from_vec(py, flat)
+        .reshape([points.len(), 2])?
+        .into_py(py))
+}
+
+/// Runs a hull algorithm on a dataset and scores its output against the ground truth.
+///
+/// # Arguments
+///
+/// * `py`: Python<'_> - Python interpreter context.
+/// * `algorithm`: &PyAny - Callable taking the (n, 2) dataset and returning a hull array with
+///   2 (x, y) or 3 (x, y, id) columns, e.g. `lambda p: concave_hull_2d(p, 3, True)`.
+/// * `ground_truth`: PyReadonlyArray2<f64> - True boundary with 2 or 3 columns.
+/// * `points`: &PyArray2<f64> - Dataset represented as a 2D NumPy array.
+///
+/// # Returns
+///
+/// * `PyResult<Py<PyDict>>` - A dict with the keys `boundary_rmse`, `iou`, `hull_vertices` and
+///   `truth_vertices`.
+#[pyfunction]
+pub fn evaluate(
+    py: Python<'_>,
+    algorithm: &PyAny,
+    ground_truth: PyReadonlyArray2<f64>,
+    points: &PyArray2<f64>,
+) -> PyResult<Py<PyDict>> {
+    let hull: PyReadonlyArray2<f64> = algorithm.call1((points,))?.extract()?;
+    let evaluation = crate::evaluate::evaluate_hull(
+        &numpy_to_hull_points(hull)?,
+        &numpy_to_hull_points(ground_truth)?,
+    );
 
-    Ok(array.into_py(py))
+    let dict = PyDict::new(py);
+    dict.set_item("boundary_rmse", evaluation.boundary_rmse)?;
+    dict.set_item("iou", evaluation.iou)?;
+    dict.set_item("hull_vertices", evaluation.hull_vertices)?;
+    dict.set_item("truth_vertices", evaluation.truth_vertices)?;
+    Ok(dict.into())
 }
 
 /// Initializes the Python module for the concave hull algorithm.
 ///
 /// This function is called when the Python interpreter loads the module.
-/// It registers the `Point` class and the `concave_hull_2d` function to the Python module.
+/// It registers the `Point` and `PreparedHull` classes and the hull and dataset diagnostic
+/// functions to the Python module.
 ///
 /// # Arguments
 ///
@@ -103,8 +1485,48 @@ pub fn concave_hull_2d(
 ///
 /// * `PyResult<()>` - Ok on success, or a Python error on failure.
 #[pymodule]
-pub fn concave_hull(_py: Python, m: &PyModule) -> PyResult<()> {
+pub fn concave_hull(py: Python, m: &PyModule) -> PyResult<()> {
+    let info = crate::build_info();
+    let build_info = PyDict::new(py);
+    build_info.set_item("version", info.version)?;
+    build_info.set_item("features", info.features)?;
+    build_info.set_item("index", info.index)?;
+    build_info.set_item("index_precisions", info.index_precisions)?;
+    build_info.set_item("optimized", info.optimized)?;
+    m.add("__build_info__", build_info)?;
     m.add_class::<Point>()?;
+    m.add_class::<PyDataset>()?;
+    m.add_class::<PyHullIter>()?;
+    m.add_class::<PyPreparedHull>()?;
+    m.add_function(wrap_pyfunction!(ball_pivoting, m)?)?;
+    m.add_function(wrap_pyfunction!(boundary_band, m)?)?;
+    m.add_function(wrap_pyfunction!(buffer, m)?)?;
+    m.add_function(wrap_pyfunction!(buffer_rings, m)?)?;
     m.add_function(wrap_pyfunction!(concave_hull_2d, m)?)?;
+    m.add_function(wrap_pyfunction!(concave_hull_aggregated, m)?)?;
+    m.add_function(wrap_pyfunction!(concave_hull_antimeridian, m)?)?;
+    m.add_function(wrap_pyfunction!(concave_hull_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(concave_hull_iter, m)?)?;
+    m.add_function(wrap_pyfunction!(concave_hull_periodic, m)?)?;
+    m.add_function(wrap_pyfunction!(concave_hull_polylines, m)?)?;
+    m.add_function(wrap_pyfunction!(concave_hull_tiled, m)?)?;
+    m.add_function(wrap_pyfunction!(core_area_hull, m)?)?;
+    m.add_function(wrap_pyfunction!(dataset_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(edge_length_histogram, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate, m)?)?;
+    m.add_function(wrap_pyfunction!(get_defaults, m)?)?;
+    m.add_function(wrap_pyfunction!(grid_hull, m)?)?;
+    m.add_function(wrap_pyfunction!(hulls, m)?)?;
+    m.add_function(wrap_pyfunction!(kde_contour, m)?)?;
+    m.add_function(wrap_pyfunction!(knn_distance_histogram, m)?)?;
+    m.add_function(wrap_pyfunction!(hull_edge_list, m)?)?;
+    m.add_function(wrap_pyfunction!(orthogonalize, m)?)?;
+    m.add_function(wrap_pyfunction!(sample_polygon_boundary_and_interior, m)?)?;
+    m.add_function(wrap_pyfunction!(set_defaults, m)?)?;
+    m.add_function(wrap_pyfunction!(simplify, m)?)?;
+    m.add_function(wrap_pyfunction!(smooth, m)?)?;
+    m.add_function(wrap_pyfunction!(suggest_thresholds, m)?)?;
+    m.add_function(wrap_pyfunction!(triangulate, m)?)?;
+    m.add_function(wrap_pyfunction!(window_hulls, m)?)?;
     Ok(())
 }
@@ -0,0 +1,286 @@
+/// Measurements Along Hull Boundaries
+use crate::point::Point;
+
+/// The two ways between two places on a closed boundary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct BoundaryDistance {
+    /// length from the first place to the second in the vertex order of the hull
+    pub forward: f64,
+    /// length from the first place to the second against the vertex order
+    pub backward: f64,
+}
+
+impl BoundaryDistance {
+    /// the length of the shorter way
+    pub fn shorter(&self) -> f64 {
+        self.forward.min(self.backward)
+    }
+}
+
+/// The place on the boundary of `ring` nearest to `p`, as its arclength from the first
+/// vertex.
+fn arclength(ring: &[Point], p: &Point) -> f64 {
+    let n = ring.len();
+    let (mut start, mut best) = (0.0, (0.0, f64::INFINITY));
+    for i in 0..n {
+        let (a, b) = (&ring[i], &ring[(i + 1) % n]);
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let length = dx.hypot(dy);
+        let t = if length > 0.0 {
+            (((p.x - a.x) * dx + (p.y - a.y) * dy) / (length * length)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let offset = (p.x - (a.x + t * dx)).hypot(p.y - (a.y + t * dy));
+        if offset < best.1 {
+            best = (start + t * length, offset);
+        }
+        start += length;
+    }
+    best.0
+}
+
+/// Measures the boundary of a hull between two places on it, both ways round.
+///
+/// Where the hull stands in for an outline such as a shoreline, this is the length of
+/// coast between two sites. The places need not be vertices: each is taken to the nearest
+/// point of the boundary, so sites just off it measure from where they meet it.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed, in either orientation.
+/// * `a` - where to measure from.
+/// * `b` - where to measure to.
+///
+/// # Returns
+///
+/// Returns the length of boundary from `a` to `b` in the vertex order of `hull` and against
+/// it, which add up to the perimeter unless the places coincide, or `None` if the hull has
+/// fewer than two vertices.
+pub fn boundary_distance(hull: &[Point], a: &Point, b: &Point) -> Option<BoundaryDistance> {
+    let ring = crate::open_ring(hull);
+    if ring.len() < 2 {
+        return None;
+    }
+
+    let n = ring.len();
+    let perimeter: f64 = (0..n)
+        .map(|i| {
+            let (a, b) = (&ring[i], &ring[(i + 1) % n]);
+            (b.x - a.x).hypot(b.y - a.y)
+        })
+        .sum();
+    if perimeter == 0.0 {
+        return Some(BoundaryDistance {
+            forward: 0.0,
+            backward: 0.0,
+        });
+    }
+
+    let forward = (arclength(ring, b) - arclength(ring, a)).rem_euclid(perimeter);
+    Some(BoundaryDistance {
+        forward,
+        backward: (perimeter - forward) % perimeter,
+    })
+}
+
+/// How [`segment_boundary`] labels the vertices of a ring.
+///
+/// Besides [`TurnAngle`], any `FnMut(&Point) -> L` is a criterion, labelling each vertex
+/// by an attribute of its own, e.g. looked up by its id.
+pub trait Criterion {
+    /// what the sections are told apart by
+    type Label: PartialEq;
+
+    /// the label of `vertex`, where the boundary turns by `turn` radians, positive to the
+    /// left
+    fn label(&mut self, vertex: &Point, turn: f64) -> Self::Label;
+}
+
+/// Labels the vertices turning by more than this many radians either way as corners, and
+/// the rest as straight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TurnAngle(pub f64);
+
+/// The label of a vertex under [`TurnAngle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    /// the boundary runs on nearly straight
+    Straight,
+    /// the boundary turns sharply
+    Corner,
+}
+
+impl Criterion for TurnAngle {
+    type Label = Shape;
+
+    fn label(&mut self, _vertex: &Point, turn: f64) -> Shape {
+        if turn.abs() > self.0 {
+            Shape::Corner
+        } else {
+            Shape::Straight
+        }
+    }
+}
+
+impl<L: PartialEq, F: FnMut(&Point) -> L> Criterion for F {
+    type Label = L;
+
+    fn label(&mut self, vertex: &Point, _turn: f64) -> L {
+        self(vertex)
+    }
+}
+
+/// A maximal run of consecutive boundary vertices with the same label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundarySection<L> {
+    /// label of every vertex of the section
+    pub label: L,
+    /// position of the first vertex in the hull
+    pub start: usize,
+    /// the vertices in boundary order
+    pub vertices: Vec<Point>,
+}
+
+/// Splits the boundary of a hull into sections of like vertices.
+///
+/// Each vertex is labelled by `criterion`, and every maximal run of consecutive vertices
+/// with the same label becomes a section, so that e.g. a building footprint falls apart
+/// into straight walls and the corners between them, to be regularised separately.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed.
+/// * `criterion` - labels the vertices, e.g. [`TurnAngle`] or a closure over attributes.
+///
+/// # Returns
+///
+/// Returns the sections in boundary order. Runs continue round the ring, so a section may
+/// wrap past the last vertex; the first section starts where the label changes, and a
+/// boundary of a single label is one section starting at 0.
+pub fn segment_boundary<C: Criterion>(
+    hull: &[Point],
+    mut criterion: C,
+) -> Vec<BoundarySection<C::Label>> {
+    let ring = crate::open_ring(hull);
+    let n = ring.len();
+    let mut labels: Vec<C::Label> = (0..n)
+        .map(|i| {
+            let (a, b, c) = (&ring[(i + n - 1) % n], &ring[i], &ring[(i + 1) % n]);
+            let (ux, uy) = (b.x - a.x, b.y - a.y);
+            let (vx, vy) = (c.x - b.x, c.y - b.y);
+            let turn = (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+            criterion.label(b, turn)
+        })
+        .collect();
+
+    let first = (0..n)
+        .find(|&i| labels[i] != labels[(i + n - 1) % n])
+        .unwrap_or(0);
+    labels.rotate_left(first);
+    let mut sections: Vec<BoundarySection<C::Label>> = Vec::new();
+    for (step, label) in labels.into_iter().enumerate() {
+        let i = (first + step) % n;
+        match sections.last_mut() {
+            Some(section) if section.label == label => section.vertices.push(ring[i].clone()),
+            _ => sections.push(BoundarySection {
+                label,
+                start: i,
+                vertices: vec![ring[i].clone()],
+            }),
+        }
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
+
+    fn point(x: f64, y: f64) -> Point {
+        Point { x, y, id: 0 }
+    }
+
+    #[test]
+    fn test_boundary_distance() {
+        // a 4 x 2 rectangle, counterclockwise and closed
+        let hull = vec![
+            point(0.0, 0.0),
+            point(4.0, 0.0),
+            point(4.0, 2.0),
+            point(0.0, 2.0),
+            point(0.0, 0.0),
+        ];
+        let distance = boundary_distance(&hull, &point(1.0, 0.0), &point(4.0, 1.0)).unwrap();
+        assert!((distance.forward - 4.0).abs() < 1E-12);
+        assert!((distance.backward - 8.0).abs() < 1E-12);
+        assert_eq!(distance.shorter(), distance.forward);
+
+        // across the first vertex, and from just off the boundary
+        let distance = boundary_distance(&hull, &point(0.0, 1.0), &point(1.0, -0.5)).unwrap();
+        assert!((distance.forward - 2.0).abs() < 1E-12);
+        assert!((distance.backward - 10.0).abs() < 1E-12);
+
+        let same = boundary_distance(&hull, &point(2.0, 2.0), &point(2.0, 2.0)).unwrap();
+        assert!(
+            ulps_eq(same.forward, 0.0, DEFAULT_ULPS) && ulps_eq(same.backward, 0.0, DEFAULT_ULPS)
+        );
+        assert!(boundary_distance(&hull[..1], &point(0.0, 0.0), &point(1.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_segment_boundary() {
+        // a square with two points along each side, starting mid-side
+        let hull: Vec<Point> = [
+            (2.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 1.0),
+            (3.0, 2.0),
+            (3.0, 3.0),
+            (2.0, 3.0),
+            (1.0, 3.0),
+            (0.0, 3.0),
+            (0.0, 2.0),
+            (0.0, 1.0),
+            (0.0, 0.0),
+            (1.0, 0.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(id, &(x, y))| Point {
+            x,
+            y,
+            id: id as u64,
+        })
+        .collect();
+
+        let sections = segment_boundary(&hull, TurnAngle(0.5));
+        let shapes: Vec<(Shape, usize, usize)> = sections
+            .iter()
+            .map(|s| (s.label, s.start, s.vertices.len()))
+            .collect();
+        assert_eq!(
+            shapes,
+            [
+                (Shape::Corner, 1, 1),
+                (Shape::Straight, 2, 2),
+                (Shape::Corner, 4, 1),
+                (Shape::Straight, 5, 2),
+                (Shape::Corner, 7, 1),
+                (Shape::Straight, 8, 2),
+                (Shape::Corner, 10, 1),
+                (Shape::Straight, 11, 2),
+            ]
+        );
+
+        // by attribute, with a run that wraps past the last vertex
+        let sections = segment_boundary(&hull, |p: &Point| p.id >= 2 && p.id < 6);
+        assert_eq!(sections.len(), 2);
+        assert_eq!((sections[0].label, sections[0].start), (true, 2));
+        let ids: Vec<u64> = sections[1].vertices.iter().map(|p| p.id).collect();
+        assert_eq!(ids, [6, 7, 8, 9, 10, 11, 0, 1]);
+        assert_eq!(segment_boundary(&hull, |_: &Point| 0).len(), 1);
+    }
+}
@@ -0,0 +1,425 @@
+/// Hull Buffering
+use crate::point::Point;
+use crate::prepared::PreparedHull;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// angular step of the arcs approximating round corners
+const ARC_STEP: f64 = PI / 32.0;
+/// relative slack of the distance test, for rounding errors
+const SLACK: f64 = 1E-9;
+
+/// Offsets a hull outwards (positive `distance`) or inwards (negative `distance`).
+///
+/// Corners that the offset moves away from are rounded with arcs of radius `distance`, so
+/// every point of the result lies at exactly `distance` from the hull; corners the offset
+/// moves into are cut where the offset edges meet. Erosion can split a hull, and dilation
+/// can close a narrow bay into a hole; this returns the ring enclosing the largest area,
+/// see [`buffer_rings`] for all of them.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed.
+/// * `distance` - the offset; positive dilates, negative erodes.
+///
+/// # Returns
+///
+/// Returns the offset ring counter-clockwise, closed if `hull` was closed. An erosion
+/// that leaves nothing gives an empty ring.
+pub fn buffer(hull: &[Point], distance: f64) -> Vec<Point> {
+    let closed = hull.len() > crate::open_ring(hull).len();
+    let rings = buffer_rings(hull, distance);
+    match rings
+        .into_iter()
+        .max_by(|a, b| signed_area(a).abs().total_cmp(&signed_area(b).abs()))
+    {
+        Some(mut ring) => {
+            if closed {
+                ring.push(ring[0].clone());
+            }
+            ring
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Offsets a hull like [`buffer`], returning every ring of the result.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed.
+/// * `distance` - the offset; positive dilates, negative erodes.
+///
+/// # Returns
+///
+/// Returns the offset rings as open rings. Outer rings run counter-clockwise, holes
+/// clockwise.
+pub fn buffer_rings(hull: &[Point], distance: f64) -> Vec<Vec<Point>> {
+    let mut ring = without_collinear(crate::open_ring(hull));
+    if ring.is_empty() || distance == 0.0 {
+        return if ring.is_empty() {
+            Vec::new()
+        } else {
+            vec![ring]
+        };
+    }
+    if ring.len() < 3 && distance < 0.0 {
+        return Vec::new();
+    }
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+    if ring.len() == 1 {
+        let centre = &ring[0];
+        let steps = (2.0 * PI / ARC_STEP) as usize;
+        let disc = (0..steps)
+            .map(|i| {
+                let t = i as f64 * ARC_STEP;
+                offset(centre, t, distance)
+            })
+            .collect();
+        return vec![disc];
+    }
+
+    let segments = raw_offset(&ring, distance);
+    let pieces = split(&segments);
+
+    // keep the pieces at the offset distance on the offset side of the hull
+    let prepared = PreparedHull::new(&ring);
+    let reach = distance.abs() * (1.0 - SLACK);
+    let kept: Vec<(Point, Point)> = pieces
+        .into_iter()
+        .filter(|(a, b)| {
+            let mid = Point {
+                x: (a.x + b.x) / 2.0,
+                y: (a.y + b.y) / 2.0,
+                id: 0,
+            };
+            prepared.contains(&mid) == (distance < 0.0) && prepared.distance(&mid) >= reach
+        })
+        .collect();
+
+    chain(kept)
+}
+
+/// The ring without repeated vertices, vertices on a straight line between their
+/// neighbours, or folds where the boundary runs back over itself and then forward again.
+/// Those would give overlapping offset edges; the tips of true spikes are kept.
+fn without_collinear(ring: &[Point]) -> Vec<Point> {
+    let collinear = |a: &Point, b: &Point, c: &Point| {
+        let (ux, uy, vx, vy) = (b.x - a.x, b.y - a.y, c.x - b.x, c.y - b.y);
+        (ux * vy - uy * vx).abs() <= 1E-12 * ux.hypot(uy) * vx.hypot(vy)
+    };
+    // `b` lies on the segment from `a` to `c`, given the three are collinear
+    let between = |a: &Point, b: &Point, c: &Point| {
+        (b.x - a.x) * (c.x - b.x) + (b.y - a.y) * (c.y - b.y) >= 0.0
+    };
+
+    let mut ring = ring.to_vec();
+    // the seam of the ring moves by half a turn on every pass, so two passes without
+    // changes have looked at every vertex
+    let mut unchanged = 0;
+    while unchanged < 2 && ring.len() >= 3 {
+        let before = ring.len();
+        let mut out: Vec<Point> = Vec::with_capacity(before);
+        for p in ring {
+            if out.last().is_some_and(|q| q.x == p.x && q.y == p.y) {
+                continue;
+            }
+            out.push(p);
+            loop {
+                let m = out.len();
+                if m >= 3
+                    && collinear(&out[m - 3], &out[m - 2], &out[m - 1])
+                    && between(&out[m - 3], &out[m - 2], &out[m - 1])
+                {
+                    out.remove(m - 2);
+                } else if m >= 4
+                    && collinear(&out[m - 4], &out[m - 3], &out[m - 2])
+                    && collinear(&out[m - 3], &out[m - 2], &out[m - 1])
+                    && between(&out[m - 4], &out[m - 2], &out[m - 3])
+                    && between(&out[m - 2], &out[m - 3], &out[m - 1])
+                {
+                    // a -> b -> c -> d covers no more than a -> d
+                    out.drain(m - 3..m - 1);
+                } else {
+                    break;
+                }
+            }
+        }
+        if out.len() > 1 && out[0].x == out[out.len() - 1].x && out[0].y == out[out.len() - 1].y {
+            out.pop();
+        }
+        let half = out.len() / 2;
+        out.rotate_left(half);
+        unchanged = if out.len() == before {
+            unchanged + 1
+        } else {
+            0
+        };
+        ring = out;
+    }
+    ring
+}
+
+/// The offset edges joined by arcs or straight chords, possibly self-crossing.
+fn raw_offset(ring: &[Point], distance: f64) -> Vec<(Point, Point)> {
+    let n = ring.len();
+    let normal = |i: usize| {
+        let (a, b) = (&ring[i], &ring[(i + 1) % n]);
+        // right-hand normal, outward for a counter-clockwise ring
+        (b.y - a.y).atan2(b.x - a.x) - PI / 2.0
+    };
+
+    let mut path: Vec<Point> = Vec::new();
+    for i in 0..n {
+        let (a, b) = (&ring[i], &ring[(i + 1) % n]);
+        let theta = normal(i);
+        path.push(offset(a, theta, distance));
+        path.push(offset(b, theta, distance));
+
+        // the corner at b, turning from this edge's normal to the next one's
+        let next = normal((i + 1) % n);
+        let mut turn = (next - theta).rem_euclid(2.0 * PI);
+        if turn > PI {
+            turn -= 2.0 * PI;
+        }
+        if n == 2 || PI - turn.abs() < 1E-9 {
+            // the boundary turns back on itself, around the outside
+            turn = PI.copysign(distance);
+        }
+        if (turn > 0.0) == (distance > 0.0) {
+            // a circumscribed polygon of the arc, so no chord comes closer than the radius
+            let steps = (turn.abs() / ARC_STEP).ceil() as usize;
+            let step = turn / steps as f64;
+            let radius = distance / (step / 2.0).cos();
+            for s in 0..steps {
+                path.push(offset(b, theta + step * (s as f64 + 0.5), radius));
+            }
+        }
+        // elsewhere the offset edges overlap, and are cut at their crossing
+    }
+
+    (0..path.len())
+        .map(|i| (path[i].clone(), path[(i + 1) % path.len()].clone()))
+        .filter(|(a, b)| a.x != b.x || a.y != b.y)
+        .collect()
+}
+
+fn offset(p: &Point, theta: f64, distance: f64) -> Point {
+    Point {
+        x: p.x + distance * theta.cos(),
+        y: p.y + distance * theta.sin(),
+        id: p.id,
+    }
+}
+
+/// Splits the segments at their crossings; both halves of a crossing share its point.
+fn split(segments: &[(Point, Point)]) -> Vec<(Point, Point)> {
+    let mut cuts: Vec<Vec<(f64, Point)>> = vec![Vec::new(); segments.len()];
+
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    let min_x = |i: usize| segments[i].0.x.min(segments[i].1.x);
+    let max_x = |i: usize| segments[i].0.x.max(segments[i].1.x);
+    order.sort_by(|&a, &b| min_x(a).total_cmp(&min_x(b)));
+    for (o, &i) in order.iter().enumerate() {
+        for &j in order[o + 1..].iter() {
+            if min_x(j) > max_x(i) {
+                break;
+            }
+            let adjacent = j.abs_diff(i) == 1 || j.abs_diff(i) == segments.len() - 1;
+            if adjacent {
+                continue;
+            }
+            if let Some((t, u, point)) = crossing(&segments[i], &segments[j]) {
+                cuts[i].push((t, point.clone()));
+                cuts[j].push((u, point));
+            }
+        }
+    }
+
+    let mut pieces = Vec::with_capacity(segments.len());
+    for (segment, mut at) in segments.iter().zip(cuts) {
+        at.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut start = segment.0.clone();
+        for (_, point) in at {
+            pieces.push((start, point.clone()));
+            start = point;
+        }
+        pieces.push((start, segment.1.clone()));
+    }
+    pieces
+}
+
+/// Where two segments properly cross: the parameter along each and the point.
+fn crossing(s: &(Point, Point), r: &(Point, Point)) -> Option<(f64, f64, Point)> {
+    let (dx, dy) = (s.1.x - s.0.x, s.1.y - s.0.y);
+    let (ex, ey) = (r.1.x - r.0.x, r.1.y - r.0.y);
+    let det = dx * ey - dy * ex;
+    // nearly parallel segments meet at an unreliable point; they never need cutting
+    if det.abs() <= 1E-12 * dx.hypot(dy) * ex.hypot(ey) {
+        return None;
+    }
+    let (fx, fy) = (r.0.x - s.0.x, r.0.y - s.0.y);
+    let t = (fx * ey - fy * ex) / det;
+    let u = (fx * dy - fy * dx) / det;
+    if t <= 0.0 || t >= 1.0 || u <= 0.0 || u >= 1.0 {
+        return None;
+    }
+    Some((
+        t,
+        u,
+        Point {
+            x: s.0.x + t * dx,
+            y: s.0.y + t * dy,
+            id: s.0.id,
+        },
+    ))
+}
+
+/// Joins segments end to start into rings.
+///
+/// Segments leading into or out of a dead end are the stubs of offset edges running past
+/// a crossing, nearly tangent to the result; they are pruned first.
+fn chain(segments: Vec<(Point, Point)>) -> Vec<Vec<Point>> {
+    let key = |p: &Point| (p.x.to_bits(), p.y.to_bits());
+    let mut starting: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+    let mut ending: HashMap<(u64, u64), Vec<usize>> = HashMap::new();
+    for (i, (a, b)) in segments.iter().enumerate() {
+        starting.entry(key(a)).or_default().push(i);
+        ending.entry(key(b)).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let live = |i: usize, used: &[bool], map: &HashMap<(u64, u64), Vec<usize>>, p: &Point| {
+        map.get(&key(p))
+            .is_some_and(|next| next.iter().any(|&j| j != i && !used[j]))
+    };
+    let mut queue: Vec<usize> = (0..segments.len()).collect();
+    while let Some(i) = queue.pop() {
+        let (a, b) = &segments[i];
+        if used[i] || (live(i, &used, &ending, a) && live(i, &used, &starting, b)) {
+            continue;
+        }
+        used[i] = true;
+        for map in [&ending, &starting] {
+            for p in [a, b] {
+                queue.extend(map.get(&key(p)).into_iter().flatten().copied());
+            }
+        }
+    }
+
+    let mut rings = Vec::new();
+    for first in 0..segments.len() {
+        if used[first] {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut current = first;
+        loop {
+            used[current] = true;
+            ring.push(segments[current].0.clone());
+            let end = key(&segments[current].1);
+            match starting
+                .get(&end)
+                .and_then(|next| next.iter().copied().find(|&s| !used[s]))
+            {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        // open chains are slivers left by the distance test
+        if ring.len() >= 3 && key(&segments[current].1) == key(&ring[0]) {
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+/// Shoelace area, positive for counter-clockwise rings.
+pub(crate) fn signed_area(ring: &[Point]) -> f64 {
+    (0..ring.len())
+        .map(|i| {
+            let (a, b) = (&ring[i], &ring[(i + 1) % ring.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring(coordinates: &[(f64, f64)]) -> Vec<Point> {
+        coordinates
+            .iter()
+            .map(|&(x, y)| Point { x, y, id: 0 })
+            .collect()
+    }
+
+    #[test]
+    fn test_buffer() {
+        let square = ring(&[(0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 0.0)]);
+
+        // dilation: the square, four side strips and four quarter discs
+        let dilated = buffer(&square, 0.5);
+        let expected = 4.0 + 4.0 * 2.0 * 0.5 + PI * 0.25;
+        assert!((signed_area(&dilated) - expected).abs() < 1E-2);
+
+        // erosion keeps sharp corners
+        let eroded = buffer(&square, -0.5);
+        assert!((signed_area(&eroded) - 1.0).abs() < 1E-12);
+        assert!(buffer(&square, -1.5).is_empty());
+
+        // a U shape on a thick base: eroding by more than half the arm width removes the arms
+        let u = ring(&[
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 4.0),
+            (2.0, 4.0),
+            (2.0, 2.0),
+            (1.0, 2.0),
+            (1.0, 4.0),
+            (0.0, 4.0),
+        ]);
+        let eroded = buffer(&u, -0.55);
+        // the base rectangle, plus the corners between its top and the arcs around (1, 2)
+        // and (2, 2) left where the arms used to be
+        let (r, a) = (0.55f64, 0.45f64);
+        let corner = a * r - (a * (r * r - a * a).sqrt() + r * r * (a / r).asin()) / 2.0;
+        assert!((signed_area(&eroded) - (1.9 * 0.9 + 2.0 * corner)).abs() < 1E-2);
+        let prepared = PreparedHull::new(&u);
+        assert!(eroded
+            .iter()
+            .all(|p| prepared.contains(p) && prepared.distance(p) > 0.55 - 1E-2));
+
+        // a chamber behind a narrow channel becomes a hole under dilation
+        let chamber = ring(&[
+            (0.0, 0.0),
+            (5.0, 0.0),
+            (5.0, 5.0),
+            (2.6, 5.0),
+            (2.6, 4.0),
+            (4.0, 4.0),
+            (4.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 4.0),
+            (2.4, 4.0),
+            (2.4, 5.0),
+            (0.0, 5.0),
+        ]);
+        let rings = buffer_rings(&chamber, 0.2);
+        assert_eq!(rings.len(), 2);
+        let hole = rings.iter().find(|r| signed_area(r) < 0.0).unwrap();
+        assert!((signed_area(hole) + 2.6 * 2.6).abs() < 0.1);
+
+        // a closed hull stays closed, and a point grows into a disc
+        let closed = [square.clone(), vec![square[0].clone()]].concat();
+        let dilated = buffer(&closed, 0.5);
+        assert_eq!(dilated.first(), dilated.last());
+        let disc = buffer(&square[..1], 1.0);
+        assert!((signed_area(&disc) - PI).abs() < 1E-2);
+    }
+}
@@ -0,0 +1,123 @@
+/// Build Information
+use std::fmt;
+
+/// The version and configuration of this build of the library.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildInfo {
+    /// crate version
+    pub version: &'static str,
+    /// enabled cargo features, in alphabetical order
+    pub features: Vec<&'static str>,
+    /// neighbour index implementations, the default first, see
+    /// [`crate::index::IndexBackend`]
+    pub index: Vec<&'static str>,
+    /// coordinate precisions the neighbour index can store, see [`crate::index::IndexPrecision`]
+    pub index_precisions: Vec<&'static str>,
+    /// whether the crate was compiled with optimisations
+    pub optimized: bool,
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "concave_hull {} (features: {}; index: {} [{}]; {})",
+            self.version,
+            if self.features.is_empty() {
+                "none".to_string()
+            } else {
+                self.features.join(", ")
+            },
+            self.index.join(", "),
+            self.index_precisions.join(", "),
+            if self.optimized { "release" } else { "debug" },
+        )
+    }
+}
+
+/// every cargo feature of the crate but `default`, in alphabetical order, with whether it
+/// is enabled; checked against the manifest by the tests
+const FEATURES: &[(&str, bool)] = &[
+    ("config", cfg!(feature = "config")),
+    ("experimental-f16", cfg!(feature = "experimental-f16")),
+    ("float", cfg!(feature = "float")),
+    ("geojson", cfg!(feature = "geojson")),
+    ("gpkg", cfg!(feature = "gpkg")),
+    ("kd-tree", cfg!(feature = "kd-tree")),
+    ("object_store", cfg!(feature = "object_store")),
+    ("parallel", cfg!(feature = "parallel")),
+    ("python", cfg!(feature = "python")),
+    ("rstar", cfg!(feature = "rstar")),
+    ("serde", cfg!(feature = "serde")),
+    ("simd", cfg!(feature = "simd")),
+    ("tracing", cfg!(feature = "tracing")),
+];
+
+/// Reports the version and configuration of the running library.
+///
+/// Include it in bug reports and reproducibility logs to record exactly which code computed a
+/// hull; its `Display` form fits on one line.
+///
+/// # Returns
+///
+/// Returns the `BuildInfo` of this build.
+pub fn build_info() -> BuildInfo {
+    let mut index = vec![if cfg!(feature = "kd-tree") {
+        "kiddo"
+    } else {
+        "sweep"
+    }];
+    if cfg!(feature = "rstar") {
+        index.push("rstar");
+    }
+    let mut index_precisions = vec!["f64"];
+    if cfg!(feature = "experimental-f16") {
+        index_precisions.push("f16");
+    }
+
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+        index,
+        index_precisions,
+        optimized: !cfg!(debug_assertions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info() {
+        let info = build_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.features.contains(&"serde"), cfg!(feature = "serde"));
+        assert!(info.index_precisions.contains(&"f64"));
+        assert_eq!(info.index.contains(&"rstar"), cfg!(feature = "rstar"));
+        assert!(info.to_string().starts_with("concave_hull "));
+    }
+
+    #[test]
+    fn test_build_info_features() {
+        // the features of the manifest, so that none is left out of the table
+        let manifest = include_str!("../Cargo.toml");
+        let section = manifest.split("\n[features]\n").nth(1).unwrap();
+        let mut features: Vec<&str> = section
+            .lines()
+            .take_while(|line| !line.starts_with('['))
+            .filter_map(|line| line.split_once(" = "))
+            .map(|(name, _)| name)
+            .filter(|&name| name != "default")
+            .collect();
+        let mut table: Vec<&str> = FEATURES.iter().map(|(name, _)| *name).collect();
+        assert!(table.windows(2).all(|pair| pair[0] < pair[1]));
+        table.sort_unstable();
+        features.sort_unstable();
+        assert_eq!(table, features);
+    }
+}
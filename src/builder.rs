@@ -0,0 +1,603 @@
+/// Configurable Hull Computation
+///
+/// [`ConcaveHull`] holds every setting of a hull computation, so options are added as
+/// builder methods instead of new parameters of [`crate::concave_hull`].
+///
+/// ```
+/// use concave_hull::builder::ConcaveHull;
+///
+/// let hull = ConcaveHull::builder().k(5).iterate(true).max_k(64).build().compute(&points)?;
+/// ```
+use crate::error::ConcaveHullError;
+use crate::index::{Dataset, IndexBackend, PointIndex};
+use crate::metric::{Metric, Tolerance};
+use crate::point::Point;
+use crate::polygon::Containment;
+use crate::stats::{HullStats, Progress};
+use crate::{Options, Orientation, ProgressCallback};
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A configured hull computation, see [`ConcaveHull::builder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcaveHull {
+    /// number of nearest neighbours of the first attempt
+    k: usize,
+    /// whether to raise `k` until a hull enclosing every point is found
+    iterate: bool,
+    /// the largest `k` tried when iterating
+    max_k: usize,
+    /// how `k` is raised when iterating
+    escalation: Escalation,
+    /// how distances and angles between points are measured
+    metric: Metric,
+    /// the neighbour index the walk searches
+    index: IndexBackend,
+    /// below which the crossing and containment tests treat values as zero
+    tolerance: Tolerance,
+    /// the test the other points are checked to be inside the hull by
+    containment: Containment,
+    /// the most vertices of the hull, `None` for no limit
+    max_vertices: Option<usize>,
+    /// whether the hull repeats its first vertex at the end, `None` for as traced
+    closed: Option<bool>,
+    /// winding order of the hull
+    orientation: Orientation,
+    /// cancels the computation once set
+    cancel: Option<CancelToken>,
+    /// how long the search for a hull may take, `None` for no limit
+    max_duration: Option<Duration>,
+    /// what is returned once `max_duration` has passed
+    on_timeout: OnTimeout,
+}
+
+/// How [`ConcaveHull`] raises `k` after a rejected hull.
+///
+/// Datasets that need a `k` close to their size take as many attempts stepping by one;
+/// a faster escalation gets there in a few, at the risk of overshooting the smallest `k`
+/// that works, and so of a coarser hull. A step beyond `max_k` or the number of points
+/// tries the largest `k` within both instead.
+#[derive(Clone)]
+pub enum Escalation {
+    /// adds this many, at least one
+    Step(usize),
+    /// doubles `k`
+    Double,
+    /// goes on with the first of these that is larger than the last `k` tried
+    Schedule(Vec<usize>),
+    /// the `k` after the given one, or `None` to stop
+    Custom(Arc<dyn Fn(usize) -> Option<usize> + Send + Sync>),
+}
+
+impl Escalation {
+    /// An escalation by `next`, which gives the `k` after the one passed, or `None` to
+    /// stop.
+    pub fn custom(next: impl Fn(usize) -> Option<usize> + Send + Sync + 'static) -> Self {
+        Escalation::Custom(Arc::new(next))
+    }
+
+    /// the `k` to try after `k`, always larger, or `None` if there is none
+    pub(crate) fn next(&self, k: usize) -> Option<usize> {
+        let next = match self {
+            Escalation::Step(step) => k.checked_add((*step).max(1)),
+            Escalation::Double => k.max(1).checked_mul(2),
+            Escalation::Schedule(schedule) => schedule.iter().copied().find(|&next| next > k),
+            Escalation::Custom(next) => next(k),
+        };
+        next.filter(|&next| next > k)
+    }
+}
+
+impl Default for Escalation {
+    /// Steps of one, as [`crate::concave_hull`] takes them.
+    fn default() -> Self {
+        Escalation::Step(1)
+    }
+}
+
+impl std::fmt::Debug for Escalation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Escalation::Step(step) => f.debug_tuple("Step").field(step).finish(),
+            Escalation::Double => f.write_str("Double"),
+            Escalation::Schedule(schedule) => f.debug_tuple("Schedule").field(schedule).finish(),
+            Escalation::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl PartialEq for Escalation {
+    /// Custom escalations are equal if they share a closure.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Escalation::Step(a), Escalation::Step(b)) => a == b,
+            (Escalation::Double, Escalation::Double) => true,
+            (Escalation::Schedule(a), Escalation::Schedule(b)) => a == b,
+            (Escalation::Custom(a), Escalation::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// What [`ConcaveHull::compute`] returns once the time limit has passed without a hull.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnTimeout {
+    /// the error [`ConcaveHullError::TimedOut`]
+    #[default]
+    Error,
+    /// the convex hull of the points, which encloses them all
+    ConvexHull,
+}
+
+impl Default for ConcaveHull {
+    /// `k` of 3, iterating one at a time without a limit, Euclidean distances, the built-in
+    /// neighbour index, the default tolerance, any number of vertices, the ring as traced,
+    /// clockwise, and no time limit.
+    fn default() -> Self {
+        ConcaveHull {
+            k: 3,
+            iterate: true,
+            max_k: usize::MAX,
+            escalation: Escalation::Step(1),
+            metric: Metric::Euclidean,
+            index: IndexBackend::Builtin,
+            tolerance: Tolerance::default(),
+            containment: Containment::CrossingNumber,
+            max_vertices: None,
+            closed: None,
+            orientation: Orientation::Clockwise,
+            cancel: None,
+            max_duration: None,
+            on_timeout: OnTimeout::Error,
+        }
+    }
+}
+
+impl ConcaveHull {
+    /// A builder starting from the [`Default`] configuration.
+    pub fn builder() -> ConcaveHullBuilder {
+        ConcaveHullBuilder::default()
+    }
+
+    /// Computes the concave hull of `dataset` with this configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataset` -  2D point cloud; it is left unchanged.
+    ///
+    /// # Returns
+    ///
+    /// Returns the hull as [`crate::concave_hull`] does, or a [`ConcaveHullError`], which
+    /// reports `max_k` as the largest `k` tried if it stopped the search, and
+    /// [`ConcaveHullError::Cancelled`] once the [`CancelToken`] is cancelled. Once the time
+    /// limit has passed it returns what [`OnTimeout`] says.
+    pub fn compute(&self, dataset: &[Point]) -> Result<Vec<Point>, ConcaveHullError> {
+        self.compute_with(&mut PointIndex::new(dataset), None)
+            .map(|(hull, _)| hull)
+    }
+
+    /// Computes the concave hull like [`ConcaveHull::compute`] and reports how it was
+    /// found.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataset` -  2D point cloud; it is left unchanged.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result of [`ConcaveHull::compute`], with the `k` of the hull and every
+    /// attempt on the way in the [`HullStats`]. The fallback of [`OnTimeout::ConvexHull`]
+    /// has no `k`.
+    pub fn compute_with_stats(
+        &self,
+        dataset: &[Point],
+    ) -> Result<(Vec<Point>, HullStats), ConcaveHullError> {
+        self.compute_with(&mut PointIndex::new(dataset), None)
+    }
+
+    /// Computes the concave hull like [`ConcaveHull::compute`], of the points of `index`
+    /// and with its neighbour index, which is built once for any number of computations,
+    /// e.g. a sweep over configurations on one dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - the 2D point cloud and its neighbour index.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result of [`ConcaveHull::compute`] for the indexed points.
+    pub fn compute_indexed(&self, index: &mut PointIndex) -> Result<Vec<Point>, ConcaveHullError> {
+        self.compute_with(index, None).map(|(hull, _)| hull)
+    }
+
+    /// Computes the concave hull like [`ConcaveHull::compute`], of the points of a shared
+    /// `dataset` and with its neighbour index, which waits for other users of the dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataset` - the 2D point cloud and its neighbour index.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result of [`ConcaveHull::compute`] for the points of the dataset.
+    pub fn compute_dataset(&self, dataset: &Dataset) -> Result<Vec<Point>, ConcaveHullError> {
+        dataset.with_index(|index| self.compute_with(index, None).map(|(hull, _)| hull))
+    }
+
+    /// Computes the concave hull like [`ConcaveHull::compute`], telling `progress` how far
+    /// the walk has got every few hundred steps and whenever a walk ends, e.g. to show
+    /// progress on runs over millions of points.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataset` -  2D point cloud; it is left unchanged.
+    /// * `progress` - called with the step, `k` and hull size of the current attempt.
+    ///
+    /// # Returns
+    ///
+    /// Returns the result of [`ConcaveHull::compute`].
+    pub fn compute_with_progress(
+        &self,
+        dataset: &[Point],
+        progress: impl FnMut(Progress),
+    ) -> Result<Vec<Point>, ConcaveHullError> {
+        self.compute_with(
+            &mut PointIndex::new(dataset),
+            Some(RefCell::new(Box::new(progress))),
+        )
+        .map(|(hull, _)| hull)
+    }
+
+    fn compute_with(
+        &self,
+        index: &mut PointIndex,
+        progress: Option<RefCell<ProgressCallback<'_>>>,
+    ) -> Result<(Vec<Point>, HullStats), ConcaveHullError> {
+        let cancel = self.cancel.clone();
+        let deadline = self
+            .max_duration
+            .and_then(|duration| Instant::now().checked_add(duration));
+        let interrupt = (cancel.is_some() || deadline.is_some()).then(|| {
+            Box::new(move || {
+                cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+                    || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            }) as Box<dyn Fn() -> bool + Send + Sync>
+        });
+        let options = Options {
+            metric: self.metric,
+            backend: self.index.clone(),
+            escalation: self.escalation.clone(),
+            tolerance: self.tolerance,
+            containment: self.containment,
+            interrupt,
+            progress,
+            ..Default::default()
+        };
+        let (hull, stats) = match index.search(self.k, self.iterate, self.max_k, &options, true) {
+            // interrupted by the deadline rather than the token
+            Err(ConcaveHullError::Cancelled)
+                if !self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) =>
+            {
+                match self.on_timeout {
+                    OnTimeout::Error => return Err(ConcaveHullError::TimedOut),
+                    OnTimeout::ConvexHull => {
+                        let convex = crate::convex::convex_hull(index.points());
+                        (crate::close_ring(convex), HullStats::default())
+                    }
+                }
+            }
+            result => result?,
+        };
+        let hull = match self.max_vertices {
+            Some(max_vertices) => crate::simplify::reduce_vertices(&hull, max_vertices),
+            None => hull,
+        };
+        let hull = crate::orient(hull, self.orientation);
+        let hull = match self.closed {
+            Some(true) => crate::close_ring(hull),
+            Some(false) => crate::open_ring(&hull).to_vec(),
+            None => hull,
+        };
+        Ok((hull, stats))
+    }
+}
+
+/// Sets up a [`ConcaveHull`] one option at a time.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConcaveHullBuilder {
+    /// the configuration built so far
+    config: ConcaveHull,
+}
+
+impl ConcaveHullBuilder {
+    /// Sets the number of nearest neighbours of the first attempt.
+    pub fn k(mut self, k: usize) -> Self {
+        self.config.k = k;
+        self
+    }
+
+    /// Sets whether to raise `k` until a hull enclosing every point is found.
+    pub fn iterate(mut self, iterate: bool) -> Self {
+        self.config.iterate = iterate;
+        self
+    }
+
+    /// Sets the largest `k` tried when iterating; the first `k` is tried regardless.
+    pub fn max_k(mut self, max_k: usize) -> Self {
+        self.config.max_k = max_k;
+        self
+    }
+
+    /// Sets how `k` is raised after a rejected hull, one at a time unless set.
+    pub fn escalation(mut self, escalation: Escalation) -> Self {
+        self.config.escalation = escalation;
+        self
+    }
+
+    /// Sets how distances and angles are measured, e.g. [`Metric::Haversine`] for
+    /// longitude/latitude degrees as in [`crate::concave_hull_geographic`].
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.config.metric = metric;
+        self
+    }
+
+    /// Sets the neighbour index the walk searches, e.g. [`IndexBackend::BruteForce`], the
+    /// R-tree of the `rstar` feature or an index of another crate to compare on a dataset.
+    pub fn index(mut self, index: IndexBackend) -> Self {
+        self.config.index = index;
+        self
+    }
+
+    /// Sets the tolerance of the crossing and containment tests, e.g.
+    /// [`Tolerance::relative`] for coordinates far from unit size.
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.config.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the point-in-polygon test a hull is accepted by, the crossing number unless
+    /// set; [`Containment::WindingNumber`] keeps hulls whose boundary touches itself.
+    pub fn containment(mut self, containment: Containment) -> Self {
+        self.config.containment = containment;
+        self
+    }
+
+    /// Sets the most vertices of the hull, not counting a repeated first vertex; larger hulls
+    /// are reduced by [`crate::simplify::reduce_vertices`], which keeps every point
+    /// enclosed.
+    pub fn max_vertices(mut self, max_vertices: usize) -> Self {
+        self.config.max_vertices = Some(max_vertices);
+        self
+    }
+
+    /// Sets whether the hull repeats its first vertex at the end (a closed ring, as GIS
+    /// formats expect) or lists every vertex once; unset, the hull is returned as traced,
+    /// which is closed unless the walk used up every point.
+    pub fn closed(mut self, closed: bool) -> Self {
+        self.config.closed = Some(closed);
+        self
+    }
+
+    /// Sets the winding order of the hull, [`Orientation::Clockwise`] unless set.
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.config.orientation = orientation;
+        self
+    }
+
+    /// Sets a token that cancels the computation from another thread; the walk checks it
+    /// every few hundred steps and before every attempted `k`.
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.config.cancel = Some(token);
+        self
+    }
+
+    /// Sets how long the search for a hull may take, checked every few hundred steps of the
+    /// walk and before every attempted `k`.
+    pub fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.config.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Sets what is returned once `max_duration` has passed, [`OnTimeout::Error`] unless set.
+    pub fn on_timeout(mut self, on_timeout: OnTimeout) -> Self {
+        self.config.on_timeout = on_timeout;
+        self
+    }
+
+    /// The configured computation.
+    pub fn build(self) -> ConcaveHull {
+        let mut config = self.config;
+        config.max_k = config.max_k.max(config.k);
+        config
+    }
+}
+
+/// A flag shared between the computations it cancels and the threads that cancel them.
+///
+/// Clones share the flag; an `Arc<AtomicBool>` the application already has converts into a
+/// token with `From`.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// A token that is not cancelled yet.
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// Cancels the computations using this token and its clones.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancelToken {
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        CancelToken(flag)
+    }
+}
+
+impl PartialEq for CancelToken {
+    /// Tokens are equal if they share a flag.
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_concave_hull_builder() {
+        let mut rng = Rng::new(7);
+        let points: Vec<Point> = (0..500)
+            .map(|id| Point {
+                x: rng.uniform(),
+                y: rng.uniform(),
+                id,
+            })
+            .collect();
+
+        let hull = ConcaveHull::builder().build().compute(&points);
+        assert_eq!(hull, crate::concave_hull(&mut points.clone(), 3, true));
+        let hull = ConcaveHull::builder().k(5).build().compute(&points);
+        assert_eq!(hull, crate::concave_hull(&mut points.clone(), 5, true));
+
+        // the first attempt at k = 3 fails, so a limit of 3 stops the search there
+        let limited = ConcaveHull::builder().k(3).max_k(3).build();
+        assert_eq!(
+            limited.compute(&points),
+            Err(ConcaveHullError::NoValidHullFound { max_k_tried: 3 })
+        );
+        assert_eq!(ConcaveHull::builder().k(4).max_k(2).build().max_k, 4);
+
+        let hull = ConcaveHull::builder()
+            .build()
+            .compute(&points[..4])
+            .unwrap();
+        assert_eq!(hull.len(), 4);
+        let closed = ConcaveHull::builder().closed(true).build();
+        assert_eq!(closed.compute(&points[..4]).unwrap().len(), 5);
+        let ccw = ConcaveHull::builder()
+            .orientation(Orientation::CounterClockwise)
+            .build();
+        assert!(crate::buffer::signed_area(&ccw.compute(&points).unwrap()) > 0.0);
+        let open = ConcaveHull::builder().closed(false).build();
+        let expected = crate::concave_hull(&mut points.clone(), 3, true).unwrap();
+        assert_eq!(
+            open.compute(&points).unwrap(),
+            expected[..expected.len() - 1]
+        );
+
+        let mut reports = Vec::new();
+        let hull = ConcaveHull::builder()
+            .build()
+            .compute_with_progress(&points, |progress| reports.push(progress));
+        assert_eq!(hull, crate::concave_hull(&mut points.clone(), 3, true));
+        // every attempt from k = 3 reports, the accepted one last
+        assert_eq!(reports.last().unwrap().hull_size, hull.unwrap().len());
+        assert_eq!(reports[0].k, 3);
+        assert!(reports.windows(2).all(|pair| pair[0].k <= pair[1].k));
+
+        let capped = ConcaveHull::builder()
+            .max_vertices(10)
+            .closed(false)
+            .build();
+        assert!(capped.compute(&points).unwrap().len() <= 10);
+
+        let token = CancelToken::new();
+        let cancellable = ConcaveHull::builder().cancel_token(token.clone()).build();
+        assert!(cancellable.compute(&points).is_ok());
+        token.cancel();
+        assert_eq!(
+            cancellable.compute(&points),
+            Err(ConcaveHullError::Cancelled)
+        );
+        // cancelled from the progress callback, in the middle of a walk
+        let token = CancelToken::new();
+        let cancellable = ConcaveHull::builder().cancel_token(token.clone()).build();
+        let result = cancellable.compute_with_progress(&points, |_| token.cancel());
+        assert_eq!(result, Err(ConcaveHullError::Cancelled));
+
+        let timed = ConcaveHull::builder().max_duration(Duration::ZERO);
+        assert_eq!(
+            timed.clone().build().compute(&points),
+            Err(ConcaveHullError::TimedOut)
+        );
+        let fallback = timed.on_timeout(OnTimeout::ConvexHull).build();
+        let convex = crate::convex::convex_hull(&points);
+        assert_eq!(fallback.compute(&points).unwrap().len(), convex.len() + 1);
+        let generous = ConcaveHull::builder().max_duration(Duration::from_secs(3600));
+        assert_eq!(
+            generous.build().compute(&points),
+            crate::concave_hull(&mut points.clone(), 3, true)
+        );
+
+        let geographic = ConcaveHull::builder().metric(Metric::Haversine).build();
+        assert_eq!(
+            geographic.compute(&points),
+            crate::concave_hull_geographic(&mut points.clone(), 3, true)
+        );
+    }
+
+    #[test]
+    fn test_escalation() {
+        let mut rng = Rng::new(7);
+        let points: Vec<Point> = (0..500)
+            .map(|id| Point {
+                x: rng.uniform(),
+                y: rng.uniform(),
+                id,
+            })
+            .collect();
+        let tried = |escalation: Escalation| {
+            let builder = ConcaveHull::builder().escalation(escalation).build();
+            let (hull, stats) = builder.compute_with_stats(&points).unwrap();
+            let ks: Vec<usize> = stats.history.iter().map(|attempt| attempt.k).collect();
+            assert_eq!(stats.k, ks.last().copied());
+            assert_eq!(stats.attempts, ks.len());
+            (hull, ks)
+        };
+
+        let (hull, ks) = tried(Escalation::default());
+        assert_eq!(Ok(hull), crate::concave_hull(&mut points.clone(), 3, true));
+        assert!(ks.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        let (_, ks) = tried(Escalation::Double);
+        assert!(ks.windows(2).all(|pair| pair[1] == 2 * pair[0]));
+        let (_, ks) = tried(Escalation::Schedule(vec![2, 8, 20, 50]));
+        assert!(ks.iter().all(|k| [3, 8, 20, 50].contains(k)));
+        let (_, ks) = tried(Escalation::custom(|k| Some(k + 3)));
+        assert!(ks.windows(2).all(|pair| pair[1] == pair[0] + 3));
+
+        // an empty schedule leaves nothing after the first attempt
+        let once = ConcaveHull::builder()
+            .escalation(Escalation::Schedule(Vec::new()))
+            .build();
+        assert_eq!(
+            once.compute(&points),
+            Err(ConcaveHullError::NoValidHullFound { max_k_tried: 3 })
+        );
+        // a step past the limit tries the limit itself
+        let jump = ConcaveHull::builder()
+            .escalation(Escalation::custom(|_| Some(usize::MAX)))
+            .max_k(10)
+            .build();
+        match jump.compute_with_stats(&points) {
+            Ok((_, stats)) => assert_eq!(stats.k, Some(10)),
+            Err(error) => assert_eq!(
+                error,
+                ConcaveHullError::NoValidHullFound { max_k_tried: 10 }
+            ),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+/// Convex Hull
+use crate::point::Point;
+
+/// Computes the convex hull of `points` (Andrew's monotone chain).
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+///
+/// # Returns
+///
+/// Returns the hull vertices counter-clockwise as an open ring, starting from the point with
+/// the smallest x (then y). Collinear boundary points are left out, so degenerate input
+/// gives one or two vertices.
+pub fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut sorted: Vec<&Point> = points.iter().collect();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup_by(|a, b| a.x == b.x && a.y == b.y);
+
+    if sorted.len() < 3 {
+        return sorted.into_iter().cloned().collect();
+    }
+
+    let mut hull: Vec<&Point> = Vec::with_capacity(sorted.len() + 1);
+    // lower chain, left to right
+    for &p in sorted.iter() {
+        while hull.len() >= 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // upper chain, right to left
+    let lower = hull.len() + 1;
+    for &p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.0 {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+    // the upper chain ends on the first point again
+    hull.pop();
+
+    hull.into_iter().cloned().collect()
+}
+
+/// Twice the signed area of the triangle `o`, `a`, `b`: positive for a left turn.
+pub(crate) fn cross(o: &Point, a: &Point, b: &Point) -> f64 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convex_hull() {
+        let mut points: Vec<Point> = (0..5)
+            .flat_map(|i| (0..5).map(move |j| (i as f64, j as f64)))
+            .enumerate()
+            .map(|(id, (x, y))| Point {
+                x,
+                y,
+                id: id as u64,
+            })
+            .collect();
+        points.push(Point {
+            x: 4.0,
+            y: 4.0,
+            id: 99,
+        });
+
+        let hull = convex_hull(&points);
+        let corners: Vec<(f64, f64)> = hull.iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(
+            corners,
+            vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]
+        );
+        assert_eq!(hull[0].id, 0);
+
+        // collinear input keeps only the end points
+        let line = &points[..5];
+        assert_eq!(convex_hull(line).len(), 2);
+    }
+}
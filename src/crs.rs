@@ -0,0 +1,168 @@
+/// Coordinate Reference Systems
+///
+/// The hull is computed from bare coordinates, so nothing stops hulls in degrees and hulls
+/// in metres from being put side by side. A [`Crs`] tags inputs and results with the system
+/// their coordinates are in, the exporters write it out, and combining results in
+/// different systems fails with a [`CrsMismatch`] instead of producing nonsense.
+use std::fmt;
+
+/// A coordinate reference system, identified by an authority and a code, e.g. `EPSG:4326`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Crs {
+    /// authority defining the system, upper case, e.g. `EPSG`
+    authority: String,
+    /// code of the system at the authority
+    code: String,
+}
+
+impl Crs {
+    /// The system with this EPSG code, e.g. 4326 for longitude and latitude on WGS 84.
+    pub fn epsg(code: u32) -> Self {
+        Crs {
+            authority: "EPSG".to_string(),
+            code: code.to_string(),
+        }
+    }
+
+    /// Reads an identifier such as `EPSG:32633`, `epsg:4326` or the OGC URN
+    /// `urn:ogc:def:crs:EPSG::4326`, `None` if it is neither.
+    pub fn parse(identifier: &str) -> Option<Self> {
+        let identifier = identifier.trim();
+        let (authority, code) = match identifier.strip_prefix("urn:ogc:def:crs:") {
+            // the version between the authority and the code may be empty
+            Some(urn) => {
+                let mut parts = urn.split(':');
+                let (authority, code) = (parts.next()?, parts.nth(1)?);
+                if parts.next().is_some() {
+                    return None;
+                }
+                (authority, code)
+            }
+            None => identifier.split_once(':')?,
+        };
+        let valid = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric());
+        (valid(authority) && valid(code)).then(|| Crs {
+            authority: authority.to_ascii_uppercase(),
+            code: code.to_string(),
+        })
+    }
+
+    /// the authority, e.g. `EPSG`
+    pub fn authority(&self) -> &str {
+        &self.authority
+    }
+
+    /// the code at the authority, e.g. `4326`
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// the EPSG code, if this is an EPSG system
+    pub fn epsg_code(&self) -> Option<u32> {
+        (self.authority == "EPSG")
+            .then(|| self.code.parse().ok())
+            .flatten()
+    }
+
+    /// the OGC URN of the system, e.g. `urn:ogc:def:crs:EPSG::4326`
+    pub fn to_urn(&self) -> String {
+        format!("urn:ogc:def:crs:{}::{}", self.authority, self.code)
+    }
+}
+
+impl fmt::Display for Crs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.authority, self.code)
+    }
+}
+
+/// Results in two different coordinate reference systems were combined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrsMismatch {
+    /// the system of the results so far
+    pub expected: Crs,
+    /// the system of the result added to them
+    pub found: Crs,
+}
+
+impl fmt::Display for CrsMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "coordinates in {} combined with coordinates in {}",
+            self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for CrsMismatch {}
+
+/// The system shared by results tagged with `systems`.
+///
+/// Untagged results go with any system, so only the tagged ones need to agree.
+///
+/// # Arguments
+///
+/// * `systems` - the system of each result, `None` where it is not known.
+///
+/// # Returns
+///
+/// Returns the system of the tagged results, `None` if there are none, or a
+/// [`CrsMismatch`] naming the first two systems that differ.
+pub fn common<'a>(
+    systems: impl IntoIterator<Item = Option<&'a Crs>>,
+) -> Result<Option<Crs>, CrsMismatch> {
+    let mut shared: Option<&Crs> = None;
+    for crs in systems.into_iter().flatten() {
+        match shared {
+            Some(expected) if expected != crs => {
+                return Err(CrsMismatch {
+                    expected: expected.clone(),
+                    found: crs.clone(),
+                })
+            }
+            _ => shared = Some(crs),
+        }
+    }
+    Ok(shared.cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crs() {
+        let wgs84 = Crs::epsg(4326);
+        assert_eq!(Crs::parse("EPSG:4326"), Some(wgs84.clone()));
+        assert_eq!(Crs::parse(" epsg:4326"), Some(wgs84.clone()));
+        assert_eq!(
+            Crs::parse("urn:ogc:def:crs:EPSG::4326"),
+            Some(wgs84.clone())
+        );
+        assert_eq!(
+            Crs::parse("urn:ogc:def:crs:OGC:1.3:CRS84").unwrap().code(),
+            "CRS84"
+        );
+        assert_eq!(Crs::parse("4326"), None);
+        assert_eq!(Crs::parse("EPSG:"), None);
+        assert_eq!(wgs84.to_string(), "EPSG:4326");
+        assert_eq!(Crs::parse(&wgs84.to_urn()), Some(wgs84.clone()));
+        assert_eq!(wgs84.epsg_code(), Some(4326));
+
+        let utm = Crs::epsg(32633);
+        assert_eq!(
+            common([None, Some(&wgs84), Some(&wgs84)]),
+            Ok(Some(wgs84.clone()))
+        );
+        assert_eq!(common([None, None]), Ok(None));
+        assert_eq!(
+            common([Some(&wgs84), None, Some(&utm)]),
+            Err(CrsMismatch {
+                expected: wgs84,
+                found: utm,
+            })
+        );
+    }
+}
@@ -0,0 +1,515 @@
+/// Minimum Enclosing Shapes
+use crate::convex::convex_hull;
+use crate::point::Point;
+use crate::synthetic::Rng;
+use crate::triangulation::circumcenter;
+
+/// A rectangle rotated by `angle` about its centre.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotatedRect {
+    /// centre x coordinate
+    pub cx: f64,
+    /// centre y coordinate
+    pub cy: f64,
+    /// side length along the `angle` direction
+    pub width: f64,
+    /// side length perpendicular to the `angle` direction
+    pub height: f64,
+    /// direction of the width side, radians from the x axis in [0, PI)
+    pub angle: f64,
+}
+
+impl RotatedRect {
+    /// Area of the rectangle.
+    pub fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    /// The four corners, counter-clockwise.
+    pub fn corners(&self) -> [Point; 4] {
+        let (ux, uy) = (
+            self.angle.cos() * self.width / 2.0,
+            self.angle.sin() * self.width / 2.0,
+        );
+        let (vx, vy) = (
+            -self.angle.sin() * self.height / 2.0,
+            self.angle.cos() * self.height / 2.0,
+        );
+        [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].map(|(s, t)| Point {
+            x: self.cx + s * ux + t * vx,
+            y: self.cy + s * uy + t * vy,
+            id: 0,
+        })
+    }
+}
+
+/// Computes the minimum-area rectangle enclosing `points` (rotating calipers).
+///
+/// The optimal rectangle has a side on the convex hull, so every hull edge is tried once
+/// while the three other calipers advance monotonically around the hull.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud, or a hull computed from one.
+///
+/// # Returns
+///
+/// Returns the rectangle, or `None` for an empty input. Collinear input gives a rectangle of
+/// zero height along the line.
+pub fn min_bounding_rect(points: &[Point]) -> Option<RotatedRect> {
+    let hull = convex_hull(points);
+    let n = hull.len();
+    match n {
+        0 => return None,
+        1 => {
+            return Some(RotatedRect {
+                cx: hull[0].x,
+                cy: hull[0].y,
+                width: 0.0,
+                height: 0.0,
+                angle: 0.0,
+            })
+        }
+        _ => {}
+    }
+
+    let along = |j: usize, ux: f64, uy: f64| hull[j].x * ux + hull[j].y * uy;
+    let extreme = |ux: f64, uy: f64| {
+        (0..n)
+            .max_by(|&p, &q| along(p, ux, uy).total_cmp(&along(q, ux, uy)))
+            .unwrap_or(0)
+    };
+    // the calipers: furthest along the edge, furthest from it, and furthest back
+    let (mut right, mut top, mut left) = (0, 0, 0);
+    let mut best: Option<(f64, RotatedRect)> = None;
+
+    // a segment has one edge; both of its directions give the same rectangle
+    let edges = if n == 2 { 1 } else { n };
+    for i in 0..edges {
+        let (a, b) = (&hull[i], &hull[(i + 1) % n]);
+        let len = (b.x - a.x).hypot(b.y - a.y);
+        let (ux, uy) = ((b.x - a.x) / len, (b.y - a.y) / len);
+        // inward normal of a counter-clockwise ring
+        let (vx, vy) = (-uy, ux);
+
+        if i == 0 {
+            right = extreme(ux, uy);
+            top = extreme(vx, vy);
+            left = extreme(-ux, -uy);
+        }
+        // projections onto a convex ring are unimodal, so each caliper only moves forward
+        while along((right + 1) % n, ux, uy) > along(right, ux, uy) {
+            right = (right + 1) % n;
+        }
+        while along((top + 1) % n, vx, vy) > along(top, vx, vy) {
+            top = (top + 1) % n;
+        }
+        while along((left + 1) % n, -ux, -uy) > along(left, -ux, -uy) {
+            left = (left + 1) % n;
+        }
+
+        let (u0, u1) = (along(left, ux, uy), along(right, ux, uy));
+        let (v0, v1) = (along(i, vx, vy), along(top, vx, vy));
+        let (width, height) = (u1 - u0, v1 - v0);
+        let area = width * height;
+        if best.as_ref().is_none_or(|(a, _)| area < *a) {
+            let (mu, mv) = ((u0 + u1) / 2.0, (v0 + v1) / 2.0);
+            best = Some((
+                area,
+                RotatedRect {
+                    cx: mu * ux + mv * vx,
+                    cy: mu * uy + mv * vy,
+                    width,
+                    height,
+                    angle: uy.atan2(ux).rem_euclid(std::f64::consts::PI),
+                },
+            ));
+        }
+    }
+
+    best.map(|(_, rect)| rect)
+}
+
+/// Computes the smallest circle enclosing `points` (Welzl's algorithm).
+///
+/// The iterative form of the algorithm runs on the convex hull vertices in a shuffled
+/// order, giving expected linear time after the hull. The shuffle is seeded, so equal
+/// inputs give equal circles.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud, or a hull computed from one.
+///
+/// # Returns
+///
+/// Returns the centre and the radius of the circle. An empty input gives a zero radius at
+/// the origin.
+pub fn min_enclosing_circle(points: &[Point]) -> (Point, f64) {
+    let mut hull = convex_hull(points);
+    let mut rng = Rng::new(0);
+    for i in (1..hull.len()).rev() {
+        hull.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+    }
+
+    let mut circle = (
+        Point {
+            x: 0.0,
+            y: 0.0,
+            id: 0,
+        },
+        0.0,
+    );
+    for i in 0..hull.len() {
+        if i > 0 && encloses(&circle, &hull[i]) {
+            continue;
+        }
+        // hull[i] lies on the boundary of the circle enclosing hull[..=i]
+        circle = (hull[i].clone(), 0.0);
+        for j in 0..i {
+            if encloses(&circle, &hull[j]) {
+                continue;
+            }
+            // so do hull[i] and hull[j]
+            circle = diameter_circle(&hull[i], &hull[j]);
+            for k in 0..j {
+                if !encloses(&circle, &hull[k]) {
+                    circle = circumcircle(&hull[i], &hull[j], &hull[k]);
+                }
+            }
+        }
+    }
+
+    let (mut centre, radius) = circle;
+    centre.id = 0;
+    (centre, radius)
+}
+
+fn encloses((centre, radius): &(Point, f64), p: &Point) -> bool {
+    (p.x - centre.x).hypot(p.y - centre.y) <= radius * (1.0 + 1E-12) + 1E-12
+}
+
+fn diameter_circle(a: &Point, b: &Point) -> (Point, f64) {
+    let centre = Point {
+        x: (a.x + b.x) / 2.0,
+        y: (a.y + b.y) / 2.0,
+        id: 0,
+    };
+    let radius = (a.x - b.x).hypot(a.y - b.y) / 2.0;
+    (centre, radius)
+}
+
+fn circumcircle(a: &Point, b: &Point, c: &Point) -> (Point, f64) {
+    let centre = circumcenter(a, b, c);
+    if !centre.x.is_finite() || !centre.y.is_finite() {
+        // collinear: the widest pair spans the circle
+        return [
+            diameter_circle(a, b),
+            diameter_circle(a, c),
+            diameter_circle(b, c),
+        ]
+        .into_iter()
+        .max_by(|p, q| p.1.total_cmp(&q.1))
+        .unwrap();
+    }
+    let radius = (a.x - centre.x).hypot(a.y - centre.y);
+    (centre, radius)
+}
+
+/// An ellipse rotated by `angle` about its centre.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ellipse {
+    /// centre x coordinate
+    pub cx: f64,
+    /// centre y coordinate
+    pub cy: f64,
+    /// semi-axis along the `angle` direction, the longer one
+    pub semi_major: f64,
+    /// semi-axis perpendicular to the `angle` direction
+    pub semi_minor: f64,
+    /// direction of the major axis, radians from the x axis in [0, PI)
+    pub angle: f64,
+}
+
+impl Ellipse {
+    /// Area of the ellipse.
+    pub fn area(&self) -> f64 {
+        std::f64::consts::PI * self.semi_major * self.semi_minor
+    }
+
+    /// Tests whether `point` lies on or inside the ellipse.
+    pub fn contains(&self, point: &Point) -> bool {
+        let (dx, dy) = (point.x - self.cx, point.y - self.cy);
+        let (s, c) = self.angle.sin_cos();
+        let (u, v) = (dx * c + dy * s, -dx * s + dy * c);
+        (u / self.semi_major).powi(2) + (v / self.semi_minor).powi(2) <= 1.0 + 1E-9
+    }
+}
+
+/// Computes the minimum-area ellipse enclosing `points` (Khachiyan's algorithm).
+///
+/// The ellipse only depends on the convex hull, so the iteration runs on the hull vertices
+/// until the weight update falls below 1E-10 (at most 10000 rounds). The result is then
+/// scaled up just enough that every hull vertex is enclosed.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud, or a hull computed from one.
+///
+/// # Returns
+///
+/// Returns the ellipse, or `None` if the points are collinear and enclose no area.
+pub fn min_enclosing_ellipse(points: &[Point]) -> Option<Ellipse> {
+    let hull = convex_hull(points);
+    let n = hull.len();
+    if n < 3 {
+        return None;
+    }
+
+    // lifted points q = (x, y, 1), each weighted by u
+    let mut u = vec![1.0 / n as f64; n];
+    for _ in 0..10000 {
+        let mut x = [[0.0; 3]; 3];
+        for (p, &w) in hull.iter().zip(u.iter()) {
+            let q = [p.x, p.y, 1.0];
+            for r in 0..3 {
+                for c in 0..3 {
+                    x[r][c] += w * q[r] * q[c];
+                }
+            }
+        }
+        let x_inv = invert3(&x)?;
+        let (j, m) = hull
+            .iter()
+            .map(|p| {
+                let q = [p.x, p.y, 1.0];
+                (0..3)
+                    .map(|r| (0..3).map(|c| q[r] * x_inv[r][c] * q[c]).sum::<f64>())
+                    .sum::<f64>()
+            })
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        // for d = 2: (m - d - 1) / ((d + 1) (m - 1))
+        let step = (m - 3.0) / (3.0 * (m - 1.0));
+        for w in u.iter_mut() {
+            *w *= 1.0 - step;
+        }
+        u[j] += step;
+        if step < 1E-10 {
+            break;
+        }
+    }
+
+    let (cx, cy) = hull
+        .iter()
+        .zip(u.iter())
+        .fold((0.0, 0.0), |(x, y), (p, &w)| (x + w * p.x, y + w * p.y));
+    // covariance of the weighted points; the ellipse is (p - c)' (2 cov)^-1 (p - c) <= 1
+    let (mut sxx, mut sxy, mut syy) = (0.0, 0.0, 0.0);
+    for (p, &w) in hull.iter().zip(u.iter()) {
+        let (dx, dy) = (p.x - cx, p.y - cy);
+        sxx += w * dx * dx;
+        sxy += w * dx * dy;
+        syy += w * dy * dy;
+    }
+    let (a, b, c) = (2.0 * sxx, 2.0 * sxy, 2.0 * syy);
+
+    // eigen decomposition of the symmetric [[a, b], [b, c]]
+    let mean = (a + c) / 2.0;
+    let spread = ((a - c) / 2.0).hypot(b);
+    let (major, minor) = (mean + spread, mean - spread);
+    if minor <= 0.0 {
+        return None;
+    }
+    let angle = match (b == 0.0, a >= c) {
+        (true, true) => 0.0,
+        (true, false) => std::f64::consts::FRAC_PI_2,
+        _ => b.atan2(major - c).rem_euclid(std::f64::consts::PI),
+    };
+
+    let mut ellipse = Ellipse {
+        cx,
+        cy,
+        semi_major: major.sqrt(),
+        semi_minor: minor.sqrt(),
+        angle,
+    };
+    // the iteration stops short of the optimum; grow the ellipse over its outermost vertex
+    let (s, co) = angle.sin_cos();
+    let reach = hull
+        .iter()
+        .map(|p| {
+            let (dx, dy) = (p.x - cx, p.y - cy);
+            let (u, v) = (dx * co + dy * s, -dx * s + dy * co);
+            (u / ellipse.semi_major).powi(2) + (v / ellipse.semi_minor).powi(2)
+        })
+        .fold(0.0, f64::max)
+        .max(1.0)
+        .sqrt();
+    ellipse.semi_major *= reach;
+    ellipse.semi_minor *= reach;
+    Some(ellipse)
+}
+
+/// Inverse of a 3 x 3 matrix, or `None` if it is singular.
+fn invert3(m: &[[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let cofactor = |r: usize, c: usize| {
+        let (r0, r1) = ((r + 1) % 3, (r + 2) % 3);
+        let (c0, c1) = ((c + 1) % 3, (c + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let det: f64 = (0..3).map(|c| m[0][c] * cofactor(0, c)).sum();
+    if det.abs() < f64::MIN_POSITIVE {
+        return None;
+    }
+    let mut inverse = [[0.0; 3]; 3];
+    for (r, row) in inverse.iter_mut().enumerate() {
+        for (c, value) in row.iter_mut().enumerate() {
+            *value = cofactor(c, r) / det;
+        }
+    }
+    Some(inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
+
+    #[test]
+    fn test_min_enclosing_circle() {
+        let point = |x: f64, y: f64| Point { x, y, id: 0 };
+
+        // a triangle with an obtuse angle is spanned by its longest side
+        let (centre, radius) =
+            min_enclosing_circle(&[point(0.0, 0.0), point(4.0, 0.0), point(2.0, 0.5)]);
+        assert!((centre.x - 2.0).abs() < 1E-12 && centre.y.abs() < 1E-12);
+        assert!((radius - 2.0).abs() < 1E-12);
+
+        // points on a circle, with more inside
+        let mut points: Vec<Point> = (0..100)
+            .map(|i| {
+                let t = i as f64 * 0.37;
+                point(1.0 + 3.0 * t.cos(), -2.0 + 3.0 * t.sin())
+            })
+            .collect();
+        points.extend((0..100).map(|i| point(1.0 + (i % 10) as f64 * 0.2, -2.0)));
+        let (centre, radius) = min_enclosing_circle(&points);
+        assert!((centre.x - 1.0).abs() < 1E-9 && (centre.y + 2.0).abs() < 1E-9);
+        assert!((radius - 3.0).abs() < 1E-9);
+
+        assert!(ulps_eq(
+            min_enclosing_circle(&[point(5.0, 6.0)]).1,
+            0.0,
+            DEFAULT_ULPS
+        ));
+        assert!(ulps_eq(min_enclosing_circle(&[]).1, 0.0, DEFAULT_ULPS));
+    }
+
+    #[test]
+    fn test_min_enclosing_ellipse() {
+        // points on a rotated ellipse, with more inside
+        let (s, c) = 0.5f64.sin_cos();
+        let points: Vec<Point> = (0..200)
+            .map(|i| {
+                let t = i as f64 * 0.31;
+                let r = if i % 2 == 0 { 1.0 } else { 0.5 };
+                let (u, v) = (4.0 * r * t.cos(), 1.5 * r * t.sin());
+                Point {
+                    x: 3.0 + u * c - v * s,
+                    y: 1.0 + u * s + v * c,
+                    id: i,
+                }
+            })
+            .collect();
+
+        let ellipse = min_enclosing_ellipse(&points).unwrap();
+        assert!((ellipse.cx - 3.0).abs() < 1E-4 && (ellipse.cy - 1.0).abs() < 1E-4);
+        assert!((ellipse.semi_major - 4.0).abs() < 1E-4);
+        assert!((ellipse.semi_minor - 1.5).abs() < 1E-4);
+        assert!((ellipse.angle - 0.5).abs() < 1E-4);
+        assert!(points.iter().all(|p| ellipse.contains(p)));
+
+        let line: Vec<Point> = (0..3)
+            .map(|i| Point {
+                x: i as f64,
+                y: 0.0,
+                id: 0,
+            })
+            .collect();
+        assert!(min_enclosing_ellipse(&line).is_none());
+    }
+
+    #[test]
+    fn test_min_bounding_rect() {
+        // a 4 x 1 grid of points rotated by 30 degrees
+        let (s, c) = 30f64.to_radians().sin_cos();
+        let points: Vec<Point> = (0..=40)
+            .flat_map(|i| (0..=10).map(move |j| (i as f64 / 10.0, j as f64 / 10.0)))
+            .map(|(x, y)| Point {
+                x: 5.0 + x * c - y * s,
+                y: -2.0 + x * s + y * c,
+                id: 0,
+            })
+            .collect();
+
+        let rect = min_bounding_rect(&points).unwrap();
+        assert!((rect.area() - 4.0).abs() < 1E-9);
+        assert!((rect.width.max(rect.height) - 4.0).abs() < 1E-9);
+        for p in points.iter() {
+            let (dx, dy) = (p.x - rect.cx, p.y - rect.cy);
+            let u = dx * rect.angle.cos() + dy * rect.angle.sin();
+            let v = -dx * rect.angle.sin() + dy * rect.angle.cos();
+            assert!(u.abs() <= rect.width / 2.0 + 1E-9 && v.abs() <= rect.height / 2.0 + 1E-9);
+        }
+
+        // agree with the brute force over every hull edge on a random cloud
+        let mut seed = 7u64;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let cloud: Vec<Point> = (0..500)
+            .map(|_| Point {
+                x: next() * 3.0,
+                y: next(),
+                id: 0,
+            })
+            .collect();
+        let hull = convex_hull(&cloud);
+        let brute = (0..hull.len())
+            .map(|i| {
+                let (a, b) = (&hull[i], &hull[(i + 1) % hull.len()]);
+                let t = (b.y - a.y).atan2(b.x - a.x);
+                let (u, v): (Vec<f64>, Vec<f64>) = hull
+                    .iter()
+                    .map(|p| {
+                        (
+                            p.x * t.cos() + p.y * t.sin(),
+                            -p.x * t.sin() + p.y * t.cos(),
+                        )
+                    })
+                    .unzip();
+                let span = |w: &[f64]| {
+                    w.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+                        - w.iter().cloned().fold(f64::INFINITY, f64::min)
+                };
+                span(&u) * span(&v)
+            })
+            .fold(f64::INFINITY, f64::min);
+        assert!((min_bounding_rect(&cloud).unwrap().area() - brute).abs() < 1E-9);
+
+        let line: Vec<Point> = (0..3)
+            .map(|i| Point {
+                x: i as f64,
+                y: i as f64,
+                id: 0,
+            })
+            .collect();
+        let rect = min_bounding_rect(&line).unwrap();
+        assert!(rect.height.abs() < 1E-12 && (rect.width - 8f64.sqrt()).abs() < 1E-12);
+        assert!(min_bounding_rect(&[]).is_none());
+    }
+}
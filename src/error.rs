@@ -0,0 +1,54 @@
+/// Errors of the Hull Computation
+use std::fmt;
+
+/// Why [`crate::concave_hull`] returned no hull.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConcaveHullError {
+    /// there are not more points than the `k` nearest neighbours looked at
+    InsufficientPoints {
+        /// the least number of points `k` needs
+        required: usize,
+        /// the number of points given
+        found: usize,
+    },
+    /// no `k` up to `max_k_tried` gave a hull enclosing every point
+    NoValidHullFound {
+        /// the largest `k` tried
+        max_k_tried: usize,
+    },
+    /// a point has a NaN or infinite coordinate
+    InvalidCoordinate {
+        /// the id of the point
+        id: u64,
+    },
+    /// the computation was cancelled before a hull was found
+    Cancelled,
+    /// no hull was found within the time limit
+    TimedOut,
+}
+
+impl fmt::Display for ConcaveHullError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcaveHullError::InsufficientPoints { required, found } => write!(
+                f,
+                "too few points for a hull: {} needed, {} given",
+                required, found
+            ),
+            ConcaveHullError::NoValidHullFound { max_k_tried } => {
+                write!(
+                    f,
+                    "no hull enclosing every point for k up to {}",
+                    max_k_tried
+                )
+            }
+            ConcaveHullError::InvalidCoordinate { id } => {
+                write!(f, "point {} has a non-finite coordinate", id)
+            }
+            ConcaveHullError::Cancelled => write!(f, "the hull computation was cancelled"),
+            ConcaveHullError::TimedOut => write!(f, "no hull was found within the time limit"),
+        }
+    }
+}
+
+impl std::error::Error for ConcaveHullError {}
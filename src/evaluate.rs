@@ -0,0 +1,193 @@
+/// Accuracy Evaluation Against a Known Boundary
+use crate::point::{BoundingBox, Point};
+use crate::prepared::PreparedHull;
+
+/// samples taken along each boundary for the RMSE
+const BOUNDARY_SAMPLES: usize = 1024;
+/// cells along each side of the grid the IoU is estimated on
+const AREA_GRID: usize = 512;
+
+/// How closely a computed hull matches the ground truth polygon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// root mean square distance between the two boundaries, measured both ways
+    pub boundary_rmse: f64,
+    /// area of the intersection over the area of the union, in [0, 1]
+    pub iou: f64,
+    /// vertices of the computed hull, without a repeated closing vertex
+    pub hull_vertices: usize,
+    /// vertices of the ground truth polygon, without a repeated closing vertex
+    pub truth_vertices: usize,
+}
+
+/// Runs a hull algorithm on `points` and scores its output against `ground_truth`.
+///
+/// # Arguments
+///
+/// * `algorithm` - computes a hull from a point cloud, e.g.
+///   `|p| concave_hull(&mut p.to_vec(), 3, true).unwrap()`.
+/// * `ground_truth` - the true boundary of the point cloud, open or closed.
+/// * `points` - the point cloud, e.g. from
+///   [`crate::synthetic::sample_polygon_boundary_and_interior`].
+///
+/// # Returns
+///
+/// Returns the accuracy of the hull, see [`evaluate_hull`].
+pub fn evaluate<F>(algorithm: F, ground_truth: &[Point], points: &[Point]) -> Evaluation
+where
+    F: FnOnce(&[Point]) -> Vec<Point>,
+{
+    evaluate_hull(&algorithm(points), ground_truth)
+}
+
+/// Scores a computed hull against the ground truth polygon.
+///
+/// The boundary RMSE samples both boundaries evenly by arc length and takes each sample's
+/// distance to the other boundary, so both missed and spurious parts of the boundary count.
+/// The IoU is estimated from the cell centres of a 512 x 512 grid over both polygons.
+///
+/// # Arguments
+///
+/// * `hull` - the computed hull, open or closed.
+/// * `ground_truth` - the true boundary, open or closed.
+///
+/// # Returns
+///
+/// Returns the accuracy of the hull. A hull without an interior has an IoU of 0, and an
+/// empty hull an infinite RMSE.
+pub fn evaluate_hull(hull: &[Point], ground_truth: &[Point]) -> Evaluation {
+    let hull = crate::open_ring(hull);
+    let truth = crate::open_ring(ground_truth);
+    let (prepared_hull, prepared_truth) = (PreparedHull::new(hull), PreparedHull::new(truth));
+
+    let distances: Vec<f64> = boundary_samples(hull)
+        .iter()
+        .map(|p| prepared_truth.distance(p))
+        .chain(
+            boundary_samples(truth)
+                .iter()
+                .map(|p| prepared_hull.distance(p)),
+        )
+        .collect();
+    let boundary_rmse = if hull.is_empty() || truth.is_empty() {
+        f64::INFINITY
+    } else {
+        (distances.iter().map(|d| d * d).sum::<f64>() / distances.len() as f64).sqrt()
+    };
+
+    let mut bbox = BoundingBox::from_points(hull);
+    let other = BoundingBox::from_points(truth);
+    bbox.min_x = bbox.min_x.min(other.min_x);
+    bbox.min_y = bbox.min_y.min(other.min_y);
+    bbox.max_x = bbox.max_x.max(other.max_x);
+    bbox.max_y = bbox.max_y.max(other.max_y);
+
+    let (mut intersection, mut union) = (0usize, 0usize);
+    if bbox.area() > 0.0 {
+        let (w, h) = (
+            bbox.width() / AREA_GRID as f64,
+            bbox.height() / AREA_GRID as f64,
+        );
+        for row in 0..AREA_GRID {
+            for col in 0..AREA_GRID {
+                let centre = Point {
+                    x: bbox.min_x + (col as f64 + 0.5) * w,
+                    y: bbox.min_y + (row as f64 + 0.5) * h,
+                    id: 0,
+                };
+                let (a, b) = (
+                    prepared_hull.contains(&centre),
+                    prepared_truth.contains(&centre),
+                );
+                intersection += (a && b) as usize;
+                union += (a || b) as usize;
+            }
+        }
+    }
+
+    Evaluation {
+        boundary_rmse,
+        iou: if union > 0 {
+            intersection as f64 / union as f64
+        } else {
+            0.0
+        },
+        hull_vertices: hull.len(),
+        truth_vertices: truth.len(),
+    }
+}
+
+/// Points spaced evenly by arc length around a ring.
+fn boundary_samples(ring: &[Point]) -> Vec<Point> {
+    if ring.len() < 2 {
+        return ring.to_vec();
+    }
+    let edge = |i: usize| (&ring[i], &ring[(i + 1) % ring.len()]);
+    let perimeter: f64 = (0..ring.len())
+        .map(|i| {
+            let (a, b) = edge(i);
+            (b.x - a.x).hypot(b.y - a.y)
+        })
+        .sum();
+    let step = perimeter / BOUNDARY_SAMPLES as f64;
+
+    let mut samples = Vec::with_capacity(BOUNDARY_SAMPLES);
+    let mut offset = 0.0;
+    for i in 0..ring.len() {
+        let (a, b) = edge(i);
+        let length = (b.x - a.x).hypot(b.y - a.y);
+        while offset < length && samples.len() < BOUNDARY_SAMPLES {
+            let t = offset / length;
+            samples.push(Point {
+                x: a.x + t * (b.x - a.x),
+                y: a.y + t * (b.y - a.y),
+                id: 0,
+            });
+            offset += step;
+        }
+        offset -= length;
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
+    use crate::synthetic::sample_polygon_boundary_and_interior;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Point> {
+        [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+            .iter()
+            .map(|&(dx, dy)| Point {
+                x: x + dx * size,
+                y: y + dy * size,
+                id: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let truth = square(0.0, 0.0, 2.0);
+
+        let exact = evaluate_hull(&truth, &truth);
+        assert!(exact.boundary_rmse < 1E-12);
+        assert!((exact.iou - 1.0).abs() < 1E-12);
+        assert_eq!(exact.truth_vertices, 4);
+
+        // a quarter of the truth: IoU of 1/4
+        let quarter = evaluate_hull(&square(0.0, 0.0, 1.0), &truth);
+        assert!((quarter.iou - 0.25).abs() < 1E-2);
+        assert!(quarter.boundary_rmse > 0.1);
+
+        let points = sample_polygon_boundary_and_interior(&truth, 2000, 0.0, 1);
+        let convex = evaluate(crate::convex::convex_hull, &truth, &points);
+        assert!(convex.iou > 0.99);
+        assert!(convex.boundary_rmse < 1E-2);
+
+        let empty = evaluate(|_| Vec::new(), &truth, &points);
+        assert!(ulps_eq(empty.iou, 0.0, DEFAULT_ULPS));
+        assert_eq!(empty.hull_vertices, 0);
+    }
+}
@@ -0,0 +1,80 @@
+/// Hull Export Formats
+use crate::point::Point;
+
+/// A hull as an indexed edge list
+///
+/// Mesh-processing and graph tools usually want vertices stored once and edges as index
+/// pairs rather than a coordinate ring.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeList {
+    /// hull vertices, each stored once
+    pub vertices: Vec<Point>,
+    /// edges as index pairs into `vertices`, in ring order
+    pub edges: Vec<(usize, usize)>,
+    /// indices of the vertices adjacent to each vertex, (previous, next) around the ring
+    pub adjacency: Vec<Vec<usize>>,
+}
+
+/// Converts a hull ring into an indexed edge list.
+///
+/// The hull may be open or closed (first vertex repeated at the end), as returned by
+/// [`crate::concave_hull`]. A ring of n >= 3 vertices has n edges, each vertex two neighbours.
+///
+/// # Arguments
+///
+/// * `hull` - ordered hull vertices.
+///
+/// # Returns
+///
+/// Returns the `EdgeList` of the ring.
+pub fn to_edge_list(hull: &[Point]) -> EdgeList {
+    let vertices = crate::open_ring(hull).to_vec();
+    let n = vertices.len();
+
+    let edges: Vec<(usize, usize)> = match n {
+        0 | 1 => Vec::new(),
+        2 => vec![(0, 1)],
+        _ => (0..n).map(|i| (i, (i + 1) % n)).collect(),
+    };
+
+    let mut adjacency = vec![Vec::new(); n];
+    for &(a, b) in edges.iter() {
+        adjacency[b].push(a);
+    }
+    for &(a, b) in edges.iter() {
+        adjacency[a].push(b);
+    }
+
+    EdgeList {
+        vertices,
+        edges,
+        adjacency,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_edge_list() {
+        let point = |x: f64, y: f64, id: u64| Point { x, y, id };
+        let hull = vec![
+            point(0.0, 0.0, 4),
+            point(1.0, 0.0, 2),
+            point(1.0, 1.0, 7),
+            point(0.0, 1.0, 1),
+            point(0.0, 0.0, 4),
+        ];
+
+        let list = to_edge_list(&hull);
+        assert_eq!(list.vertices.len(), 4);
+        assert_eq!(list.edges, vec![(0, 1), (1, 2), (2, 3), (3, 0)]);
+        assert_eq!(list.adjacency[0], vec![3, 1]);
+        assert_eq!(list.adjacency[2], vec![1, 3]);
+
+        let list = to_edge_list(&hull[..2]);
+        assert_eq!(list.edges, vec![(0, 1)]);
+        assert_eq!(list.adjacency, vec![vec![1], vec![0]]);
+    }
+}
@@ -0,0 +1,72 @@
+/// Hulls of Coordinates of Any Float Type
+use crate::error::ConcaveHullError;
+
+use num_traits::Float;
+
+/// Computes the concave hull of coordinate pairs of any float type, such as `f32`.
+///
+/// The walk runs on `f64` copies of the coordinates, which hold any `f32` (or half
+/// precision) value exactly, so the hull is the one [`crate::concave_hull`] finds for the
+/// same points. The hull vertices are returned as the input coordinates themselves, so
+/// nothing is rounded on the way back. For runs that are short of memory,
+/// [`crate::concave_hull_with_precision`] stores the neighbour index in lower precision.
+/// This is [`crate::concave_hull_of`] for coordinate pairs.
+///
+/// # Arguments
+///
+/// * `coordinates` - 2D point cloud as (x, y) pairs.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull as a closed ring of the input coordinates, or the error of
+/// [`crate::concave_hull`]; an [`ConcaveHullError::InvalidCoordinate`] id is the position
+/// of the pair.
+pub fn concave_hull_float<T: Float>(
+    coordinates: &[[T; 2]],
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<[T; 2]>, ConcaveHullError> {
+    crate::concave_hull_of(coordinates, k, iterate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_concave_hull_float() {
+        let mut rng = Rng::new(3);
+        let coordinates: Vec<[f32; 2]> = (0..400)
+            .map(|_| [rng.uniform() as f32 * 1E3, rng.uniform() as f32 * 1E3])
+            .collect();
+        let points: Vec<Point> = coordinates
+            .iter()
+            .enumerate()
+            .map(|(i, c)| Point {
+                x: c[0] as f64,
+                y: c[1] as f64,
+                id: i as u64,
+            })
+            .collect();
+
+        let hull = concave_hull_float(&coordinates, 3, true).unwrap();
+        let expected = crate::concave_hull(&mut points.clone(), 3, true).unwrap();
+        assert_eq!(hull.len(), expected.len());
+        assert!(hull
+            .iter()
+            .zip(expected.iter())
+            .all(|(c, p)| (c[0] as f64, c[1] as f64) == (p.x, p.y)));
+
+        let mut broken = coordinates.clone();
+        broken[5][1] = f32::INFINITY;
+        assert_eq!(
+            concave_hull_float(&broken, 3, true),
+            Err(ConcaveHullError::InvalidCoordinate { id: 5 })
+        );
+    }
+}
@@ -0,0 +1,475 @@
+/// GeoJSON Point Features
+use crate::crs::Crs;
+use crate::io::{HullSink, PointSource};
+use crate::point::Point;
+use crate::prepared::PreparedHull;
+
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::io::{BufRead, Read, Write};
+
+/// Why a GeoJSON document could not be read as point features.
+#[derive(Debug)]
+pub enum GeoJsonError {
+    /// the stream could not be read
+    Io(std::io::Error),
+    /// the document is not valid JSON
+    Json(serde_json::Error),
+    /// the document is not a `FeatureCollection`
+    NotFeatureCollection,
+    /// the feature at this index has no `Point` geometry with numeric coordinates
+    NotPoint(usize),
+}
+
+impl fmt::Display for GeoJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeoJsonError::Io(err) => write!(f, "cannot read GeoJSON: {}", err),
+            GeoJsonError::Json(err) => write!(f, "invalid GeoJSON: {}", err),
+            GeoJsonError::NotFeatureCollection => write!(f, "not a GeoJSON FeatureCollection"),
+            GeoJsonError::NotPoint(index) => write!(f, "feature {} is not a point", index),
+        }
+    }
+}
+
+impl std::error::Error for GeoJsonError {}
+
+/// A feature of the input, without its geometry.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FeatureInfo {
+    /// the feature's `id` member, if it had one
+    pub id: Option<Value>,
+    /// the feature's `properties`, empty if they were missing or null
+    pub properties: Map<String, Value>,
+}
+
+/// The point features of a GeoJSON `FeatureCollection`.
+///
+/// Every point's `id` is the index of its feature, so the features behind a hull's vertices,
+/// or behind any other output of the crate that passes points through, can be looked up
+/// with [`Features::feature`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Features {
+    /// the feature positions, in input order
+    pub points: Vec<Point>,
+    /// the feature ids and properties, in input order
+    pub features: Vec<FeatureInfo>,
+    /// the coordinate reference system named by the collection's `crs` member, if any
+    pub crs: Option<Crs>,
+}
+
+impl Features {
+    /// Reads the point features of a GeoJSON `FeatureCollection`.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - the GeoJSON document. Coordinates beyond the first two are ignored.
+    ///
+    /// # Returns
+    ///
+    /// Returns the features, or an error if the document is not a collection of points.
+    pub fn from_json(json: &str) -> Result<Self, GeoJsonError> {
+        let document: Value = serde_json::from_str(json).map_err(GeoJsonError::Json)?;
+        Features::from_document(&document)
+    }
+
+    /// Reads point features from a file, stdin, a buffer or any other [`PointSource`].
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - a GeoJSON `FeatureCollection` document, or a newline-delimited stream
+    ///   of point features as [`Features::from_seq`] reads.
+    ///
+    /// # Returns
+    ///
+    /// Returns the features, or an error if the source cannot be read or holds anything
+    /// other than point features.
+    pub fn read(source: impl PointSource) -> Result<Self, GeoJsonError> {
+        let mut text = String::new();
+        source
+            .open()
+            .and_then(|mut reader| reader.read_to_string(&mut text))
+            .map_err(GeoJsonError::Io)?;
+        match serde_json::from_str::<Value>(&text) {
+            Ok(document) if document["type"] == "FeatureCollection" => {
+                Features::from_document(&document)
+            }
+            _ => Features::from_seq(text.as_bytes()),
+        }
+    }
+
+    /// The point features of a parsed `FeatureCollection`.
+    fn from_document(document: &Value) -> Result<Self, GeoJsonError> {
+        if document["type"] != "FeatureCollection" {
+            return Err(GeoJsonError::NotFeatureCollection);
+        }
+        let features = document["features"]
+            .as_array()
+            .ok_or(GeoJsonError::NotFeatureCollection)?;
+
+        let mut collection = Features {
+            crs: read_crs(document),
+            ..Default::default()
+        };
+        for feature in features {
+            collection.push(feature)?;
+        }
+        Ok(collection)
+    }
+
+    /// Reads a newline-delimited stream of point features (GeoJSONSeq or NDJSON).
+    ///
+    /// See [`read_seq`] to process the features one at a time instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - the stream, one GeoJSON `Feature` per line.
+    ///
+    /// # Returns
+    ///
+    /// Returns the features, or the first error in the stream.
+    pub fn from_seq<R: BufRead>(reader: R) -> Result<Self, GeoJsonError> {
+        let mut collection = Features::default();
+        for feature in read_seq(reader) {
+            let (point, info) = feature?;
+            collection.points.push(point);
+            collection.features.push(info);
+        }
+        Ok(collection)
+    }
+
+    /// Appends a single point feature, giving its point the next id.
+    fn push(&mut self, feature: &Value) -> Result<(), GeoJsonError> {
+        let (point, info) = parse_feature(feature, self.points.len())?;
+        self.points.push(point);
+        self.features.push(info);
+        Ok(())
+    }
+
+    /// The feature a point of this collection came from.
+    pub fn feature(&self, point: &Point) -> Option<&FeatureInfo> {
+        self.features.get(point.id as usize)
+    }
+
+    /// The hull vertices as a `FeatureCollection` of points.
+    ///
+    /// Each vertex keeps the `id` and properties of its input feature, and gains a
+    /// `hull_index` property with its position along the hull.
+    ///
+    /// # Arguments
+    ///
+    /// * `hull` - a hull of [`Features::points`], open or closed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the GeoJSON `FeatureCollection`.
+    pub fn hull_vertices(&self, hull: &[Point]) -> Value {
+        let features = crate::open_ring(hull)
+            .iter()
+            .enumerate()
+            .map(|(i, p)| self.to_feature(p, "hull_index", json!(i)))
+            .collect();
+        with_crs(feature_collection(features), self.crs.as_ref())
+    }
+
+    /// Every input point as a `FeatureCollection`, classified against a hull.
+    ///
+    /// Each feature keeps its `id` and properties, and gains a `hull` property that is
+    /// `"vertex"` for hull vertices, `"inside"` for points within the hull, and `"outside"`
+    /// for the rest.
+    ///
+    /// # Arguments
+    ///
+    /// * `hull` - a hull of [`Features::points`], open or closed.
+    ///
+    /// # Returns
+    ///
+    /// Returns the GeoJSON `FeatureCollection`, in input order.
+    pub fn classify(&self, hull: &[Point]) -> Value {
+        let mut vertex = vec![false; self.points.len()];
+        for p in hull {
+            if let Some(v) = vertex.get_mut(p.id as usize) {
+                *v = true;
+            }
+        }
+        let prepared = PreparedHull::new(crate::open_ring(hull));
+
+        let features = self
+            .points
+            .iter()
+            .map(|p| {
+                let class = if vertex[p.id as usize] {
+                    "vertex"
+                } else if prepared.contains(p) {
+                    "inside"
+                } else {
+                    "outside"
+                };
+                self.to_feature(p, "hull", json!(class))
+            })
+            .collect();
+        with_crs(feature_collection(features), self.crs.as_ref())
+    }
+
+    /// A point feature with the input feature's id and properties, plus one more property.
+    fn to_feature(&self, point: &Point, key: &str, value: Value) -> Value {
+        let info = self.feature(point).cloned().unwrap_or_default();
+        let mut properties = info.properties;
+        properties.insert(key.to_string(), value);
+
+        let mut feature = json!({
+            "type": "Feature",
+            "geometry": {"type": "Point", "coordinates": [point.x, point.y]},
+            "properties": properties,
+        });
+        if let Some(id) = info.id {
+            feature["id"] = id;
+        }
+        feature
+    }
+}
+
+/// Reads a newline-delimited stream of point features one at a time.
+///
+/// Each line holds one GeoJSON `Feature`, optionally preceded by the RFC 8142 record
+/// separator; blank lines are skipped. Features are parsed as the iterator advances, so a
+/// stream larger than memory, or one still being written, is consumed incrementally.
+///
+/// # Arguments
+///
+/// * `reader` - the stream.
+///
+/// # Returns
+///
+/// Returns an iterator over the points, with ids counting the features from 0, and their
+/// feature ids and properties.
+pub fn read_seq<R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<(Point, FeatureInfo), GeoJsonError>> {
+    let mut index = 0;
+    reader.lines().filter_map(move |line| {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => return Some(Err(GeoJsonError::Io(err))),
+        };
+        let record = line.trim_start_matches('\u{1e}').trim();
+        if record.is_empty() {
+            return None;
+        }
+        let feature = serde_json::from_str(record)
+            .map_err(GeoJsonError::Json)
+            .and_then(|feature: Value| parse_feature(&feature, index));
+        index += 1;
+        Some(feature)
+    })
+}
+
+/// A point feature as a point with the given id, and its id and properties.
+fn parse_feature(feature: &Value, index: usize) -> Result<(Point, FeatureInfo), GeoJsonError> {
+    let geometry = &feature["geometry"];
+    let coordinates = geometry["coordinates"].as_array();
+    let (x, y) = match coordinates.map(|c| c.as_slice()) {
+        Some([x, y, ..]) if geometry["type"] == "Point" => (x.as_f64(), y.as_f64()),
+        _ => (None, None),
+    };
+    let (Some(x), Some(y)) = (x, y) else {
+        return Err(GeoJsonError::NotPoint(index));
+    };
+
+    let point = Point {
+        x,
+        y,
+        id: index as u64,
+    };
+    let info = FeatureInfo {
+        id: feature.get("id").cloned(),
+        properties: feature["properties"]
+            .as_object()
+            .cloned()
+            .unwrap_or_default(),
+    };
+    Ok((point, info))
+}
+
+/// Writes a GeoJSON document, such as [`Features::hull_vertices`], to a [`HullSink`].
+///
+/// # Arguments
+///
+/// * `sink` - the file, stdout, buffer or other destination.
+/// * `document` - the GeoJSON value.
+///
+/// # Returns
+///
+/// Returns an error if the sink cannot be written.
+pub fn write(sink: impl HullSink, document: &Value) -> Result<(), GeoJsonError> {
+    let mut writer = sink.create().map_err(GeoJsonError::Io)?;
+    serde_json::to_writer(&mut writer, document).map_err(|err| GeoJsonError::Io(err.into()))?;
+    writer.flush().map_err(GeoJsonError::Io)
+}
+
+/// Names the coordinate reference system of a GeoJSON object in a `crs` member.
+///
+/// RFC 7946 takes all GeoJSON to be longitude and latitude on WGS 84 and dropped the `crs`
+/// member, but readers such as GDAL still honour the named form of the 2008 specification
+/// written here, and it keeps projected coordinates from being taken for degrees.
+///
+/// # Arguments
+///
+/// * `document` - the GeoJSON object.
+/// * `crs` - its coordinate reference system, or `None` to leave it unnamed.
+///
+/// # Returns
+///
+/// Returns the object with its `crs` member set, or unchanged without a system.
+pub fn with_crs(mut document: Value, crs: Option<&Crs>) -> Value {
+    if let Some(crs) = crs {
+        document["crs"] = json!({"type": "name", "properties": {"name": crs.to_urn()}});
+    }
+    document
+}
+
+/// the system named by the `crs` member of `document`, if it has one
+fn read_crs(document: &Value) -> Option<Crs> {
+    document["crs"]["properties"]["name"]
+        .as_str()
+        .and_then(Crs::parse)
+}
+
+fn feature_collection(features: Vec<Value>) -> Value {
+    json!({"type": "FeatureCollection", "features": features})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{point_approx_eq, DEFAULT_ULPS};
+
+    #[test]
+    fn test_features() {
+        let mut features = Vec::new();
+        for i in 0..25 {
+            let (x, y) = ((i % 5) as f64, (i / 5) as f64 + 0.01 * (i % 3) as f64);
+            features.push(json!({
+                "type": "Feature",
+                "id": format!("site-{}", i),
+                "geometry": {"type": "Point", "coordinates": [x, y, 100.0]},
+                "properties": {"depth": i},
+            }));
+        }
+        let document = feature_collection(features).to_string();
+
+        let collection = Features::from_json(&document).unwrap();
+        assert_eq!(collection.points.len(), 25);
+        assert_eq!(collection.features[7].id, Some(json!("site-7")));
+
+        let hull = crate::concave_hull(&mut collection.points.clone(), 3, true).unwrap();
+        let vertices = collection.hull_vertices(&hull);
+        let vertices = vertices["features"].as_array().unwrap();
+        assert_eq!(vertices.len(), hull.len() - 1);
+        for (vertex, p) in vertices.iter().zip(hull.iter()) {
+            assert_eq!(vertex["id"], json!(format!("site-{}", p.id)));
+            assert_eq!(vertex["properties"]["depth"], json!(p.id));
+        }
+        let mut written = Vec::new();
+        write(&mut written, &collection.hull_vertices(&hull)).unwrap();
+        let read = Features::read(written.as_slice()).unwrap();
+        assert_eq!(read.features[0].properties["hull_index"], json!(0));
+        assert_eq!(read.points.len(), hull.len() - 1);
+
+        let classified = collection.classify(&hull);
+        let classes: Vec<&str> = classified["features"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["properties"]["hull"].as_str().unwrap())
+            .collect();
+        assert_eq!(classes[12], "inside");
+        assert_eq!(
+            classes.iter().filter(|&&c| c == "vertex").count(),
+            hull.len() - 1
+        );
+
+        let line = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 1]]}}
+        ]}"#;
+        assert!(matches!(
+            Features::from_json(line),
+            Err(GeoJsonError::NotPoint(0))
+        ));
+        assert!(matches!(
+            Features::from_json(r#"{"type": "Point"}"#),
+            Err(GeoJsonError::NotFeatureCollection)
+        ));
+
+        // the coordinate reference system goes from the input to the outputs
+        assert_eq!(collection.crs, None);
+        assert!(collection.hull_vertices(&hull).get("crs").is_none());
+        let utm = Crs::epsg(32633);
+        let tagged = with_crs(serde_json::from_str(&document).unwrap(), Some(&utm));
+        let collection = Features::from_json(&tagged.to_string()).unwrap();
+        assert_eq!(collection.crs, Some(utm.clone()));
+        let vertices = collection.hull_vertices(&hull);
+        assert_eq!(read_crs(&vertices), Some(utm));
+        assert_eq!(
+            vertices["crs"]["properties"]["name"],
+            "urn:ogc:def:crs:EPSG::32633"
+        );
+    }
+
+    #[test]
+    fn test_read_seq() {
+        let point = |coordinates: Value| json!({"type": "Point", "coordinates": coordinates});
+        let records = [
+            json!({"type": "Feature", "id": 3, "geometry": point(json!([1.5, 2.0])),
+                   "properties": {"name": "a"}}),
+            json!({"type": "Feature", "geometry": point(json!([0, 1])), "properties": null}),
+            json!({"type": "Feature", "geometry": null}),
+        ];
+        // a record separator before the first record, and a blank line after it
+        let stream = format!("\u{1e}{}\n\n{}\n{}\n", records[0], records[1], records[2]);
+
+        let mut features = read_seq(stream.as_bytes());
+        let (point, info) = features.next().unwrap().unwrap();
+        assert!(point_approx_eq(
+            &point,
+            &Point {
+                x: 1.5,
+                y: 2.0,
+                id: 0
+            },
+            DEFAULT_ULPS
+        ));
+        assert_eq!(info.id, Some(json!(3)));
+        assert_eq!(info.properties["name"], json!("a"));
+        let (point, info) = features.next().unwrap().unwrap();
+        assert!(point_approx_eq(
+            &point,
+            &Point {
+                x: 0.0,
+                y: 1.0,
+                id: 1
+            },
+            DEFAULT_ULPS
+        ));
+        assert!(info.properties.is_empty());
+        assert!(matches!(
+            features.next(),
+            Some(Err(GeoJsonError::NotPoint(2)))
+        ));
+        assert!(features.next().is_none());
+
+        assert!(matches!(
+            Features::from_seq(stream.as_bytes()),
+            Err(GeoJsonError::NotPoint(2))
+        ));
+        let complete = format!("{}\n{}", records[0], records[1]);
+        assert_eq!(
+            Features::from_seq(complete.as_bytes())
+                .unwrap()
+                .points
+                .len(),
+            2
+        );
+        assert_eq!(Features::read(complete.as_bytes()).unwrap().points.len(), 2);
+    }
+}
@@ -0,0 +1,1125 @@
+/// GeoPackage Layers
+///
+/// A GeoPackage is an SQLite database with a few tables describing its layers. The files
+/// are read and written here without SQLite: hulls go out as a fresh database of one
+/// polygon layer, and points come in from the b-trees of an existing one.
+use crate::crs::Crs;
+use crate::io::{HullSink, PointSource};
+use crate::point::{BoundingBox, Point};
+use crate::Orientation;
+
+use std::io::{self, Read, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// page size of written files
+const PAGE_SIZE: usize = 4096;
+
+/// `GPKG`, the SQLite application id of GeoPackages
+const APPLICATION_ID: u32 = 0x4750_4B47;
+
+/// the GeoPackage version written, 1.3.0
+const USER_VERSION: u32 = 10300;
+
+/// The spatial reference system of a layer, a row of `gpkg_spatial_ref_sys`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpatialRef {
+    /// id the layer refers to the system by
+    pub srs_id: i32,
+    /// human readable name
+    pub name: String,
+    /// authority defining the system, e.g. `EPSG`
+    pub organization: String,
+    /// id of the system at that authority
+    pub organization_coordsys_id: i32,
+    /// well-known text definition
+    pub definition: String,
+}
+
+impl SpatialRef {
+    /// Coordinates in no particular reference system, GeoPackage's srs -1.
+    pub fn undefined() -> Self {
+        SpatialRef {
+            srs_id: -1,
+            name: "Undefined cartesian SRS".to_string(),
+            organization: "NONE".to_string(),
+            organization_coordsys_id: -1,
+            definition: "undefined".to_string(),
+        }
+    }
+
+    /// Longitude and latitude in degrees on WGS 84, EPSG:4326.
+    pub fn wgs84() -> Self {
+        SpatialRef {
+            srs_id: 4326,
+            name: "WGS 84 geodetic".to_string(),
+            organization: "EPSG".to_string(),
+            organization_coordsys_id: 4326,
+            definition: concat!(
+                r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,"#,
+                r#"AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0,"#,
+                r#"AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,"#,
+                r#"AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4326"]]"#
+            )
+            .to_string(),
+        }
+    }
+
+    /// The system of `crs`, without a definition beyond its identifier.
+    ///
+    /// EPSG systems take their code as id, as GeoPackages conventionally number them, and
+    /// EPSG:4326 is [`SpatialRef::wgs84`]; systems of other authorities take id 100000.
+    pub fn from_crs(crs: &Crs) -> Self {
+        match crs.epsg_code() {
+            Some(4326) => SpatialRef::wgs84(),
+            code => SpatialRef {
+                srs_id: code
+                    .and_then(|code| i32::try_from(code).ok())
+                    .unwrap_or(100_000),
+                name: crs.to_string(),
+                organization: crs.authority().to_string(),
+                organization_coordsys_id: crs.code().parse().unwrap_or(0),
+                definition: "undefined".to_string(),
+            },
+        }
+    }
+
+    /// the system as a [`Crs`], `None` for the undefined systems
+    pub fn crs(&self) -> Option<Crs> {
+        (self.organization_coordsys_id > 0)
+            .then(|| {
+                Crs::parse(&format!(
+                    "{}:{}",
+                    self.organization, self.organization_coordsys_id
+                ))
+            })
+            .flatten()
+    }
+
+    /// the row of the system in `gpkg_spatial_ref_sys`, keyed by its id
+    fn row(&self, description: &str) -> (i64, Vec<u8>) {
+        let values = [
+            Value::Text(self.name.clone()),
+            Value::Null,
+            Value::Text(self.organization.clone()),
+            Value::Integer(self.organization_coordsys_id as i64),
+            Value::Text(self.definition.clone()),
+            Value::Text(description.to_string()),
+        ];
+        (self.srs_id as i64, record(&values))
+    }
+}
+
+/// Writes hulls as the polygon layer of a new GeoPackage.
+///
+/// Each hull becomes a feature with `fid` from 1 in the order given, its ring closed and
+/// counterclockwise. The file holds the layer and the tables GeoPackage requires, without
+/// a spatial index; GIS software builds one on demand.
+///
+/// # Arguments
+///
+/// * `sink` - where to write the file, e.g. a path ending in `.gpkg`.
+/// * `layer` - the name of the layer, also its table.
+/// * `hulls` - the hulls, open or closed.
+/// * `srs` - the spatial reference system of the coordinates.
+///
+/// # Returns
+///
+/// Returns the error of writing to `sink`.
+pub fn write_gpkg(
+    sink: impl HullSink,
+    layer: &str,
+    hulls: &[Vec<Point>],
+    srs: &SpatialRef,
+) -> io::Result<()> {
+    let mut pages = Pages::new();
+
+    let mut systems = vec![
+        SpatialRef::undefined().row("undefined cartesian coordinate reference system"),
+        SpatialRef {
+            srs_id: 0,
+            name: "Undefined geographic SRS".to_string(),
+            organization_coordsys_id: 0,
+            ..SpatialRef::undefined()
+        }
+        .row("undefined geographic coordinate reference system"),
+        SpatialRef::wgs84()
+            .row("longitude/latitude coordinates in decimal degrees on the WGS 84 spheroid"),
+    ];
+    if ![-1, 0, 4326].contains(&srs.srs_id) {
+        systems.push(srs.row(""));
+        systems.sort_by_key(|&(id, _)| id);
+    }
+    let systems_root = pages.table(systems, 0);
+
+    let points: Vec<Point> = hulls.iter().flatten().cloned().collect();
+    let bbox = BoundingBox::from_points(&points);
+    let bounds = if points.is_empty() {
+        [Value::Null, Value::Null, Value::Null, Value::Null]
+    } else {
+        [bbox.min_x, bbox.min_y, bbox.max_x, bbox.max_y].map(Value::Real)
+    };
+    let srs_id = srs.srs_id as i64;
+    let mut contents = vec![
+        Value::Text(layer.to_string()),
+        Value::Text("features".to_string()),
+        Value::Text(layer.to_string()),
+        Value::Text(String::new()),
+        Value::Text(timestamp()),
+    ];
+    contents.extend(bounds);
+    contents.push(Value::Integer(srs_id));
+    let contents_root = pages.table(vec![(1, record(&contents))], 0);
+    let contents_keys = [
+        pages.index(record(&[Value::Text(layer.to_string()), Value::Integer(1)])),
+        pages.index(record(&[Value::Text(layer.to_string()), Value::Integer(1)])),
+    ];
+
+    let columns = [
+        Value::Text(layer.to_string()),
+        Value::Text("geom".to_string()),
+        Value::Text("POLYGON".to_string()),
+        Value::Integer(srs_id),
+        Value::Integer(0),
+        Value::Integer(0),
+    ];
+    let columns_root = pages.table(vec![(1, record(&columns))], 0);
+    let columns_keys = [
+        pages.index(record(&[
+            Value::Text(layer.to_string()),
+            Value::Text("geom".to_string()),
+            Value::Integer(1),
+        ])),
+        pages.index(record(&[Value::Text(layer.to_string()), Value::Integer(1)])),
+    ];
+
+    let features = hulls
+        .iter()
+        .enumerate()
+        .map(|(i, hull)| {
+            let geometry = Value::Blob(geometry(hull, srs.srs_id));
+            (i as i64 + 1, record(&[Value::Null, geometry]))
+        })
+        .collect();
+    let features_root = pages.table(features, 0);
+
+    let quoted = format!("\"{}\"", layer.replace('"', "\"\""));
+    let index = |n: usize, table: &str| format!("sqlite_autoindex_{}_{}", table, n);
+    let schema: Vec<(&str, String, &str, u32, Option<String>)> = vec![
+        (
+            "table",
+            "gpkg_spatial_ref_sys".to_string(),
+            "gpkg_spatial_ref_sys",
+            systems_root,
+            Some(SPATIAL_REF_SYS.to_string()),
+        ),
+        (
+            "table",
+            "gpkg_contents".to_string(),
+            "gpkg_contents",
+            contents_root,
+            Some(CONTENTS.to_string()),
+        ),
+        (
+            "index",
+            index(1, "gpkg_contents"),
+            "gpkg_contents",
+            contents_keys[0],
+            None,
+        ),
+        (
+            "index",
+            index(2, "gpkg_contents"),
+            "gpkg_contents",
+            contents_keys[1],
+            None,
+        ),
+        (
+            "table",
+            "gpkg_geometry_columns".to_string(),
+            "gpkg_geometry_columns",
+            columns_root,
+            Some(GEOMETRY_COLUMNS.to_string()),
+        ),
+        (
+            "index",
+            index(1, "gpkg_geometry_columns"),
+            "gpkg_geometry_columns",
+            columns_keys[0],
+            None,
+        ),
+        (
+            "index",
+            index(2, "gpkg_geometry_columns"),
+            "gpkg_geometry_columns",
+            columns_keys[1],
+            None,
+        ),
+        (
+            "table",
+            layer.to_string(),
+            layer,
+            features_root,
+            Some(format!(
+                "CREATE TABLE {} (fid INTEGER PRIMARY KEY NOT NULL, geom POLYGON)",
+                quoted
+            )),
+        ),
+    ];
+    let schema = schema
+        .into_iter()
+        .enumerate()
+        .map(|(i, (kind, name, table, root, sql))| {
+            let entry = record(&[
+                Value::Text(kind.to_string()),
+                Value::Text(name),
+                Value::Text(table.to_string()),
+                Value::Integer(root as i64),
+                sql.map_or(Value::Null, Value::Text),
+            ]);
+            (i as i64 + 1, entry)
+        })
+        .collect();
+    pages.table(schema, 100);
+
+    let mut writer = sink.create()?;
+    writer.write_all(&pages.into_bytes())?;
+    writer.flush()
+}
+
+/// Reads the points of a GeoPackage feature layer.
+///
+/// Point and multipoint features give their points, the other geometry types their
+/// vertices; Z and M values are ignored. Only the main database file is read, so changes
+/// still in a write-ahead log (`-wal` file) are missed.
+///
+/// # Arguments
+///
+/// * `source` - the `.gpkg` file, or its contents.
+/// * `layer` - the layer to read, or `None` for the first feature layer.
+///
+/// # Returns
+///
+/// Returns the points in table order, with the `fid` of their feature as id, or an
+/// `InvalidData` error for a file that is not a GeoPackage or has no such layer.
+pub fn read_gpkg_points(source: impl PointSource, layer: Option<&str>) -> io::Result<Vec<Point>> {
+    let mut bytes = Vec::new();
+    source.open()?.read_to_end(&mut bytes)?;
+    let database = Database::new(&bytes)?;
+
+    let schema = database.rows(1)?;
+    let entry = |name: &str| {
+        schema
+            .iter()
+            .find_map(|(_, values)| match values.as_slice() {
+                [Value::Text(kind), Value::Text(entry), _, Value::Integer(root), sql, ..]
+                    if kind == "table" && entry == name =>
+                {
+                    let sql = match sql {
+                        Value::Text(sql) => sql.clone(),
+                        _ => String::new(),
+                    };
+                    Some((*root as u32, sql))
+                }
+                _ => None,
+            })
+    };
+
+    let (columns_root, _) =
+        entry("gpkg_geometry_columns").ok_or_else(|| invalid("not a GeoPackage"))?;
+    let (table, column) = database
+        .rows(columns_root)?
+        .into_iter()
+        .find_map(|(_, values)| match values.as_slice() {
+            [Value::Text(table), Value::Text(column), ..]
+                if layer.is_none_or(|layer| layer == table) =>
+            {
+                Some((table.clone(), column.clone()))
+            }
+            _ => None,
+        })
+        .ok_or_else(|| invalid("no such feature layer"))?;
+    let (root, sql) = entry(&table).ok_or_else(|| invalid("no such feature layer"))?;
+    let position = column_names(&sql)
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(&column))
+        .ok_or_else(|| invalid("no geometry column"))?;
+
+    let mut points = Vec::new();
+    for (fid, values) in database.rows(root)? {
+        if let Some(Value::Blob(blob)) = values.get(position) {
+            let start = points.len();
+            geometry_points(blob, &mut points)?;
+            for point in &mut points[start..] {
+                point.id = fid as u64;
+            }
+        }
+    }
+    Ok(points)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// the current time as GeoPackage timestamps it, e.g. `2024-01-31T12:00:00.000Z`
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as i64);
+    let (days, millis) = (now.div_euclid(86_400_000), now.rem_euclid(86_400_000));
+    // civil date of a day count since 1970-01-01, after Howard Hinnant
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// A hull as a GeoPackage geometry blob: a header with the srs and envelope, then the
+/// polygon as little-endian well-known binary.
+fn geometry(hull: &[Point], srs_id: i32) -> Vec<u8> {
+    let ring = crate::close_ring(crate::orient(hull.to_vec(), Orientation::CounterClockwise));
+    let bbox = BoundingBox::from_points(&ring);
+
+    let mut blob = Vec::with_capacity(8 + 32 + 13 + 16 * ring.len());
+    blob.extend(b"GP");
+    blob.push(0);
+    if ring.is_empty() {
+        // little endian, no envelope, empty
+        blob.push(0b0001_0001);
+        blob.extend(srs_id.to_le_bytes());
+    } else {
+        // little endian, an xy envelope
+        blob.push(0b0000_0011);
+        blob.extend(srs_id.to_le_bytes());
+        for v in [bbox.min_x, bbox.max_x, bbox.min_y, bbox.max_y] {
+            blob.extend(v.to_le_bytes());
+        }
+    }
+    blob.push(1);
+    blob.extend(3u32.to_le_bytes());
+    if ring.is_empty() {
+        blob.extend(0u32.to_le_bytes());
+    } else {
+        blob.extend(1u32.to_le_bytes());
+        blob.extend((ring.len() as u32).to_le_bytes());
+        for p in &ring {
+            blob.extend(p.x.to_le_bytes());
+            blob.extend(p.y.to_le_bytes());
+        }
+    }
+    blob
+}
+
+/// Appends the points of a GeoPackage geometry blob to `points`, with id 0.
+fn geometry_points(blob: &[u8], points: &mut Vec<Point>) -> io::Result<()> {
+    if blob.len() < 8 || &blob[..2] != b"GP" {
+        return Err(invalid("not a GeoPackage geometry"));
+    }
+    let flags = blob[3];
+    if flags & 0b0010_0000 != 0 {
+        return Err(invalid("unsupported extended GeoPackage geometry"));
+    }
+    if flags & 0b0001_0000 != 0 {
+        return Ok(());
+    }
+    let envelope = match (flags >> 1) & 0b111 {
+        0 => 0,
+        1 => 32,
+        2 | 3 => 48,
+        4 => 64,
+        _ => return Err(invalid("invalid GeoPackage geometry envelope")),
+    };
+    let mut wkb = Wkb {
+        bytes: blob,
+        at: 8 + envelope,
+        little_endian: true,
+    };
+    wkb.geometry(points)
+}
+
+/// A cursor over well-known binary.
+struct Wkb<'a> {
+    bytes: &'a [u8],
+    at: usize,
+    little_endian: bool,
+}
+
+impl Wkb<'_> {
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let bytes = self
+            .bytes
+            .get(self.at..self.at + N)
+            .ok_or_else(|| invalid("truncated geometry"))?;
+        self.at += N;
+        let mut array: [u8; N] = bytes.try_into().unwrap();
+        if !self.little_endian {
+            array.reverse();
+        }
+        Ok(array)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn f64(&mut self) -> io::Result<f64> {
+        self.take().map(f64::from_le_bytes)
+    }
+
+    /// `count` points of `dimensions` coordinates, of which x and y are kept
+    fn points(&mut self, count: u32, dimensions: usize, points: &mut Vec<Point>) -> io::Result<()> {
+        for _ in 0..count {
+            let (x, y) = (self.f64()?, self.f64()?);
+            for _ in 2..dimensions {
+                self.f64()?;
+            }
+            // an empty point is written as NaN coordinates
+            if !(x.is_nan() && y.is_nan()) {
+                points.push(Point { x, y, id: 0 });
+            }
+        }
+        Ok(())
+    }
+
+    fn geometry(&mut self, points: &mut Vec<Point>) -> io::Result<()> {
+        self.little_endian = match self.bytes.get(self.at) {
+            Some(0) => false,
+            Some(1) => true,
+            _ => return Err(invalid("invalid geometry byte order")),
+        };
+        self.at += 1;
+        let kind = self.u32()?;
+        // ISO codes: 1000s for Z, 2000s for M, 3000s for ZM
+        let dimensions = match kind / 1000 {
+            0 => 2,
+            1 | 2 => 3,
+            3 => 4,
+            _ => return Err(invalid("unsupported geometry type")),
+        };
+        match kind % 1000 {
+            1 => self.points(1, dimensions, points),
+            2 => {
+                let count = self.u32()?;
+                self.points(count, dimensions, points)
+            }
+            3 => {
+                for _ in 0..self.u32()? {
+                    let count = self.u32()?;
+                    self.points(count, dimensions, points)?;
+                }
+                Ok(())
+            }
+            4..=7 => {
+                for _ in 0..self.u32()? {
+                    self.geometry(points)?;
+                }
+                Ok(())
+            }
+            _ => Err(invalid("unsupported geometry type")),
+        }
+    }
+}
+
+/// The names of the columns of a `CREATE TABLE` statement, in order.
+fn column_names(sql: &str) -> Vec<String> {
+    let Some(open) = sql.find('(') else {
+        return Vec::new();
+    };
+    // split the definitions at the commas outside parentheses and quotes
+    let mut definitions = Vec::new();
+    let (mut depth, mut quote, mut start) = (0, None, open + 1);
+    for (i, c) in sql.char_indices().skip_while(|&(i, _)| i <= open) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '`' | '\'') => quote = Some(c),
+            (None, '[') => quote = Some(']'),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => {
+                definitions.push(&sql[start..i]);
+                break;
+            }
+            (None, ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                definitions.push(&sql[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    definitions
+        .iter()
+        .map(|definition| definition.trim())
+        .filter(|definition| {
+            let first = definition
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .to_ascii_uppercase();
+            !["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"].contains(&first.as_str())
+        })
+        .map(|definition| {
+            let mut chars = definition.chars();
+            match chars.next() {
+                Some(q @ ('"' | '`' | '[')) => {
+                    let close = if q == '[' { ']' } else { q };
+                    let name: String = chars.take_while(|&c| c != close).collect();
+                    name
+                }
+                _ => definition
+                    .split(|c: char| c.is_whitespace())
+                    .next()
+                    .unwrap_or("")
+                    .to_string(),
+            }
+        })
+        .collect()
+}
+
+/// A value of an SQLite record.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+/// Appends `value` as an SQLite varint: big endian, 7 bits a byte, all 8 in the ninth.
+fn varint(value: u64, out: &mut Vec<u8>) {
+    if value >> 56 != 0 {
+        for shift in (1..=8).rev() {
+            out.push(0x80 | ((value >> (8 + 7 * (shift - 1))) & 0x7F) as u8);
+        }
+        out.push(value as u8);
+        return;
+    }
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest != 0 {
+        groups.push(0x80 | (rest & 0x7F) as u8);
+        rest >>= 7;
+    }
+    out.extend(groups.iter().rev());
+}
+
+/// The varint at the start of `bytes` and its length.
+fn read_varint(bytes: &[u8]) -> io::Result<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().enumerate().take(9) {
+        if i == 8 {
+            return Ok(((value << 8) | byte as u64, 9));
+        }
+        value = (value << 7) | (byte & 0x7F) as u64;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(invalid("truncated varint"))
+}
+
+/// The SQLite record of `values`: a header of serial types, then the values.
+fn record(values: &[Value]) -> Vec<u8> {
+    let (mut types, mut body) = (Vec::new(), Vec::new());
+    for value in values {
+        let serial = match value {
+            Value::Null => 0,
+            Value::Integer(0) => 8,
+            Value::Integer(1) => 9,
+            Value::Integer(v) => {
+                let (serial, size) = match *v {
+                    -0x80..=0x7F => (1, 1),
+                    -0x8000..=0x7FFF => (2, 2),
+                    -0x80_0000..=0x7F_FFFF => (3, 3),
+                    -0x8000_0000..=0x7FFF_FFFF => (4, 4),
+                    -0x8000_0000_0000..=0x7FFF_FFFF_FFFF => (5, 6),
+                    _ => (6, 8),
+                };
+                body.extend(&v.to_be_bytes()[8 - size..]);
+                serial
+            }
+            Value::Real(v) => {
+                body.extend(v.to_be_bytes());
+                7
+            }
+            Value::Text(text) => {
+                body.extend(text.as_bytes());
+                2 * text.len() as u64 + 13
+            }
+            Value::Blob(blob) => {
+                body.extend(blob);
+                2 * blob.len() as u64 + 12
+            }
+        };
+        varint(serial, &mut types);
+    }
+
+    // the header size counts itself
+    let mut size = types.len() + 1;
+    let mut header = Vec::new();
+    loop {
+        header.clear();
+        varint(size as u64, &mut header);
+        if header.len() + types.len() == size {
+            break;
+        }
+        size = header.len() + types.len();
+    }
+    header.extend(types);
+    header.extend(body);
+    header
+}
+
+/// The values of an SQLite record.
+fn parse_record(payload: &[u8]) -> io::Result<Vec<Value>> {
+    let (header_size, mut at) = read_varint(payload)?;
+    let mut body = header_size as usize;
+    let mut values = Vec::new();
+    while at < header_size as usize {
+        let (serial, length) = read_varint(&payload[at..])?;
+        at += length;
+        let size = match serial {
+            0 | 8 | 9 | 10 | 11 => 0,
+            1..=4 => serial as usize,
+            5 => 6,
+            6 | 7 => 8,
+            _ => (serial as usize - 12) / 2,
+        };
+        let bytes = payload
+            .get(body..body + size)
+            .ok_or_else(|| invalid("truncated record"))?;
+        body += size;
+        let integer = || {
+            let mut be = if bytes[0] & 0x80 != 0 {
+                [0xFF; 8]
+            } else {
+                [0; 8]
+            };
+            be[8 - size..].copy_from_slice(bytes);
+            i64::from_be_bytes(be)
+        };
+        values.push(match serial {
+            0 | 10 | 11 => Value::Null,
+            8 => Value::Integer(0),
+            9 => Value::Integer(1),
+            1..=6 => Value::Integer(integer()),
+            7 => Value::Real(f64::from_be_bytes(bytes.try_into().unwrap())),
+            s if s % 2 == 0 => Value::Blob(bytes.to_vec()),
+            _ => Value::Text(String::from_utf8_lossy(bytes).into_owned()),
+        });
+    }
+    Ok(values)
+}
+
+/// The largest payload a table b-tree cell keeps on its page, for a page of `usable` bytes;
+/// `index` for index b-trees.
+fn max_local(usable: usize, index: bool) -> usize {
+    if index {
+        (usable - 12) * 64 / 255 - 23
+    } else {
+        usable - 35
+    }
+}
+
+/// How much of a payload of `size` bytes stays on the page, the rest going to overflow pages.
+fn local_size(size: usize, usable: usize, index: bool) -> usize {
+    let max = max_local(usable, index);
+    if size <= max {
+        return size;
+    }
+    let min = (usable - 12) * 32 / 255 - 23;
+    let local = min + (size - min) % (usable - 4);
+    if local <= max {
+        local
+    } else {
+        min
+    }
+}
+
+/// The pages of a database in the making; page `n` is `pages[n - 1]`.
+struct Pages {
+    pages: Vec<Vec<u8>>,
+}
+
+impl Pages {
+    /// the pages with page 1 set aside for the header and schema, written last
+    fn new() -> Self {
+        Pages {
+            pages: vec![vec![0; PAGE_SIZE]],
+        }
+    }
+
+    fn allocate(&mut self) -> u32 {
+        self.pages.push(vec![0; PAGE_SIZE]);
+        self.pages.len() as u32
+    }
+
+    /// the page at `number`
+    fn page(&mut self, number: u32) -> &mut Vec<u8> {
+        &mut self.pages[number as usize - 1]
+    }
+
+    /// `payload` in a cell, with the part that doesn't fit in overflow pages
+    fn cell(&mut self, prefix: Vec<u8>, payload: &[u8], index: bool) -> Vec<u8> {
+        let local = local_size(payload.len(), PAGE_SIZE, index);
+        let mut cell = prefix;
+        cell.extend(&payload[..local]);
+        if local < payload.len() {
+            let chunks: Vec<&[u8]> = payload[local..].chunks(PAGE_SIZE - 4).collect();
+            let numbers: Vec<u32> = chunks.iter().map(|_| self.allocate()).collect();
+            cell.extend(numbers[0].to_be_bytes());
+            for (i, chunk) in chunks.iter().enumerate() {
+                let next = numbers.get(i + 1).copied().unwrap_or(0);
+                let page = self.page(numbers[i]);
+                page[..4].copy_from_slice(&next.to_be_bytes());
+                page[4..4 + chunk.len()].copy_from_slice(chunk);
+            }
+        }
+        cell
+    }
+
+    /// Lays out `cells` on page `number` behind its b-tree header at `offset`.
+    fn write(
+        &mut self,
+        number: u32,
+        offset: usize,
+        kind: u8,
+        cells: &[Vec<u8>],
+        right: Option<u32>,
+    ) {
+        let page = self.page(number);
+        let header = if right.is_some() { 12 } else { 8 };
+        let mut content = PAGE_SIZE;
+        for (i, cell) in cells.iter().enumerate() {
+            content -= cell.len();
+            page[content..content + cell.len()].copy_from_slice(cell);
+            let pointer = offset + header + 2 * i;
+            page[pointer..pointer + 2].copy_from_slice(&(content as u16).to_be_bytes());
+        }
+        page[offset] = kind;
+        page[offset + 3..offset + 5].copy_from_slice(&(cells.len() as u16).to_be_bytes());
+        page[offset + 5..offset + 7].copy_from_slice(&((content % 65536) as u16).to_be_bytes());
+        if let Some(right) = right {
+            page[offset + 8..offset + 12].copy_from_slice(&right.to_be_bytes());
+        }
+    }
+
+    /// Writes a table b-tree of `rows` in rowid order, returning its root page.
+    ///
+    /// `offset` 100 writes the schema table onto page 1, which must then fit on it.
+    fn table(&mut self, rows: Vec<(i64, Vec<u8>)>, offset: usize) -> u32 {
+        // the leaves, with the largest rowid of each
+        let mut leaves: Vec<(Vec<Vec<u8>>, i64)> = vec![(Vec::new(), i64::MIN)];
+        let mut used = offset + 8;
+        for (rowid, payload) in rows {
+            let mut prefix = Vec::new();
+            varint(payload.len() as u64, &mut prefix);
+            varint(rowid as u64, &mut prefix);
+            let cell = self.cell(prefix, &payload, false);
+            if used + cell.len() + 2 > PAGE_SIZE {
+                assert!(offset == 0, "the schema does not fit on the first page");
+                leaves.push((Vec::new(), i64::MIN));
+                used = 8;
+            }
+            used += cell.len() + 2;
+            let leaf = leaves.last_mut().unwrap();
+            leaf.0.push(cell);
+            leaf.1 = rowid;
+        }
+
+        let mut level: Vec<(u32, i64)> = leaves
+            .iter()
+            .map(|(cells, last)| {
+                let number = if offset == 100 { 1 } else { self.allocate() };
+                self.write(number, offset, 0x0D, cells, None);
+                (number, *last)
+            })
+            .collect();
+        // interior pages over the level below until one page is left
+        while level.len() > 1 {
+            let mut parents = Vec::new();
+            for children in level.chunks(PAGE_SIZE / 16) {
+                let (right, last) = *children.last().unwrap();
+                let cells: Vec<Vec<u8>> = children[..children.len() - 1]
+                    .iter()
+                    .map(|&(child, key)| {
+                        let mut cell = child.to_be_bytes().to_vec();
+                        varint(key as u64, &mut cell);
+                        cell
+                    })
+                    .collect();
+                let number = self.allocate();
+                self.write(number, 0, 0x05, &cells, Some(right));
+                parents.push((number, last));
+            }
+            level = parents;
+        }
+        level[0].0
+    }
+
+    /// Writes an index b-tree over a single key, returning its page.
+    fn index(&mut self, key: Vec<u8>) -> u32 {
+        let mut prefix = Vec::new();
+        varint(key.len() as u64, &mut prefix);
+        let cell = self.cell(prefix, &key, true);
+        let number = self.allocate();
+        self.write(number, 0, 0x0A, &[cell], None);
+        number
+    }
+
+    /// The database file: the header on page 1 and the pages.
+    fn into_bytes(mut self) -> Vec<u8> {
+        let count = self.pages.len() as u32;
+        let header = &mut self.pages[0][..100];
+        header[..16].copy_from_slice(b"SQLite format 3\0");
+        header[16..18].copy_from_slice(&(PAGE_SIZE as u16).to_be_bytes());
+        // legacy journal, no reserved bytes, the fixed payload fractions
+        header[18..24].copy_from_slice(&[1, 1, 0, 64, 32, 32]);
+        let fields: [(usize, u32); 9] = [
+            (24, 1),
+            (28, count),
+            (40, 1),
+            (44, 4),
+            (56, 1),
+            (60, USER_VERSION),
+            (68, APPLICATION_ID),
+            (92, 1),
+            (96, 3_045_000),
+        ];
+        for (at, value) in fields {
+            header[at..at + 4].copy_from_slice(&value.to_be_bytes());
+        }
+        self.pages.concat()
+    }
+}
+
+/// An SQLite database file being read.
+struct Database<'a> {
+    bytes: &'a [u8],
+    page_size: usize,
+    usable: usize,
+}
+
+impl<'a> Database<'a> {
+    fn new(bytes: &'a [u8]) -> io::Result<Self> {
+        if bytes.len() < 100 || &bytes[..16] != b"SQLite format 3\0" {
+            return Err(invalid("not a GeoPackage"));
+        }
+        let page_size = match u16::from_be_bytes([bytes[16], bytes[17]]) {
+            1 => 65536,
+            size => size as usize,
+        };
+        let usable = page_size - bytes[20] as usize;
+        if page_size < 512 || usable < 480 {
+            return Err(invalid("invalid SQLite page size"));
+        }
+        Ok(Database {
+            bytes,
+            page_size,
+            usable,
+        })
+    }
+
+    fn page(&self, number: u32) -> io::Result<&'a [u8]> {
+        let start = (number as usize)
+            .checked_sub(1)
+            .ok_or_else(|| invalid("invalid page number"))?
+            * self.page_size;
+        self.bytes
+            .get(start..start + self.page_size)
+            .ok_or_else(|| invalid("truncated database"))
+    }
+
+    /// The rowids and values of the rows of the table b-tree rooted at `root`, in order.
+    fn rows(&self, root: u32) -> io::Result<Vec<(i64, Vec<Value>)>> {
+        let mut rows = Vec::new();
+        let mut pending = vec![root];
+        let mut visited = 0;
+        // children are visited left to right, so pushed right to left
+        while let Some(number) = pending.pop() {
+            visited += 1;
+            if visited > self.bytes.len() / self.page_size {
+                return Err(invalid("cyclic b-tree"));
+            }
+            let page = self.page(number)?;
+            let offset = if number == 1 { 100 } else { 0 };
+            let header = page
+                .get(offset..offset + 12)
+                .ok_or_else(|| invalid("truncated page"))?;
+            let count = u16::from_be_bytes([header[3], header[4]]) as usize;
+            let pointers = offset + if header[0] == 0x05 { 12 } else { 8 };
+            let cell = |i: usize| -> io::Result<&'a [u8]> {
+                let at = pointers + 2 * i;
+                let start = u16::from_be_bytes(
+                    page.get(at..at + 2)
+                        .ok_or_else(|| invalid("truncated page"))?
+                        .try_into()
+                        .unwrap(),
+                ) as usize;
+                page.get(start..).ok_or_else(|| invalid("invalid cell"))
+            };
+            match header[0] {
+                0x05 => {
+                    pending.push(u32::from_be_bytes(header[8..12].try_into().unwrap()));
+                    for i in (0..count).rev() {
+                        let child = cell(i)?.get(..4).ok_or_else(|| invalid("invalid cell"))?;
+                        pending.push(u32::from_be_bytes(child.try_into().unwrap()));
+                    }
+                }
+                0x0D => {
+                    for i in 0..count {
+                        let cell = cell(i)?;
+                        let (size, a) = read_varint(cell)?;
+                        let (rowid, b) = read_varint(&cell[a..])?;
+                        let payload = self.payload(&cell[a + b..], size as usize)?;
+                        rows.push((rowid as i64, parse_record(&payload)?));
+                    }
+                }
+                _ => return Err(invalid("not a table b-tree page")),
+            }
+        }
+        Ok(rows)
+    }
+
+    /// The payload of `size` bytes starting in `cell`, with its overflow pages.
+    fn payload(&self, cell: &[u8], size: usize) -> io::Result<Vec<u8>> {
+        let local = local_size(size, self.usable, false);
+        let mut payload = cell
+            .get(..local)
+            .ok_or_else(|| invalid("invalid cell"))?
+            .to_vec();
+        if local < size {
+            let mut next = u32::from_be_bytes(
+                cell.get(local..local + 4)
+                    .ok_or_else(|| invalid("invalid cell"))?
+                    .try_into()
+                    .unwrap(),
+            );
+            while payload.len() < size {
+                let page = self.page(next)?;
+                let take = (size - payload.len()).min(self.usable - 4);
+                payload.extend(&page[4..4 + take]);
+                next = u32::from_be_bytes(page[..4].try_into().unwrap());
+            }
+        }
+        Ok(payload)
+    }
+}
+
+/// The statements creating the GeoPackage tables, as the specification gives them.
+const SPATIAL_REF_SYS: &str = "CREATE TABLE gpkg_spatial_ref_sys (srs_name TEXT NOT NULL, \
+    srs_id INTEGER NOT NULL PRIMARY KEY, organization TEXT NOT NULL, \
+    organization_coordsys_id INTEGER NOT NULL, definition TEXT NOT NULL, description TEXT)";
+const CONTENTS: &str = "CREATE TABLE gpkg_contents (table_name TEXT NOT NULL PRIMARY KEY, \
+    data_type TEXT NOT NULL, identifier TEXT UNIQUE, description TEXT DEFAULT '', \
+    last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')), \
+    min_x DOUBLE, min_y DOUBLE, max_x DOUBLE, max_y DOUBLE, srs_id INTEGER, \
+    CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id))";
+const GEOMETRY_COLUMNS: &str = "CREATE TABLE gpkg_geometry_columns (table_name TEXT NOT NULL, \
+    column_name TEXT NOT NULL, geometry_type_name TEXT NOT NULL, srs_id INTEGER NOT NULL, \
+    z TINYINT NOT NULL, m TINYINT NOT NULL, \
+    CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name), \
+    CONSTRAINT uk_gc_table_name UNIQUE (table_name), \
+    CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name), \
+    CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys (srs_id))";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::{FromReader, IntoWriter};
+
+    #[test]
+    fn test_gpkg_round_trip() {
+        // enough hulls for several leaves, and one long enough to overflow its page
+        let square = |i: usize| {
+            let (x, y) = ((i % 20) as f64 * 10.0, (i / 20) as f64 * 10.0);
+            [(0.0, 0.0), (0.0, 5.0), (5.0, 5.0), (5.0, 0.0)]
+                .iter()
+                .map(|&(dx, dy)| Point {
+                    x: x + dx,
+                    y: y + dy,
+                    id: 0,
+                })
+                .collect::<Vec<Point>>()
+        };
+        let mut hulls: Vec<Vec<Point>> = (0..300).map(square).collect();
+        hulls.push(
+            (0..1000)
+                .map(|i| {
+                    let t = i as f64 / 1000.0 * std::f64::consts::TAU;
+                    Point {
+                        x: 100.0 * t.cos(),
+                        y: 100.0 * t.sin(),
+                        id: 0,
+                    }
+                })
+                .collect(),
+        );
+
+        let mut bytes = Vec::new();
+        write_gpkg(
+            IntoWriter(&mut bytes),
+            "hulls",
+            &hulls,
+            &SpatialRef::wgs84(),
+        )
+        .unwrap();
+        assert_eq!(bytes.len() % PAGE_SIZE, 0);
+        assert_eq!(&bytes[68..72], b"GPKG");
+
+        let points = read_gpkg_points(FromReader(&bytes[..]), Some("hulls")).unwrap();
+        assert_eq!(points.len(), 300 * 5 + 1001);
+        // squares come back closed and counterclockwise, with their fid
+        let first: Vec<(f64, f64, u64)> = points[..5].iter().map(|p| (p.x, p.y, p.id)).collect();
+        assert_eq!(
+            first,
+            [
+                (5.0, 0.0, 1),
+                (5.0, 5.0, 1),
+                (0.0, 5.0, 1),
+                (0.0, 0.0, 1),
+                (5.0, 0.0, 1)
+            ]
+        );
+        assert!(points[1500..].iter().all(|p| p.id == 301));
+        assert_eq!(
+            read_gpkg_points(FromReader(&bytes[..]), None)
+                .unwrap()
+                .len(),
+            points.len()
+        );
+        assert!(read_gpkg_points(FromReader(&bytes[..]), Some("other")).is_err());
+
+        let utm = SpatialRef::from_crs(&Crs::epsg(32633));
+        assert_eq!((utm.srs_id, utm.organization_coordsys_id), (32633, 32633));
+        assert_eq!(utm.crs(), Some(Crs::epsg(32633)));
+        assert_eq!(SpatialRef::from_crs(&Crs::epsg(4326)), SpatialRef::wgs84());
+        assert_eq!(SpatialRef::undefined().crs(), None);
+        assert!(read_gpkg_points(FromReader(&b"not a database"[..]), None).is_err());
+
+        assert_eq!(
+            column_names(
+                "CREATE TABLE \"a b\" (fid INTEGER PRIMARY KEY, [geom] POINT, \
+                 \"x y\" DOUBLE CHECK (\"x y\" > 0), CONSTRAINT u UNIQUE (fid))"
+            ),
+            ["fid", "geom", "x y"]
+        );
+    }
+}
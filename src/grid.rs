@@ -0,0 +1,397 @@
+/// Grid-Based Approximate Hull
+use crate::error::ConcaveHullError;
+use crate::point::{BoundingBox, Point};
+
+use std::collections::HashMap;
+
+/// Approximates the hull of a large point cloud from a grid of occupied cells.
+///
+/// The points are binned into square cells of side `resolution`, and the outline of the
+/// occupied cells is traced along the cell edges. Binning is a single O(n) pass, and the
+/// rest of the work is proportional to the number of cells, which makes this practical for
+/// tens of millions of points where the exact algorithm is too slow. The hull follows the
+/// data to within one cell: it contains every point of the traced region, runs at most
+/// `resolution` outside it, and closes holes in the data.
+///
+/// Two cells meeting only at a corner are joined by filling one of their neighbours, so
+/// the outline never touches itself. If the occupied cells still form several separate
+/// regions, the one holding the most points is traced.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `resolution` - side length of the grid cells. The grid has one cell per
+///   `resolution` x `resolution` square of the bounding box, so too fine a resolution costs
+///   memory without improving the hull beyond the exact one.
+///
+/// # Returns
+///
+/// Returns the outline as a closed counter-clockwise ring of cell corners, each carrying
+/// the id of a point in the occupied cell it bounds. Returns an empty vector if there are no
+/// points or `resolution` is not positive.
+pub fn grid_hull(points: &[Point], resolution: f64) -> Vec<Point> {
+    if points.is_empty() || !(resolution > 0.0 && resolution.is_finite()) {
+        return Vec::new();
+    }
+
+    let bbox = BoundingBox::from_points(points);
+    let nx = (bbox.width() / resolution) as usize + 1;
+    let ny = (bbox.height() / resolution) as usize + 1;
+    let cell_of = |p: &Point| {
+        let i = (((p.x - bbox.min_x) / resolution) as usize).min(nx - 1);
+        let j = (((p.y - bbox.min_y) / resolution) as usize).min(ny - 1);
+        j * nx + i
+    };
+
+    // points per cell, and the id of one point in each occupied cell
+    let mut count = vec![0usize; nx * ny];
+    let mut ids = vec![0u64; nx * ny];
+    for p in points {
+        let c = cell_of(p);
+        if count[c] == 0 {
+            ids[c] = p.id;
+        }
+        count[c] += 1;
+    }
+    trace(
+        &count,
+        &mut ids,
+        nx,
+        ny,
+        (bbox.min_x, bbox.min_y),
+        resolution,
+    )
+}
+
+/// An occupied cell of a sparse grid, see [`aggregate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridCell {
+    /// the mean position of the cell's points, with the id of its first point
+    pub point: Point,
+    /// the number of points in the cell
+    pub count: usize,
+}
+
+/// Aggregates points into the occupied cells of a grid.
+///
+/// Only occupied cells are stored, so the memory is proportional to the number of points
+/// however large and sparse the extent.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `resolution` - side length of the grid cells, which are aligned with the origin.
+///
+/// # Returns
+///
+/// Returns the occupied cells row by row from the lower left. Returns an empty vector if
+/// `resolution` is not positive.
+pub fn aggregate(points: &[Point], resolution: f64) -> Vec<GridCell> {
+    if !(resolution > 0.0 && resolution.is_finite()) {
+        return Vec::new();
+    }
+
+    // coordinate sums, point count and first id for each cell
+    let mut cells: HashMap<(i64, i64), (f64, f64, usize, u64)> = HashMap::new();
+    for p in points {
+        let key = (
+            (p.y / resolution).floor() as i64,
+            (p.x / resolution).floor() as i64,
+        );
+        let cell = cells.entry(key).or_insert((0.0, 0.0, 0, p.id));
+        cell.0 += p.x;
+        cell.1 += p.y;
+        cell.2 += 1;
+    }
+
+    let mut cells: Vec<_> = cells.into_iter().collect();
+    cells.sort_unstable_by_key(|&(key, _)| key);
+    cells
+        .into_iter()
+        .map(|(_, (sx, sy, count, id))| GridCell {
+            point: Point {
+                x: sx / count as f64,
+                y: sy / count as f64,
+                id,
+            },
+            count,
+        })
+        .collect()
+}
+
+/// Computes the concave hull of the grid cells holding enough points.
+///
+/// The points are aggregated with [`aggregate`], the cells with fewer than `min_count`
+/// points are dropped, and the concave hull of the mean positions of the remaining cells is
+/// computed. Dense data collapses to one point per cell, which cuts the work of the walk by
+/// the average count per cell, and sparse noise falls below the count threshold: a cell
+/// must hold `min_count` points in a `resolution` x `resolution` square to count.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `resolution` - side length of the grid cells.
+/// * `min_count` - the fewest points a cell needs to be hulled.
+/// * `k` - number of nearest neighbors, among the cells.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull of the cells as [`crate::concave_hull`] does, its vertices the mean
+/// positions of cells with the id of their first point, or the error for the kept cells.
+pub fn concave_hull_aggregated(
+    points: &[Point],
+    resolution: f64,
+    min_count: usize,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let cells: Vec<Point> = aggregate(points, resolution)
+        .into_iter()
+        .filter(|cell| cell.count >= min_count)
+        .map(|cell| cell.point)
+        .collect();
+    crate::concave_hull_borrowed(&cells, k, iterate)
+}
+
+/// Traces the outline of a grid of cells.
+///
+/// # Arguments
+///
+/// * `count` - points per cell, row by row from the lower left.
+/// * `ids` - the id of a point in each occupied cell.
+/// * `nx`, `ny` - cells per row and number of rows.
+/// * `origin` - the lower left corner of the grid.
+/// * `resolution` - side length of the cells.
+///
+/// # Returns
+///
+/// Returns the outline of the region holding the most points, as [`grid_hull`] does.
+pub(crate) fn trace(
+    count: &[usize],
+    ids: &mut [u64],
+    nx: usize,
+    ny: usize,
+    origin: (f64, f64),
+    resolution: f64,
+) -> Vec<Point> {
+    let mut occupied: Vec<bool> = count.iter().map(|&c| c > 0).collect();
+    fill_diagonals(&mut occupied, nx, ny, ids);
+
+    let region = largest_region(&occupied, count, nx, ny);
+    let inside = |i: i64, j: i64| {
+        i >= 0 && j >= 0 && (i as usize) < nx && (j as usize) < ny && {
+            region[j as usize * nx + i as usize]
+        }
+    };
+    let Some(first) = region.iter().position(|&r| r) else {
+        return Vec::new();
+    };
+
+    // walk the outer boundary with the region on the left, starting at the bottom left
+    // corner of its lowest, leftmost cell
+    let corner = |vx: i64, vy: i64, id: u64| Point {
+        x: origin.0 + vx as f64 * resolution,
+        y: origin.1 + vy as f64 * resolution,
+        id,
+    };
+    // the cell beside vertex (vx, vy) in the direction of the diagonal (ax, ay)
+    let cell = |vx: i64, vy: i64, ax: i64, ay: i64| (vx + (ax - 1) / 2, vy + (ay - 1) / 2);
+    let start = ((first % nx) as i64, (first / nx) as i64);
+    let (mut vx, mut vy) = start;
+    let (mut dx, mut dy) = (1i64, 0i64);
+    let mut hull = vec![corner(vx, vy, ids[first])];
+    loop {
+        vx += dx;
+        vy += dy;
+        if (vx, vy) == start {
+            break;
+        }
+        let (lx, ly) = (-dy, dx);
+        let (ali, alj) = cell(vx, vy, dx + lx, dy + ly);
+        let (ari, arj) = cell(vx, vy, dx - lx, dy - ly);
+        let (ahead_left, ahead_right) = (inside(ali, alj), inside(ari, arj));
+        let turned = match (ahead_left, ahead_right) {
+            (true, false) => false,
+            (true, true) => {
+                (dx, dy) = (dy, -dx);
+                true
+            }
+            _ => {
+                (dx, dy) = (lx, ly);
+                true
+            }
+        };
+        if turned {
+            // the occupied cell to the left of the outgoing edge
+            let (i, j) = cell(vx, vy, dx - dy, dy + dx);
+            hull.push(corner(vx, vy, ids[j as usize * nx + i as usize]));
+        }
+    }
+    hull.push(hull[0].clone());
+    hull
+}
+
+/// Fills an empty cell next to every pair of occupied cells that only meet at a corner.
+fn fill_diagonals(occupied: &mut [bool], nx: usize, ny: usize, ids: &mut [u64]) {
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for j in 0..ny.saturating_sub(1) {
+            for i in 0..nx - 1 {
+                let (a, b, c, d) = (
+                    j * nx + i,
+                    j * nx + i + 1,
+                    (j + 1) * nx + i,
+                    (j + 1) * nx + i + 1,
+                );
+                let fill = match (occupied[a], occupied[b], occupied[c], occupied[d]) {
+                    // lower left and upper right
+                    (true, false, false, true) => Some((b, a)),
+                    // lower right and upper left
+                    (false, true, true, false) => Some((a, b)),
+                    _ => None,
+                };
+                if let Some((empty, from)) = fill {
+                    occupied[empty] = true;
+                    ids[empty] = ids[from];
+                    changed = true;
+                }
+            }
+        }
+    }
+}
+
+/// The edge-connected region of occupied cells holding the most points.
+fn largest_region(occupied: &[bool], count: &[usize], nx: usize, ny: usize) -> Vec<bool> {
+    let mut label = vec![usize::MAX; occupied.len()];
+    let (mut best, mut best_count) = (usize::MAX, 0);
+    let mut stack = Vec::new();
+    for seed in 0..occupied.len() {
+        if !occupied[seed] || label[seed] != usize::MAX {
+            continue;
+        }
+        label[seed] = seed;
+        stack.push(seed);
+        let mut total = 0;
+        while let Some(c) = stack.pop() {
+            total += count[c];
+            let (i, j) = (c % nx, c / nx);
+            let neighbours = [
+                (i > 0).then(|| c - 1),
+                (i + 1 < nx).then(|| c + 1),
+                (j > 0).then(|| c - nx),
+                (j + 1 < ny).then(|| c + nx),
+            ];
+            for n in neighbours.into_iter().flatten() {
+                if occupied[n] && label[n] == usize::MAX {
+                    label[n] = seed;
+                    stack.push(n);
+                }
+            }
+        }
+        if total > best_count {
+            (best, best_count) = (seed, total);
+        }
+    }
+    label
+        .iter()
+        .map(|&l| l == best && best != usize::MAX)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prepared::PreparedHull;
+
+    fn area(ring: &[Point]) -> f64 {
+        ring.windows(2)
+            .map(|w| w[0].x * w[1].y - w[1].x * w[0].y)
+            .sum::<f64>()
+            / 2.0
+    }
+
+    #[test]
+    fn test_grid_hull() {
+        // an L of points: a 10 x 2 bar and a 2 x 10 bar, sampled every 0.25
+        let mut points = Vec::new();
+        for i in 0..40 {
+            for j in 0..40 {
+                let (x, y) = (i as f64 * 0.25, j as f64 * 0.25);
+                if x < 2.0 || y < 2.0 {
+                    points.push(Point {
+                        x,
+                        y,
+                        id: points.len() as u64,
+                    });
+                }
+            }
+        }
+
+        let hull = grid_hull(&points, 1.0);
+        assert_eq!(hull.first(), hull.last());
+        // six corners of the L, and the closing vertex
+        assert_eq!(hull.len(), 7);
+        assert!((area(&hull) - 36.0).abs() < 1E-9);
+        let prepared = PreparedHull::new(&hull);
+        assert!(points
+            .iter()
+            .all(|p| prepared.contains(p) || prepared.distance(p) < 1E-9));
+        assert!(hull.iter().all(|v| (v.id as usize) < points.len()));
+
+        // cells meeting at a corner are joined, and a stray point is left out
+        let diagonal: Vec<Point> = [(0.5, 0.5), (1.5, 1.5), (0.6, 0.6), (5.5, 5.5)]
+            .iter()
+            .enumerate()
+            .map(|(id, &(x, y))| Point {
+                x,
+                y,
+                id: id as u64,
+            })
+            .collect();
+        let hull = grid_hull(&diagonal, 1.0);
+        assert!((area(&hull) - 3.0).abs() < 1E-9);
+        assert!(grid_hull(&[], 1.0).is_empty());
+        assert!(grid_hull(&diagonal, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_concave_hull_aggregated() {
+        // a 10 x 10 grid of points every 0.1, and sparse noise around it
+        let mut points = Vec::new();
+        for i in 0..100 {
+            for j in 0..100 {
+                let (x, y) = (i as f64 * 0.1 + 0.05, j as f64 * 0.1 + 0.05);
+                points.push(Point {
+                    x,
+                    y,
+                    id: points.len() as u64,
+                });
+            }
+        }
+        for i in 0..8 {
+            let angle = i as f64 * std::f64::consts::FRAC_PI_4;
+            points.push(Point {
+                x: 5.0 + 9.0 * angle.cos(),
+                y: 5.0 + 9.0 * angle.sin(),
+                id: points.len() as u64,
+            });
+        }
+
+        let cells = aggregate(&points, 1.0);
+        assert_eq!(cells.len(), 108);
+        assert_eq!(cells.iter().map(|c| c.count).sum::<usize>(), points.len());
+        // three noise cells lie below the grid, then its lower left cell
+        assert_eq!(cells[3].count, 100);
+        assert!((cells[3].point.x - 0.5).abs() < 1E-9 && (cells[3].point.y - 0.5).abs() < 1E-9);
+        assert_eq!(cells.iter().filter(|c| c.count >= 2).count(), 100);
+
+        let hull = concave_hull_aggregated(&points, 1.0, 2, 3, true).unwrap();
+        assert!(hull.len() > 36);
+        assert!(hull
+            .iter()
+            .all(|p| (0.0..10.0).contains(&p.x) && (0.0..10.0).contains(&p.y)));
+    }
+}
@@ -0,0 +1,1216 @@
+/// Neighbour Search Trees
+use crate::error::ConcaveHullError;
+use crate::metric::{unit_vector, Metric, EARTH_RADIUS};
+#[cfg(feature = "experimental-f16")]
+use crate::point::BoundingBox;
+use crate::point::{Coordinates, Point, DIM};
+use crate::stats::HullStats;
+use crate::workspace::Workspace;
+use crate::Options;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+#[cfg(not(feature = "kd-tree"))]
+use crate::sweep::SweepIndex;
+
+#[cfg(feature = "experimental-f16")]
+use half::f16;
+#[cfg(feature = "kd-tree")]
+use kiddo::{KdTree, NearestNeighbour, SquaredEuclidean};
+#[cfg(feature = "rstar")]
+use rstar::{primitives::GeomWithData, RTree};
+
+/// Coordinate precision of the neighbour search index.
+///
+/// The index only proposes candidate neighbours; the hull itself is always computed from
+/// the full precision input points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IndexPrecision {
+    /// coordinates stored as `f64`
+    #[default]
+    Full,
+    /// coordinates stored as `f16` (experimental), a quarter of the coordinate memory
+    ///
+    /// Planar coordinates are centred and scaled into [-1, 1] first, so neighbours closer
+    /// than about 1/2000 of the extent of the data are no longer told apart.
+    #[cfg(feature = "experimental-f16")]
+    Half,
+}
+
+/// A nearest neighbour index the walk finds its candidates in.
+///
+/// The walk builds an index over the points with their positions as ids, takes each hull
+/// vertex out once it is visited and puts the first vertex back in under an unused id once
+/// the hull may close. Besides the built-in index, [`BruteForce`] and any type implementing
+/// this trait, e.g. one wrapping another spatial index crate, can be chosen with
+/// [`IndexBackend`].
+/// Indexes are `Send`, so that a [`Dataset`] and its index can move between threads.
+pub trait NeighborIndex: Send {
+    /// adds `point` under its id
+    fn add(&mut self, point: &Point);
+
+    /// removes the entry with the coordinates and id of `point`, if there is one
+    fn remove(&mut self, point: &Point);
+
+    /// ids of the `k` points nearest to `point`, closest first and tied points by id
+    fn nearest(&self, point: &Point, k: usize) -> Vec<u64>;
+}
+
+/// Builds a [`NeighborIndex`] over points for distances under a metric.
+pub type IndexFactory = Arc<dyn Fn(&[Point], Metric) -> Box<dyn NeighborIndex> + Send + Sync>;
+
+/// The neighbour index the walk searches, see [`crate::builder::ConcaveHullBuilder::index`].
+#[derive(Clone, Default)]
+pub enum IndexBackend {
+    /// the kiddo kd-tree, or without the `kd-tree` feature the built-in index sorted along x
+    #[default]
+    Builtin,
+    /// [`BruteForce`], which measures the distance to every point for every query
+    BruteForce,
+    /// [`RTreeIndex`], the R-tree of the rstar crate
+    #[cfg(feature = "rstar")]
+    RTree,
+    /// the index built by the factory
+    Custom(IndexFactory),
+}
+
+impl IndexBackend {
+    /// A backend building its index with `factory`.
+    pub fn custom(
+        factory: impl Fn(&[Point], Metric) -> Box<dyn NeighborIndex> + Send + Sync + 'static,
+    ) -> Self {
+        IndexBackend::Custom(Arc::new(factory))
+    }
+
+    /// builds the index of this backend over `points`
+    fn build(
+        &self,
+        points: &[Point],
+        metric: Metric,
+        precision: IndexPrecision,
+    ) -> Box<dyn NeighborIndex> {
+        match self {
+            IndexBackend::Builtin => Box::new(SearchTree::new(points, metric, precision)),
+            IndexBackend::BruteForce => Box::new(BruteForce::new(points, metric)),
+            #[cfg(feature = "rstar")]
+            IndexBackend::RTree => Box::new(RTreeIndex::new(points, metric)),
+            IndexBackend::Custom(factory) => factory(points, metric),
+        }
+    }
+}
+
+impl fmt::Debug for IndexBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexBackend::Builtin => f.write_str("Builtin"),
+            IndexBackend::BruteForce => f.write_str("BruteForce"),
+            #[cfg(feature = "rstar")]
+            IndexBackend::RTree => f.write_str("RTree"),
+            IndexBackend::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl PartialEq for IndexBackend {
+    /// Custom backends are equal if they share a factory.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (IndexBackend::Custom(a), IndexBackend::Custom(b)) => Arc::ptr_eq(a, b),
+            (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+        }
+    }
+}
+
+/// builds the index `options` ask for over `points`
+pub(crate) fn build_index(points: &[Point], options: &Options) -> Box<dyn NeighborIndex> {
+    #[cfg(feature = "tracing")]
+    let started = std::time::Instant::now();
+    let index = options
+        .backend
+        .build(points, options.metric, options.precision);
+    #[cfg(feature = "tracing")]
+    tracing::trace!(elapsed = ?started.elapsed(), "neighbour index built");
+    index
+}
+
+/// the coordinates `point` is measured in under `metric`: planar, or a unit vector on the
+/// sphere for [`Metric::Haversine`], whose chord lengths order neighbours like great circles
+fn search_key(point: &Point, metric: Metric) -> [f64; 3] {
+    match metric {
+        Metric::Euclidean => [point.x, point.y, 0.0],
+        Metric::Haversine => unit_vector(point),
+    }
+}
+
+/// orders neighbours by squared distance, tied ones by id
+fn by_distance(a: &(f64, u64), b: &(f64, u64)) -> std::cmp::Ordering {
+    a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+}
+
+/// The simplest [`NeighborIndex`]: a list of points, all of which every query measures.
+///
+/// Queries take time linear in the number of points, so this is for checking other indexes
+/// against, and for datasets small enough that building a tree costs more than it saves.
+/// Neighbours are ordered by squared planar distance, or by squared chord length on the
+/// sphere for [`Metric::Haversine`], the measures of the built-in index.
+#[derive(Debug, Clone)]
+pub struct BruteForce {
+    /// how the points are measured
+    metric: Metric,
+    /// the coordinates the points are measured in, and their ids
+    entries: Vec<([f64; 3], u64)>,
+}
+
+impl BruteForce {
+    /// An index over `points` for distances under `metric`.
+    pub fn new(points: &[Point], metric: Metric) -> Self {
+        let mut index = BruteForce {
+            metric,
+            entries: Vec::with_capacity(points.len()),
+        };
+        for point in points {
+            index.add(point);
+        }
+        index
+    }
+
+    /// the coordinates `point` is measured in
+    fn key(&self, point: &Point) -> [f64; 3] {
+        search_key(point, self.metric)
+    }
+}
+
+impl NeighborIndex for BruteForce {
+    fn add(&mut self, point: &Point) {
+        self.entries.push((self.key(point), point.id));
+    }
+
+    fn remove(&mut self, point: &Point) {
+        let entry = (self.key(point), point.id);
+        if let Some(i) = self.entries.iter().position(|e| *e == entry) {
+            self.entries.swap_remove(i);
+        }
+    }
+
+    fn nearest(&self, point: &Point, k: usize) -> Vec<u64> {
+        let query = self.key(point);
+        let mut found: Vec<(f64, u64)> = self
+            .entries
+            .iter()
+            .map(|(key, id)| {
+                let distance = key.iter().zip(query).map(|(a, b)| (a - b) * (a - b)).sum();
+                (distance, *id)
+            })
+            .collect();
+        if k < found.len() {
+            found.select_nth_unstable_by(k, by_distance);
+            found.truncate(k);
+        }
+        found.sort_unstable_by(by_distance);
+        found.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+/// A [`NeighborIndex`] on the R-tree of the rstar crate.
+///
+/// Points are measured as [`BruteForce`] measures them, so both return the same neighbours.
+/// The tree is bulk loaded, which suits the walk: it only ever takes points out, and puts
+/// back the first vertex.
+#[cfg(feature = "rstar")]
+#[derive(Debug, Clone)]
+pub struct RTreeIndex {
+    /// how the points are measured
+    metric: Metric,
+    /// the coordinates the points are measured in, with their ids
+    tree: RTree<GeomWithData<[f64; 3], u64>>,
+}
+
+#[cfg(feature = "rstar")]
+impl RTreeIndex {
+    /// An index over `points` for distances under `metric`.
+    pub fn new(points: &[Point], metric: Metric) -> Self {
+        let entries = points
+            .iter()
+            .map(|point| GeomWithData::new(search_key(point, metric), point.id))
+            .collect();
+        RTreeIndex {
+            metric,
+            tree: RTree::bulk_load(entries),
+        }
+    }
+}
+
+#[cfg(feature = "rstar")]
+impl NeighborIndex for RTreeIndex {
+    fn add(&mut self, point: &Point) {
+        self.tree
+            .insert(GeomWithData::new(search_key(point, self.metric), point.id));
+    }
+
+    fn remove(&mut self, point: &Point) {
+        self.tree
+            .remove(&GeomWithData::new(search_key(point, self.metric), point.id));
+    }
+
+    fn nearest(&self, point: &Point, k: usize) -> Vec<u64> {
+        let query = search_key(point, self.metric);
+        let mut found: Vec<(f64, u64)> = Vec::with_capacity(k);
+        for (entry, distance) in self.tree.nearest_neighbor_iter_with_distance_2(&query) {
+            // past the k-th point, only the points tying with it can still be returned
+            if found.len() >= k && found.last().is_none_or(|last| distance > last.0) {
+                break;
+            }
+            found.push((distance, entry.data));
+        }
+        found.sort_unstable_by(by_distance);
+        found.truncate(k);
+        found.into_iter().map(|(_, id)| id).collect()
+    }
+}
+
+/// A kd-tree of point ids, laid out in the coordinate space a metric needs.
+///
+/// Without the `kd-tree` feature, the built-in [`SweepIndex`] takes the place of the
+/// kd-trees.
+pub(crate) enum SearchTree {
+    /// planar (x, y) coordinates
+    #[cfg(feature = "kd-tree")]
+    Planar(KdTree<f64, DIM>),
+    /// unit vectors on the sphere, see [`unit_vector`]
+    #[cfg(feature = "kd-tree")]
+    Sphere(KdTree<f64, 3>),
+    /// planar (x, y) coordinates in the built-in index
+    #[cfg(not(feature = "kd-tree"))]
+    PlanarSweep(SweepIndex<DIM>),
+    /// unit vectors on the sphere in the built-in index
+    #[cfg(not(feature = "kd-tree"))]
+    SphereSweep(SweepIndex<3>),
+    /// planar coordinates in trees built in parallel, split along x
+    #[cfg(feature = "parallel")]
+    PlanarSharded(Shards),
+    /// planar coordinates mapped into [-1, 1] by the frame
+    #[cfg(feature = "experimental-f16")]
+    PlanarHalf(KdTree<f16, DIM>, Frame),
+    /// unit vectors on the sphere
+    #[cfg(feature = "experimental-f16")]
+    SphereHalf(KdTree<f16, 3>),
+}
+
+/// Affine map of a bounding box onto [-1, 1]
+#[cfg(feature = "experimental-f16")]
+pub(crate) struct Frame {
+    cx: f64,
+    cy: f64,
+    scale: f64,
+}
+
+#[cfg(feature = "experimental-f16")]
+impl Frame {
+    fn new(bbox: &BoundingBox) -> Self {
+        let half_size = bbox.width().max(bbox.height()) / 2.0;
+        Frame {
+            cx: (bbox.min_x + bbox.max_x) / 2.0,
+            cy: (bbox.min_y + bbox.max_y) / 2.0,
+            scale: if half_size > 0.0 {
+                1.0 / half_size
+            } else {
+                1.0
+            },
+        }
+    }
+
+    fn key(&self, point: &Point) -> [f16; 2] {
+        [
+            f16::from_f64((point.x - self.cx) * self.scale),
+            f16::from_f64((point.y - self.cy) * self.scale),
+        ]
+    }
+}
+
+#[cfg(feature = "experimental-f16")]
+fn half_unit_vector(point: &Point) -> [f16; 3] {
+    unit_vector(point).map(f16::from_f64)
+}
+
+/// Inputs with at least this many points have their planar tree built on several threads.
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 1 << 16;
+
+/// A planar kd-tree split into slabs along x, one tree per slab.
+#[cfg(feature = "parallel")]
+pub(crate) struct Shards {
+    /// x coordinates between the slabs; slab `i` holds the points with x in
+    /// `[bounds[i - 1], bounds[i])`
+    bounds: Vec<f64>,
+    /// tree of each slab
+    trees: Vec<KdTree<f64, DIM>>,
+}
+
+#[cfg(feature = "parallel")]
+impl Shards {
+    /// builds the trees of about `count` slabs holding similar numbers of points, one
+    /// thread per slab
+    fn new(points: &[Point], count: usize) -> Self {
+        // slab bounds at the quantiles of a sample of the x coordinates
+        let step = (points.len() / (64 * count)).max(1);
+        let mut sample: Vec<f64> = points.iter().step_by(step).map(|p| p.x).collect();
+        sample.sort_by(f64::total_cmp);
+        let mut bounds: Vec<f64> = (1..count)
+            .map(|i| sample[i * sample.len() / count])
+            .collect();
+        bounds.dedup();
+
+        let trees = std::thread::scope(|scope| {
+            let bounds = &bounds;
+            let builders: Vec<_> = (0..=bounds.len())
+                .map(|slab| {
+                    scope.spawn(move || {
+                        let mut tree = KdTree::new();
+                        for point in points.iter().filter(|p| slab_of(bounds, p.x) == slab) {
+                            tree.add(&point.coordinates(), point.id);
+                        }
+                        tree
+                    })
+                })
+                .collect();
+            builders
+                .into_iter()
+                .map(|builder| builder.join().unwrap())
+                .collect()
+        });
+        Shards { bounds, trees }
+    }
+
+    fn tree_mut(&mut self, x: f64) -> &mut KdTree<f64, DIM> {
+        &mut self.trees[slab_of(&self.bounds, x)]
+    }
+
+    /// every tree with the squared x distance from `x` to its slab, nearest first
+    fn by_gap(&self, x: f64) -> Vec<(f64, &KdTree<f64, DIM>)> {
+        let mut trees: Vec<(f64, &KdTree<f64, DIM>)> = self
+            .trees
+            .iter()
+            .enumerate()
+            .map(|(slab, tree)| {
+                let lower = if slab == 0 {
+                    f64::NEG_INFINITY
+                } else {
+                    self.bounds[slab - 1]
+                };
+                let upper = self.bounds.get(slab).copied().unwrap_or(f64::INFINITY);
+                let gap = (lower - x).max(x - upper).max(0.0);
+                (gap * gap, tree)
+            })
+            .collect();
+        trees.sort_by(|a, b| a.0.total_cmp(&b.0));
+        trees
+    }
+}
+
+/// the slab of the points with x coordinate `x`
+#[cfg(feature = "parallel")]
+fn slab_of(bounds: &[f64], x: f64) -> usize {
+    bounds.partition_point(|&bound| bound <= x)
+}
+
+/// The `k` points nearest to `query` in `trees` of any dimension `D`.
+///
+/// `trees` come with a lower bound on the squared distance from `query` to any of their
+/// points, smallest first. Neighbours are ordered by distance and then id, and points tied
+/// with the k-th are all considered, so the result doesn't depend on how the points are
+/// split among trees or arranged within one.
+#[cfg(feature = "kd-tree")]
+fn nearest_in<const D: usize>(
+    trees: &[(f64, &KdTree<f64, D>)],
+    query: &[f64; D],
+    k: usize,
+) -> Vec<NearestNeighbour<f64, u64>> {
+    let by_distance = |a: &NearestNeighbour<f64, u64>, b: &NearestNeighbour<f64, u64>| {
+        a.distance.total_cmp(&b.distance).then(a.item.cmp(&b.item))
+    };
+    if k == 0 {
+        return Vec::new();
+    }
+
+    // one more than needed, to see whether the k-th is tied
+    let mut found: Vec<NearestNeighbour<f64, u64>> = Vec::with_capacity(2 * k + 2);
+    for &(gap, tree) in trees.iter() {
+        if found.len() > k && gap > found[k].distance {
+            break;
+        }
+        found.extend(tree.nearest_n::<SquaredEuclidean>(query, k + 1));
+        found.sort_by(by_distance);
+        found.truncate(k + 1);
+    }
+
+    if found.len() > k && found[k].distance == found[k - 1].distance {
+        let radius = found[k].distance.next_up();
+        found = trees
+            .iter()
+            .filter(|&&(gap, _)| gap < radius)
+            .flat_map(|(_, tree)| tree.within_unsorted::<SquaredEuclidean>(query, radius))
+            .collect();
+        found.sort_by(by_distance);
+    }
+    found.truncate(k);
+    found
+}
+
+/// great-circle distance in metres for a squared chord length on the unit sphere
+fn chord_to_metres(squared_chord: f64) -> f64 {
+    2.0 * EARTH_RADIUS * (squared_chord.sqrt() / 2.0).min(1.0).asin()
+}
+
+impl SearchTree {
+    /// builds a tree over `points` for nearest neighbour queries under `metric`
+    ///
+    /// With the `parallel` feature, large planar inputs are split into slabs along x whose
+    /// trees are built on all available threads. Queries return the same neighbours as from
+    /// a single tree.
+    pub fn new(points: &[Point], metric: Metric, precision: IndexPrecision) -> Self {
+        #[cfg(feature = "parallel")]
+        if (metric, precision) == (Metric::Euclidean, IndexPrecision::Full)
+            && points.len() >= PARALLEL_THRESHOLD
+        {
+            let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+            if threads > 1 {
+                return SearchTree::PlanarSharded(Shards::new(points, threads));
+            }
+        }
+
+        #[cfg(not(feature = "kd-tree"))]
+        return match (metric, precision) {
+            (Metric::Euclidean, IndexPrecision::Full) => SearchTree::PlanarSweep(SweepIndex::new(
+                points.iter().map(|p| (p.coordinates(), p.id)).collect(),
+            )),
+            (Metric::Haversine, IndexPrecision::Full) => SearchTree::SphereSweep(SweepIndex::new(
+                points.iter().map(|p| (unit_vector(p), p.id)).collect(),
+            )),
+        };
+
+        #[cfg(feature = "kd-tree")]
+        let mut tree = match (metric, precision) {
+            (Metric::Euclidean, IndexPrecision::Full) => SearchTree::Planar(KdTree::new()),
+            (Metric::Haversine, IndexPrecision::Full) => SearchTree::Sphere(KdTree::new()),
+            #[cfg(feature = "experimental-f16")]
+            (Metric::Euclidean, IndexPrecision::Half) => {
+                SearchTree::PlanarHalf(KdTree::new(), Frame::new(&BoundingBox::from_points(points)))
+            }
+            #[cfg(feature = "experimental-f16")]
+            (Metric::Haversine, IndexPrecision::Half) => SearchTree::SphereHalf(KdTree::new()),
+        };
+        #[cfg(feature = "kd-tree")]
+        for point in points.iter() {
+            tree.add(point);
+        }
+        #[cfg(feature = "kd-tree")]
+        tree
+    }
+
+    pub fn add(&mut self, point: &Point) {
+        match self {
+            #[cfg(feature = "kd-tree")]
+            SearchTree::Planar(tree) => tree.add(&point.coordinates(), point.id),
+            #[cfg(feature = "kd-tree")]
+            SearchTree::Sphere(tree) => tree.add(&unit_vector(point), point.id),
+            #[cfg(not(feature = "kd-tree"))]
+            SearchTree::PlanarSweep(index) => index.add(&point.coordinates(), point.id),
+            #[cfg(not(feature = "kd-tree"))]
+            SearchTree::SphereSweep(index) => index.add(&unit_vector(point), point.id),
+            #[cfg(feature = "parallel")]
+            SearchTree::PlanarSharded(shards) => {
+                shards.tree_mut(point.x).add(&point.coordinates(), point.id)
+            }
+            #[cfg(feature = "experimental-f16")]
+            SearchTree::PlanarHalf(tree, frame) => tree.add(&frame.key(point), point.id),
+            #[cfg(feature = "experimental-f16")]
+            SearchTree::SphereHalf(tree) => tree.add(&half_unit_vector(point), point.id),
+        }
+    }
+
+    pub fn remove(&mut self, point: &Point) {
+        match self {
+            #[cfg(feature = "kd-tree")]
+            SearchTree::Planar(tree) => tree.remove(&point.coordinates(), point.id),
+            #[cfg(feature = "kd-tree")]
+            SearchTree::Sphere(tree) => tree.remove(&unit_vector(point), point.id),
+            #[cfg(not(feature = "kd-tree"))]
+            SearchTree::PlanarSweep(index) => index.remove(&point.coordinates(), point.id),
+            #[cfg(not(feature = "kd-tree"))]
+            SearchTree::SphereSweep(index) => index.remove(&unit_vector(point), point.id),
+            #[cfg(feature = "parallel")]
+            SearchTree::PlanarSharded(shards) => shards
+                .tree_mut(point.x)
+                .remove(&point.coordinates(), point.id),
+            #[cfg(feature = "experimental-f16")]
+            SearchTree::PlanarHalf(tree, frame) => tree.remove(&frame.key(point), point.id),
+            #[cfg(feature = "experimental-f16")]
+            SearchTree::SphereHalf(tree) => tree.remove(&half_unit_vector(point), point.id),
+        };
+    }
+
+    /// distances to the `k` points nearest to `point`, closest first (metres on the sphere)
+    pub fn nearest_distances(&self, point: &Point, k: usize) -> Vec<f64> {
+        match self {
+            #[cfg(feature = "kd-tree")]
+            SearchTree::Planar(tree) => nearest_in(&[(0.0, tree)], &point.coordinates(), k)
+                .iter()
+                .map(|n| n.distance.sqrt())
+                .collect(),
+            #[cfg(not(feature = "kd-tree"))]
+            SearchTree::PlanarSweep(index) => index
+                .nearest(&point.coordinates(), k)
+                .iter()
+                .map(|&(distance, _)| distance.sqrt())
+                .collect(),
+            #[cfg(not(feature = "kd-tree"))]
+            SearchTree::SphereSweep(index) => index
+                .nearest(&unit_vector(point), k)
+                .iter()
+                .map(|&(distance, _)| chord_to_metres(distance))
+                .collect(),
+            #[cfg(feature = "parallel")]
+            SearchTree::PlanarSharded(shards) => {
+                nearest_in(&shards.by_gap(point.x), &point.coordinates(), k)
+                    .iter()
+                    .map(|n| n.distance.sqrt())
+                    .collect()
+            }
+            #[cfg(feature = "kd-tree")]
+            SearchTree::Sphere(tree) => tree
+                .nearest_n::<SquaredEuclidean>(&unit_vector(point), k)
+                .iter()
+                .map(|n| chord_to_metres(n.distance))
+                .collect(),
+            #[cfg(feature = "experimental-f16")]
+            SearchTree::PlanarHalf(tree, frame) => tree
+                .nearest_n::<SquaredEuclidean>(&frame.key(point), k)
+                .iter()
+                .map(|n| n.distance.to_f64().sqrt() / frame.scale)
+                .collect(),
+            #[cfg(feature = "experimental-f16")]
+            SearchTree::SphereHalf(tree) => tree
+                .nearest_n::<SquaredEuclidean>(&half_unit_vector(point), k)
+                .iter()
+                .map(|n| chord_to_metres(n.distance.to_f64()))
+                .collect(),
+        }
+    }
+
+    /// ids of the `k` points nearest to `point`, closest first
+    pub fn nearest(&self, point: &Point, k: usize) -> Vec<u64> {
+        match self {
+            #[cfg(feature = "kd-tree")]
+            SearchTree::Planar(tree) => nearest_in(&[(0.0, tree)], &point.coordinates(), k)
+                .iter()
+                .map(|n| n.item)
+                .collect(),
+            #[cfg(not(feature = "kd-tree"))]
+            SearchTree::PlanarSweep(index) => index
+                .nearest(&point.coordinates(), k)
+                .iter()
+                .map(|&(_, id)| id)
+                .collect(),
+            #[cfg(not(feature = "kd-tree"))]
+            SearchTree::SphereSweep(index) => index
+                .nearest(&unit_vector(point), k)
+                .iter()
+                .map(|&(_, id)| id)
+                .collect(),
+            #[cfg(feature = "parallel")]
+            SearchTree::PlanarSharded(shards) => {
+                nearest_in(&shards.by_gap(point.x), &point.coordinates(), k)
+                    .iter()
+                    .map(|n| n.item)
+                    .collect()
+            }
+            #[cfg(feature = "kd-tree")]
+            SearchTree::Sphere(tree) => nearest_in(&[(0.0, tree)], &unit_vector(point), k)
+                .iter()
+                .map(|n| n.item)
+                .collect(),
+            #[cfg(feature = "experimental-f16")]
+            SearchTree::PlanarHalf(tree, frame) => nearest_half(tree, &frame.key(point), k),
+            #[cfg(feature = "experimental-f16")]
+            SearchTree::SphereHalf(tree) => nearest_half(tree, &half_unit_vector(point), k),
+        }
+    }
+}
+
+/// the ids of the `k` half-precision neighbours of `query` in `tree`
+///
+/// As in [`nearest_in`], neighbours are ordered by distance and then id, and points tied
+/// with the k-th are all considered.
+#[cfg(feature = "experimental-f16")]
+fn nearest_half<const D: usize>(tree: &KdTree<f16, D>, query: &[f16; D], k: usize) -> Vec<u64> {
+    let by_distance = |a: &NearestNeighbour<f16, u64>, b: &NearestNeighbour<f16, u64>| {
+        a.distance.total_cmp(&b.distance).then(a.item.cmp(&b.item))
+    };
+    if k == 0 {
+        return Vec::new();
+    }
+
+    // one more than needed, to see whether the k-th is tied
+    let mut found = tree.nearest_n::<SquaredEuclidean>(query, k + 1);
+    found.sort_by(by_distance);
+    if found.len() > k && found[k].distance == found[k - 1].distance {
+        // the next half above a non-negative distance
+        let radius = f16::from_bits(found[k].distance.to_bits() + 1);
+        found = tree.within_unsorted::<SquaredEuclidean>(query, radius);
+        found.sort_by(by_distance);
+    }
+    found.truncate(k);
+    found.iter().map(|n| n.item).collect()
+}
+
+/// what an index was built for: the metric, precision and backend
+type IndexKey = (Metric, IndexPrecision, IndexBackend);
+
+/// The neighbour index of a dataset with the buffers of its walks, kept between hulls.
+#[derive(Default)]
+struct IndexState {
+    /// the dataset with the positions as ids, as the index holds it, filled with the index
+    positions: Vec<Point>,
+    /// the index, with the metric, precision and backend it was built for
+    tree: Option<(IndexKey, Box<dyn NeighborIndex>)>,
+    /// scratch buffers of the walks
+    workspace: Workspace,
+}
+
+impl IndexState {
+    /// the index over `points` for `options`, built if there is none for them yet
+    fn tree(&mut self, points: &[Point], options: &Options) -> &mut Box<dyn NeighborIndex> {
+        if self.positions.len() != points.len() {
+            self.positions.clear();
+            self.positions
+                .extend(points.iter().enumerate().map(|(i, p)| Point {
+                    x: p.x,
+                    y: p.y,
+                    id: i as u64,
+                }));
+        }
+        let key = (options.metric, options.precision, options.backend.clone());
+        if !matches!(&self.tree, Some((built, _)) if *built == key) {
+            self.tree = None;
+        }
+        let positions = &self.positions;
+        &mut self
+            .tree
+            .get_or_insert_with(|| (key, build_index(positions, options)))
+            .1
+    }
+
+    /// the hulls of [`crate::search_in`] over `points`, in the index kept for them
+    fn search(
+        &mut self,
+        points: &[Point],
+        k: usize,
+        iterate: bool,
+        max_k: usize,
+        options: &Options,
+        history: bool,
+    ) -> Result<(Vec<Point>, HullStats), ConcaveHullError> {
+        crate::check_input(points, k)?;
+        self.tree(points, options);
+        let IndexState {
+            positions,
+            tree,
+            workspace,
+        } = self;
+        let Some((_, tree)) = tree else {
+            unreachable!("the index was just built")
+        };
+        let mut hull = Vec::new();
+        let stats = crate::search_in(
+            points,
+            positions,
+            tree.as_mut(),
+            k,
+            iterate,
+            max_k,
+            options,
+            history,
+            &mut hull,
+            workspace,
+        )?;
+        Ok((hull, stats))
+    }
+}
+
+/// A dataset with its neighbour index, for computing many hulls of the same points.
+///
+/// Every hull of [`crate::concave_hull_borrowed`] or [`crate::builder::ConcaveHull`] builds
+/// a neighbour index over its dataset, which dominates the time of a sweep over `k` or the
+/// options on one dataset. A `PointIndex` builds it on its first hull and keeps it: each
+/// walk takes its vertices out of the index and puts them back, and the points a rejected
+/// hull leaves out are taken out only for the rest of that computation. The index is
+/// rebuilt only if a hull needs another metric or precision than it was built for.
+///
+/// ```
+/// use concave_hull::index::PointIndex;
+///
+/// let mut index = PointIndex::new(&points);
+/// for k in 3..20 {
+///     let hull = index.concave_hull(k, false)?;
+///     draw(k, hull);
+/// }
+/// ```
+pub struct PointIndex<'a> {
+    /// the dataset, in its order
+    points: &'a [Point],
+    /// the index and the buffers of the walks
+    state: IndexState,
+}
+
+impl<'a> PointIndex<'a> {
+    /// An index over `dataset`, which is built on the first hull computed with it.
+    pub fn new(dataset: &'a [Point]) -> Self {
+        PointIndex {
+            points: dataset,
+            state: IndexState::default(),
+        }
+    }
+
+    /// the indexed points
+    pub fn points(&self) -> &'a [Point] {
+        self.points
+    }
+
+    /// Computes the concave hull of the indexed points.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - number of nearest neighbors.
+    /// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+    ///   as soon as the algorithm succeeds.
+    ///
+    /// # Returns
+    ///
+    /// Returns the hull or the error that [`crate::concave_hull_borrowed`] returns for the
+    /// indexed points.
+    pub fn concave_hull(
+        &mut self,
+        k: usize,
+        iterate: bool,
+    ) -> Result<Vec<Point>, ConcaveHullError> {
+        self.search(k, iterate, usize::MAX, &Options::default(), false)
+            .map(|(hull, _)| hull)
+    }
+
+    /// sorted positions of the points left out with the rejected hulls of the last search
+    pub(crate) fn dropped(&self) -> &[usize] {
+        &self.state.workspace.dropped
+    }
+
+    /// the hulls of [`IndexState::search`] over the indexed points
+    pub(crate) fn search(
+        &mut self,
+        k: usize,
+        iterate: bool,
+        max_k: usize,
+        options: &Options,
+        history: bool,
+    ) -> Result<(Vec<Point>, HullStats), ConcaveHullError> {
+        self.state
+            .search(self.points, k, iterate, max_k, options, history)
+    }
+}
+
+/// A dataset shared by reference count, with a neighbour index shared the same way.
+///
+/// Cloning a `Dataset` clones two pointers: the coordinates are stored once however many
+/// solvers, classifiers and queries hold it, and the index built by the first hull or
+/// neighbour query serves all of them, as a [`PointIndex`] does for one owner. A dataset
+/// dereferences to its points, so every function taking `&[Point]` takes `&dataset` too.
+///
+/// Hulls and queries of clones of one dataset take turns on its index; datasets meant to be
+/// hulled in parallel need an index each, see [`Dataset::detached`].
+///
+/// ```
+/// use concave_hull::index::Dataset;
+///
+/// let dataset = Dataset::new(points);
+/// let shared = dataset.clone();
+/// let hull = std::thread::spawn(move || shared.concave_hull(5, true)).join().unwrap()?;
+/// let nearest = dataset.nearest(&hull[0], 8);
+/// ```
+#[derive(Clone)]
+pub struct Dataset {
+    /// the points, in their order
+    points: Arc<[Point]>,
+    /// the index over them and the buffers of the walks
+    state: Arc<Mutex<IndexState>>,
+}
+
+impl Dataset {
+    /// A dataset of `points`, taken over without a copy if they are already an
+    /// `Arc<[Point]>`; the index is built on first use.
+    pub fn new(points: impl Into<Arc<[Point]>>) -> Self {
+        Dataset {
+            points: points.into(),
+            state: Arc::default(),
+        }
+    }
+
+    /// A dataset of the same points with an index of its own, built on first use.
+    pub fn detached(&self) -> Self {
+        Dataset::new(self.points.clone())
+    }
+
+    /// the points
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// the points, shared with the dataset
+    pub fn shared_points(&self) -> Arc<[Point]> {
+        self.points.clone()
+    }
+
+    /// Computes the concave hull of the points as [`PointIndex::concave_hull`] does.
+    pub fn concave_hull(&self, k: usize, iterate: bool) -> Result<Vec<Point>, ConcaveHullError> {
+        self.search(k, iterate, usize::MAX, &Options::default(), false)
+            .map(|(hull, _)| hull)
+    }
+
+    /// The `k` points nearest to `point` in Euclidean distance, closest first, ties by
+    /// position.
+    pub fn nearest(&self, point: &Point, k: usize) -> Vec<&Point> {
+        let mut state = self.state.lock().unwrap();
+        let ids = state
+            .tree(&self.points, &Options::default())
+            .nearest(point, k);
+        ids.into_iter()
+            .map(|id| &self.points[id as usize])
+            .collect()
+    }
+
+    /// the hulls of [`IndexState::search`] over the points
+    pub(crate) fn search(
+        &self,
+        k: usize,
+        iterate: bool,
+        max_k: usize,
+        options: &Options,
+        history: bool,
+    ) -> Result<(Vec<Point>, HullStats), ConcaveHullError> {
+        self.with_index(|index| index.search(k, iterate, max_k, options, history))
+    }
+
+    /// Runs `f` on the points with the shared index, which other users wait for meanwhile.
+    pub(crate) fn with_index<R>(&self, f: impl FnOnce(&mut PointIndex<'_>) -> R) -> R {
+        let mut state = self.state.lock().unwrap();
+        let mut index = PointIndex {
+            points: &self.points,
+            state: std::mem::take(&mut *state),
+        };
+        let result = f(&mut index);
+        *state = index.state;
+        result
+    }
+}
+
+impl std::ops::Deref for Dataset {
+    type Target = [Point];
+
+    fn deref(&self) -> &[Point] {
+        &self.points
+    }
+}
+
+impl AsRef<[Point]> for Dataset {
+    fn as_ref(&self) -> &[Point] {
+        &self.points
+    }
+}
+
+impl From<Vec<Point>> for Dataset {
+    fn from(points: Vec<Point>) -> Self {
+        Dataset::new(points)
+    }
+}
+
+impl std::fmt::Debug for PointIndex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PointIndex")
+            .field("points", &self.points.len())
+            .field("built", &self.state.tree.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Debug for Dataset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dataset")
+            .field("points", &self.points.len())
+            .field("shared", &Arc::strong_count(&self.points))
+            .finish_non_exhaustive()
+    }
+}
+
+impl NeighborIndex for SearchTree {
+    fn add(&mut self, point: &Point) {
+        SearchTree::add(self, point)
+    }
+
+    fn remove(&mut self, point: &Point) {
+        SearchTree::remove(self, point)
+    }
+
+    fn nearest(&self, point: &Point, k: usize) -> Vec<u64> {
+        SearchTree::nearest(self, point, k)
+    }
+}
+
+#[cfg(all(test, feature = "kd-tree"))]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_nearest_in_3d() {
+        let mut rng = Rng::new(4);
+        let points: Vec<[f64; 3]> = (0..500)
+            .map(|_| [rng.uniform(), rng.uniform(), rng.uniform()])
+            .collect();
+        let mut tree: KdTree<f64, 3> = KdTree::new();
+        for (id, point) in points.iter().enumerate() {
+            tree.add(&point.coordinates(), id as u64);
+        }
+
+        let query = [0.5, 0.5, 0.5];
+        let squared = |p: &[f64; 3]| p.iter().zip(query).map(|(a, b)| (a - b).powi(2)).sum();
+        let mut expected: Vec<(f64, u64)> = points
+            .iter()
+            .enumerate()
+            .map(|(id, p)| (squared(p), id as u64))
+            .collect();
+        expected.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let found: Vec<u64> = nearest_in(&[(0.0, &tree)], &query, 8)
+            .iter()
+            .map(|n| n.item)
+            .collect();
+        let expected: Vec<u64> = expected.iter().take(8).map(|&(_, id)| id).collect();
+        assert_eq!(found, expected);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_sharded_tree() {
+        let mut rng = Rng::new(9);
+        let mut points: Vec<Point> = (0..5000)
+            .map(|id| Point {
+                x: rng.uniform(),
+                y: rng.uniform(),
+                id,
+            })
+            .collect();
+        // twelve points at exactly the same distance from the centre, which the slab
+        // bound near x = 0.5 splits
+        let unit = 1.0 / 1024.0;
+        let offsets = [(3.0, 4.0), (4.0, 3.0), (5.0, 0.0), (0.0, 5.0)];
+        for (dx, dy) in offsets
+            .iter()
+            .flat_map(|&(a, b)| [(a, b), (-a, -b), (-a, b), (a, -b)])
+        {
+            let point = Point {
+                x: 0.5 + dx * unit,
+                y: 0.5 + dy * unit,
+                id: points.len() as u64,
+            };
+            if !points.iter().any(|p| (p.x, p.y) == (point.x, point.y)) {
+                points.push(point);
+            }
+        }
+
+        let mut serial = SearchTree::new(&points, Metric::Euclidean, IndexPrecision::Full);
+        let mut sharded = SearchTree::PlanarSharded(Shards::new(&points, 8));
+        for tree in [&mut serial, &mut sharded] {
+            for point in points.iter().step_by(3) {
+                tree.remove(point);
+            }
+        }
+        let centre = Point {
+            x: 0.5,
+            y: 0.5,
+            id: 0,
+        };
+        for query in points.iter().take(200).chain([&centre]) {
+            for k in [1, 6, 12] {
+                assert_eq!(serial.nearest(query, k), sharded.nearest(query, k));
+                assert_eq!(
+                    serial.nearest_distances(query, k),
+                    sharded.nearest_distances(query, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_point_index() {
+        let mut rng = Rng::new(8);
+        let points: Vec<Point> = (0..300)
+            .map(|id| Point {
+                x: rng.uniform(),
+                y: rng.uniform(),
+                id,
+            })
+            .collect();
+
+        // one index for every k, and again for the same k, rejected hulls included
+        let mut index = PointIndex::new(&points);
+        for k in [3, 4, 6, 3] {
+            for iterate in [true, false] {
+                assert_eq!(
+                    index.concave_hull(k, iterate),
+                    crate::concave_hull(&mut points.clone(), k, iterate)
+                );
+            }
+        }
+
+        let config = crate::builder::ConcaveHull::builder().k(5).build();
+        assert_eq!(config.compute_indexed(&mut index), config.compute(&points));
+        assert!(index.concave_hull(300, true).is_err());
+    }
+
+    #[test]
+    fn test_dataset() {
+        let mut rng = Rng::new(10);
+        let points: Vec<Point> = (0..300)
+            .map(|id| Point {
+                x: rng.uniform(),
+                y: rng.uniform(),
+                id,
+            })
+            .collect();
+        let expected = crate::concave_hull(&mut points.clone(), 3, true);
+
+        // clones share the points and the index, also from other threads
+        let dataset = Dataset::new(points.clone());
+        let shared = dataset.clone();
+        let hull = std::thread::spawn(move || shared.concave_hull(3, true))
+            .join()
+            .unwrap();
+        assert_eq!(hull, expected);
+        assert!(std::ptr::eq(dataset.points(), dataset.detached().points()));
+        assert_eq!(dataset.concave_hull(3, true), expected);
+        let config = crate::builder::ConcaveHull::builder().k(5).build();
+        assert_eq!(config.compute_dataset(&dataset), config.compute(&points));
+        assert_eq!(
+            crate::concave_hull_batch(std::slice::from_ref(&dataset), 3, true),
+            [expected]
+        );
+
+        let query = Point {
+            x: 0.5,
+            y: 0.5,
+            id: 0,
+        };
+        let nearest = dataset.nearest(&query, 5);
+        let mut by_distance = points.clone();
+        let distance = |p: &Point| (p.x - 0.5).hypot(p.y - 0.5);
+        by_distance.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+        assert_eq!(nearest, by_distance.iter().take(5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_index_backends() {
+        use crate::builder::ConcaveHull;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // a coarse lattice, so that many neighbours tie
+        let mut rng = Rng::new(9);
+        let points: Vec<Point> = (0..200)
+            .map(|id| Point {
+                x: (rng.uniform() * 30.0).floor(),
+                y: (rng.uniform() * 30.0).floor(),
+                id,
+            })
+            .collect();
+        let builtin = ConcaveHull::builder().k(4).build().compute(&points);
+        let brute_force = ConcaveHull::builder()
+            .k(4)
+            .index(IndexBackend::BruteForce)
+            .build();
+        assert_eq!(brute_force.compute(&points), builtin);
+
+        // a custom backend, counting the indexes it builds
+        let built = Arc::new(AtomicUsize::new(0));
+        let counter = built.clone();
+        let custom = IndexBackend::custom(move |points: &[Point], metric: Metric| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            Box::new(BruteForce::new(points, metric)) as Box<dyn NeighborIndex>
+        });
+        assert_eq!(custom, custom.clone());
+        assert_ne!(custom, IndexBackend::BruteForce);
+        let config = ConcaveHull::builder().k(4).index(custom).build();
+        let mut index = PointIndex::new(&points);
+        assert_eq!(config.compute_indexed(&mut index), builtin);
+        assert_eq!(config.compute_indexed(&mut index), builtin);
+        assert_eq!(built.load(Ordering::Relaxed), 1);
+
+        let mut tree = BruteForce::new(&points[..3], Metric::Euclidean);
+        tree.remove(&points[1]);
+        assert_eq!(tree.nearest(&points[1], 5), [0, 2]);
+    }
+
+    #[cfg(feature = "experimental-f16")]
+    #[test]
+    fn test_nearest_half() {
+        // four points tied around the origin, added in no order of id
+        let mut tree: KdTree<f16, 2> = KdTree::new();
+        for (key, id) in [
+            ([1.0, 0.0], 3),
+            ([0.0, 1.0], 1),
+            ([-1.0, 0.0], 2),
+            ([0.0, -1.0], 0),
+        ] {
+            tree.add(&key.map(f16::from_f32), id);
+        }
+        tree.add(&[f16::from_f32(2.0), f16::ZERO], 4);
+        let origin = [f16::ZERO; 2];
+        assert_eq!(nearest_half(&tree, &origin, 2), [0, 1]);
+        assert_eq!(nearest_half(&tree, &origin, 5), [0, 1, 2, 3, 4]);
+        assert!(nearest_half(&tree, &origin, 0).is_empty());
+    }
+
+    #[cfg(feature = "rstar")]
+    #[test]
+    fn test_rtree_index() {
+        use crate::builder::ConcaveHull;
+
+        // a coarse lattice, so that many neighbours tie
+        let mut rng = Rng::new(9);
+        let points: Vec<Point> = (0..200)
+            .map(|id| Point {
+                x: (rng.uniform() * 30.0).floor(),
+                y: (rng.uniform() * 30.0).floor(),
+                id,
+            })
+            .collect();
+        for metric in [Metric::Euclidean, Metric::Haversine] {
+            let mut rtree = RTreeIndex::new(&points, metric);
+            let mut brute_force = BruteForce::new(&points, metric);
+            for point in points.iter().step_by(7) {
+                assert_eq!(rtree.nearest(point, 9), brute_force.nearest(point, 9));
+                rtree.remove(point);
+                brute_force.remove(point);
+            }
+            rtree.add(&points[0]);
+            brute_force.add(&points[0]);
+            assert_eq!(
+                rtree.nearest(&points[0], 9),
+                brute_force.nearest(&points[0], 9)
+            );
+        }
+
+        let builtin = ConcaveHull::builder().k(4).build().compute(&points);
+        let rtree = ConcaveHull::builder()
+            .k(4)
+            .index(IndexBackend::RTree)
+            .build();
+        assert_eq!(rtree.compute(&points), builtin);
+        assert_eq!(format!("{:?}", IndexBackend::RTree), "RTree");
+    }
+}
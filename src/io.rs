@@ -0,0 +1,183 @@
+/// Point Sources and Hull Sinks
+///
+/// The readers and writers of the crate take a [`PointSource`] or a [`HullSink`] rather than
+/// a path, so input comes from a file, stdin, a buffer in memory, or any [`Read`] such as a
+/// download stream from an object store, and output goes to the matching destinations:
+///
+/// ```
+/// use concave_hull::io::FromReader;
+///
+/// let features = Features::read(Path::new("points.geojson"))?;
+/// let features = Features::read(std::io::stdin())?;
+/// let features = Features::read(FromReader(object.into_reader()))?;
+/// ```
+///
+/// With the `object_store` feature, S3, GCS and Azure objects are sources of their own,
+/// read in ranged requests, see [`crate::remote`]. Without it, an object is read by handing
+/// a client's download stream, as a blocking [`Read`], to [`FromReader`].
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Where points are read from.
+pub trait PointSource {
+    /// the buffered stream the source is read through
+    type Reader: BufRead;
+
+    /// Opens the source for reading.
+    fn open(self) -> io::Result<Self::Reader>;
+}
+
+/// Where hulls are written to.
+pub trait HullSink {
+    /// the stream the sink is written through
+    type Writer: Write;
+
+    /// Opens the sink for writing, replacing a file's contents.
+    fn create(self) -> io::Result<Self::Writer>;
+}
+
+/// Any reader as a [`PointSource`], buffered.
+#[derive(Debug)]
+pub struct FromReader<R>(pub R);
+
+/// Any writer as a [`HullSink`].
+#[derive(Debug)]
+pub struct IntoWriter<W>(pub W);
+
+impl PointSource for &Path {
+    type Reader = BufReader<File>;
+
+    fn open(self) -> io::Result<Self::Reader> {
+        File::open(native_path(self)).map(BufReader::new)
+    }
+}
+
+impl PointSource for PathBuf {
+    type Reader = BufReader<File>;
+
+    fn open(self) -> io::Result<Self::Reader> {
+        self.as_path().open()
+    }
+}
+
+impl PointSource for io::Stdin {
+    type Reader = io::StdinLock<'static>;
+
+    fn open(self) -> io::Result<Self::Reader> {
+        Ok(self.lock())
+    }
+}
+
+impl<'a> PointSource for &'a [u8] {
+    type Reader = &'a [u8];
+
+    fn open(self) -> io::Result<Self::Reader> {
+        Ok(self)
+    }
+}
+
+impl<R: Read> PointSource for FromReader<R> {
+    type Reader = BufReader<R>;
+
+    fn open(self) -> io::Result<Self::Reader> {
+        Ok(BufReader::new(self.0))
+    }
+}
+
+impl HullSink for &Path {
+    type Writer = BufWriter<File>;
+
+    fn create(self) -> io::Result<Self::Writer> {
+        File::create(native_path(self)).map(BufWriter::new)
+    }
+}
+
+impl HullSink for PathBuf {
+    type Writer = BufWriter<File>;
+
+    fn create(self) -> io::Result<Self::Writer> {
+        self.as_path().create()
+    }
+}
+
+impl HullSink for io::Stdout {
+    type Writer = io::StdoutLock<'static>;
+
+    fn create(self) -> io::Result<Self::Writer> {
+        Ok(self.lock())
+    }
+}
+
+impl<'a> HullSink for &'a mut Vec<u8> {
+    type Writer = &'a mut Vec<u8>;
+
+    fn create(self) -> io::Result<Self::Writer> {
+        Ok(self)
+    }
+}
+
+impl<W: Write> HullSink for IntoWriter<W> {
+    type Writer = W;
+
+    fn create(self) -> io::Result<Self::Writer> {
+        Ok(self.0)
+    }
+}
+
+/// `path` in the form the operating system opens at any length.
+///
+/// On Windows, absolute paths get the `\\?\` (or `\\?\UNC\`) prefix, which lifts the
+/// 260 character `MAX_PATH` limit, and forward slashes become backslashes, which such paths
+/// need. Relative paths and paths with `.` or `..` components, which the prefix would stop
+/// Windows from resolving, are returned unchanged, as are all paths on other systems.
+pub fn native_path(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        use std::path::Component;
+
+        let dots = path
+            .components()
+            .any(|c| matches!(c, Component::CurDir | Component::ParentDir));
+        if let (Some(text), true, false) = (path.to_str(), path.is_absolute(), dots) {
+            if !text.starts_with(r"\\?\") {
+                let text = text.replace('/', r"\");
+                return match text.strip_prefix(r"\\") {
+                    Some(share) => PathBuf::from(format!(r"\\?\UNC\{}", share)),
+                    None => PathBuf::from(format!(r"\\?\{}", text)),
+                };
+            }
+        }
+    }
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sources_and_sinks() {
+        let mut buffer = Vec::new();
+        let mut writer = (&mut buffer).create().unwrap();
+        writeln!(writer, "1 2").unwrap();
+        writeln!(IntoWriter(&mut writer).create().unwrap(), "3 4").unwrap();
+        assert_eq!(buffer, b"1 2\n3 4\n");
+
+        let path = std::env::temp_dir().join(format!("concave-hull-io-{}", std::process::id()));
+        let mut file = path.as_path().create().unwrap();
+        file.write_all(&buffer).unwrap();
+        drop(file);
+        let lines = |source: &mut dyn BufRead| source.lines().count();
+        assert_eq!(lines(&mut path.clone().open().unwrap()), 2);
+        assert_eq!(
+            lines(&mut FromReader(File::open(&path).unwrap()).open().unwrap()),
+            2
+        );
+        assert_eq!(lines(&mut buffer.as_slice().open().unwrap()), 2);
+        std::fs::remove_file(&path).unwrap();
+
+        #[cfg(not(windows))]
+        assert_eq!(native_path(&path), path);
+    }
+}
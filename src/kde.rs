@@ -0,0 +1,152 @@
+/// Density Contour Hulls from a Kernel Density Estimate
+use crate::point::{BoundingBox, Point};
+
+/// kernels are cut off beyond this many bandwidths
+const KERNEL_REACH: f64 = 3.0;
+
+/// Bandwidth of a Gaussian kernel by Silverman's rule of thumb in two dimensions.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+///
+/// # Returns
+///
+/// Returns the pooled standard deviation of the coordinates times `n^(-1/6)`, or 0 for fewer
+/// than two points.
+pub fn silverman_bandwidth(points: &[Point]) -> f64 {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return 0.0;
+    }
+    let (mx, my) = (
+        points.iter().map(|p| p.x).sum::<f64>() / n,
+        points.iter().map(|p| p.y).sum::<f64>() / n,
+    );
+    let variance = points
+        .iter()
+        .map(|p| ((p.x - mx).powi(2) + (p.y - my).powi(2)) / 2.0)
+        .sum::<f64>()
+        / (n - 1.0);
+    variance.sqrt() * n.powf(-1.0 / 6.0)
+}
+
+/// Computes the density contour enclosing a given probability mass, e.g. the 95% isopleth
+/// of a utilization distribution.
+///
+/// A Gaussian kernel density estimate is evaluated at the cell centres of a grid over the
+/// points, padded by three bandwidths. The densest cells are taken until they hold `mass`
+/// of the total density, and the outline of those cells is traced as the hull. The outline
+/// follows the grid, so it steps at cell resolution; [`crate::smooth::smooth`] rounds it off.
+/// Where the selected cells form several separate regions, the one holding the most density
+/// is traced.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `mass` - the probability mass (0 to 1) the contour encloses.
+/// * `bandwidth` - standard deviation of the kernel; see [`silverman_bandwidth`] for a
+///   default.
+/// * `resolution` - cells along the longer side of the grid.
+///
+/// # Returns
+///
+/// Returns the contour as a closed counter-clockwise ring of cell corners, which carry no
+/// point ids (0). Returns an empty vector if there are no points or `bandwidth` or
+/// `resolution` is not positive.
+pub fn kde_contour(points: &[Point], mass: f64, bandwidth: f64, resolution: usize) -> Vec<Point> {
+    if points.is_empty() || bandwidth.is_nan() || bandwidth <= 0.0 || resolution == 0 {
+        return Vec::new();
+    }
+
+    let mut bbox = BoundingBox::from_points(points);
+    let pad = KERNEL_REACH * bandwidth;
+    bbox.min_x -= pad;
+    bbox.min_y -= pad;
+    bbox.max_x += pad;
+    bbox.max_y += pad;
+    let cell = bbox.width().max(bbox.height()) / resolution as f64;
+    let nx = (bbox.width() / cell).ceil() as usize;
+    let ny = (bbox.height() / cell).ceil() as usize;
+
+    // kernels truncated at their reach, added to the cells they cover
+    let mut density = vec![0.0f64; nx * ny];
+    let reach = (pad / cell).ceil() as i64;
+    let scale = -0.5 / (bandwidth * bandwidth);
+    for p in points {
+        let (ci, cj) = (
+            ((p.x - bbox.min_x) / cell) as i64,
+            ((p.y - bbox.min_y) / cell) as i64,
+        );
+        for j in (cj - reach).max(0)..(cj + reach + 1).min(ny as i64) {
+            let y = bbox.min_y + (j as f64 + 0.5) * cell;
+            for i in (ci - reach).max(0)..(ci + reach + 1).min(nx as i64) {
+                let x = bbox.min_x + (i as f64 + 0.5) * cell;
+                let r2 = (x - p.x).powi(2) + (y - p.y).powi(2);
+                density[j as usize * nx + i as usize] += (scale * r2).exp();
+            }
+        }
+    }
+
+    // the density above which the cells hold `mass` of the total
+    let mut sorted = density.clone();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    let target = mass.clamp(0.0, 1.0) * sorted.iter().sum::<f64>();
+    let mut cumulative = 0.0;
+    let mut threshold = sorted[0];
+    for &d in sorted.iter() {
+        threshold = d;
+        cumulative += d;
+        if cumulative >= target {
+            break;
+        }
+    }
+
+    // cell weights in units of the threshold, so regions are compared by density
+    let count: Vec<usize> = density
+        .iter()
+        .map(|&d| {
+            if d >= threshold && d > 0.0 {
+                (1024.0 * d / threshold) as usize
+            } else {
+                0
+            }
+        })
+        .collect();
+    let mut ids = vec![0u64; nx * ny];
+    crate::grid::trace(&count, &mut ids, nx, ny, (bbox.min_x, bbox.min_y), cell)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_kde_contour() {
+        let mut rng = Rng::new(5);
+        let points: Vec<Point> = (0..2000)
+            .map(|id| Point {
+                x: rng.normal(),
+                y: rng.normal(),
+                id,
+            })
+            .collect();
+        let bandwidth = silverman_bandwidth(&points);
+        assert!((bandwidth - 2000f64.powf(-1.0 / 6.0)).abs() < 0.05);
+
+        let area = |ring: &[Point]| crate::buffer::signed_area(ring);
+        // the smoothed density is normal with variance 1 + h^2, and a disc of radius r
+        // holds 1 - exp(-r^2 / (2 (1 + h^2))) of it
+        let variance = 1.0 + bandwidth * bandwidth;
+        let half = kde_contour(&points, 0.5, bandwidth, 200);
+        let expected = std::f64::consts::PI * 2.0 * variance * 2f64.ln();
+        assert!((area(&half) - expected).abs() < 0.15 * expected);
+        assert_eq!(half.first(), half.last());
+
+        let most = kde_contour(&points, 0.95, bandwidth, 200);
+        let expected = std::f64::consts::PI * 2.0 * variance * 20f64.ln();
+        assert!((area(&most) - expected).abs() < 0.15 * expected);
+        assert!(kde_contour(&points, 0.5, 0.0, 200).is_empty());
+    }
+}
@@ -1,159 +1,1399 @@
 #![feature(int_roundings)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+// pyo3 0.20's #[pymethods] expansion trips this lint on newer compilers
+#![allow(non_local_definitions)]
 /// Fast Concave Hull Implementation
 ///
 /// This module provides an efficient implementation of the concave hull algorithm,
 /// using a k-nearest neighbour approach.
-use kiddo::{KdTree, SquaredEuclidean};
-
-use std::collections::HashMap;
+///
+/// The hull depends on nothing but the input and its order. Neighbours are taken by
+/// distance and then by position in the input, and the candidates of each step by turning
+/// angle, then distance, then position, so ties, common on lattices and rounded
+/// coordinates, are broken the same way on every platform and with every neighbour index.
+use std::cell::{Cell, RefCell};
 
+pub mod antimeridian;
+pub mod approx;
+#[cfg(feature = "python")]
 pub mod binding;
-mod intersect;
+pub mod boundary;
+pub mod buffer;
+pub mod build;
+pub mod builder;
+pub mod convex;
+pub mod crs;
+pub mod enclosing;
+pub mod error;
+pub mod evaluate;
+pub mod export;
+#[cfg(feature = "float")]
+pub mod float;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+#[cfg(feature = "gpkg")]
+pub mod gpkg;
+pub mod grid;
+pub mod index;
+pub mod io;
+pub mod kde;
+pub mod metric;
+pub mod moments;
+pub mod multi;
+pub mod periodic;
+pub mod pipeline;
+pub mod pivoting;
 pub mod point;
-use intersect::intersects;
-use point::{normalise_angle, Point, PointValue};
+pub mod polygon;
+pub mod polyline;
+pub mod predicates;
+pub mod prepared;
+pub mod recipes;
+pub mod regularize;
+#[cfg(feature = "object_store")]
+pub mod remote;
+pub mod retry;
+pub mod simplify;
+pub mod smooth;
+pub mod stats;
+pub mod streaming;
+#[cfg(any(not(feature = "kd-tree"), test))]
+mod sweep;
+pub mod synthetic;
+pub mod tiles;
+pub mod trajectory;
+pub mod transform;
+pub mod triangulation;
+pub mod v1;
+pub mod voronoi;
+pub mod workspace;
+use builder::Escalation;
+use error::ConcaveHullError;
+use index::{IndexBackend, IndexPrecision, NeighborIndex, PointIndex};
+use metric::{Metric, Tolerance};
+use point::{normalise_angle, HullPoint, Point};
+use polygon::{count_outside, point_in_polygon, Containment};
+use predicates::{intersects_within, last_crossing};
+use retry::Crossing;
+use stats::{Attempt, EdgeTests, Failure, HullStats, Progress};
+use workspace::{Neighbour, Workspace};
+
+pub use build::build_info;
 
 /// Computes the concave hull of a given set of points.
 ///
 /// This function iteratively calls k-nearest neighbors based concave hull algorithm.
 ///
+/// Points are told apart by their position in `dataset`; their ids are labels copied into
+/// the hull, and may repeat.
+///
 /// # Arguments
 ///
 /// * `dataset` -  2D point cloud.
 /// * `k` - number of nearest neighbors.
 /// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
-///               as soon as the algorithm succeeds.
+///   as soon as the algorithm succeeds.
 ///
 /// # Returns
 ///
-/// Returns a vector of `Point` structures representing the concave hull of the provided dataset.
-/// If the hull cannot be computed, it returns an empty vector.
+/// Returns a vector of `Point` structures representing the concave hull of the provided dataset,
+/// running clockwise as the walk traces it (see [`orient`] for the other order),
+/// or a [`ConcaveHullError`] if it has a non-finite coordinate, no more than `k` points, or
+/// no hull enclosing every point was found (with `iterate` unset, at the given `k`).
 ///
 /// # Examples
 ///
 /// ```
 /// let mut dataset = vec![Point { x: 1.0, y: 1.0, id: 1 }, Point { x: 2.0, y: 2.0, id: 2 }];
 /// let k = 3;
-/// let hull = concave_hull(&mut dataset, k, true);
+/// let hull = concave_hull(&mut dataset, k, true)?;
 /// ```
-pub fn concave_hull(dataset: &mut Vec<Point>, mut k: usize, iterate: bool) -> Vec<Point> {
-    while k < dataset.len() {
-        let mut hull = Vec::<Point>::new();
-        if concave_hull_inner(dataset, k, &mut hull) || !iterate {
-            return hull;
+pub fn concave_hull(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    try_concave_hull(dataset, k, iterate, usize::MAX, &Options::default())
+}
+
+/// Computes the concave hull of a given set of points, leaving them untouched.
+///
+/// [`concave_hull`] removes the vertices of every rejected hull from its dataset, so each
+/// further attempt runs on fewer points; this variant keeps track of the removed points
+/// instead, and gives the same hull. The points are borrowed as they are for the first
+/// attempt, and only a rejected hull that left points outside makes a copy of the points that
+/// remain for the next.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull or the error as [`concave_hull`] does.
+pub fn concave_hull_borrowed(
+    dataset: &[Point],
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    PointIndex::new(dataset)
+        .search(k, iterate, usize::MAX, &Options::default(), false)
+        .map(|(hull, _)| hull)
+}
+
+/// Computes the concave hulls of many independent point sets.
+///
+/// Each dataset gets the hull [`concave_hull_borrowed`] would find for it, but the buffers of
+/// the computation are kept from one dataset to the next, see [`workspace::Workspace`], so
+/// thousands of small clusters, e.g. one per vehicle and day, cost little more than their
+/// walks.
+///
+/// # Arguments
+///
+/// * `datasets` - the 2D point clouds, e.g. `Vec<Point>`s; they are left unchanged.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull or the error of [`concave_hull`] for each dataset, in their order.
+pub fn concave_hull_batch<D: AsRef<[Point]>>(
+    datasets: &[D],
+    k: usize,
+    iterate: bool,
+) -> Vec<Result<Vec<Point>, ConcaveHullError>> {
+    let mut workspace = Workspace::default();
+    datasets
+        .iter()
+        .map(|dataset| {
+            workspace::concave_hull_with_workspace(dataset.as_ref(), k, iterate, &mut workspace)
+                .map(|hull| hull.to_vec())
+        })
+        .collect()
+}
+
+/// Computes the concave hulls of many independent point sets on several threads.
+///
+/// The datasets are handed out one at a time to whichever thread is free, so a few large
+/// ones don't hold up the rest, and each thread keeps its own buffers as
+/// [`concave_hull_batch`] does. The hulls are those [`concave_hull_batch`] finds, whatever
+/// the number of threads.
+///
+/// # Arguments
+///
+/// * `datasets` - the 2D point clouds, e.g. `Vec<Point>`s; they are left unchanged.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+/// * `threads` - the number of threads to use, `0` for as many as the machine runs at once.
+///
+/// # Returns
+///
+/// Returns the hull or the error of [`concave_hull`] for each dataset, in their order.
+#[cfg(feature = "parallel")]
+pub fn concave_hull_batch_with_threads<D: AsRef<[Point]> + Sync>(
+    datasets: &[D],
+    k: usize,
+    iterate: bool,
+    threads: usize,
+) -> Vec<Result<Vec<Point>, ConcaveHullError>> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let threads = thread_count(threads).min(datasets.len());
+    if threads <= 1 {
+        return concave_hull_batch(datasets, k, iterate);
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut hulls: Vec<Option<Result<Vec<Point>, ConcaveHullError>>> =
+        datasets.iter().map(|_| None).collect();
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut workspace = Workspace::default();
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(dataset) = datasets.get(i) else {
+                            break done;
+                        };
+                        let hull = workspace::concave_hull_with_workspace(
+                            dataset.as_ref(),
+                            k,
+                            iterate,
+                            &mut workspace,
+                        );
+                        done.push((i, hull.map(|hull| hull.to_vec())));
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            for (i, hull) in worker.join().unwrap() {
+                hulls[i] = Some(hull);
+            }
         }
-        k += 1;
+    });
+    hulls
+        .into_iter()
+        .map(|hull| hull.expect("every dataset is taken by a thread"))
+        .collect()
+}
+
+/// Computes the concave hull of points of any type with coordinates.
+///
+/// The points need no ids: they are identified by their position in `dataset`, and the hull
+/// is made of clones of the input points, so tuples, arrays and application types with
+/// attributes come back as they went in. The hull is the one [`concave_hull_borrowed`] finds
+/// for [`Point`]s with the same coordinates and their positions as ids.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud, e.g. `(f64, f64)` tuples, `[f32; 2]` arrays or any type
+///   implementing [`HullPoint`].
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull as a closed ring of input points, or the error of [`concave_hull`]; an
+/// [`ConcaveHullError::InvalidCoordinate`] id is the position of the point.
+pub fn concave_hull_of<P: HullPoint + Clone>(
+    dataset: &[P],
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<P>, ConcaveHullError> {
+    let indices = concave_hull_indices(dataset, k, iterate)?;
+    Ok(indices.into_iter().map(|i| dataset[i].clone()).collect())
+}
+
+/// Computes the concave hull of a given set of points as positions in `dataset`.
+///
+/// Nothing is cloned, so the hull can be joined back to attributes kept in arrays parallel
+/// to the points. The hull is the one [`concave_hull_of`] finds.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud, of any type implementing [`HullPoint`].
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull as a closed ring of indices into `dataset`, or the error of
+/// [`concave_hull_of`].
+pub fn concave_hull_indices<P: HullPoint>(
+    dataset: &[P],
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<usize>, ConcaveHullError> {
+    let points: Vec<Point> = dataset
+        .iter()
+        .enumerate()
+        .map(|(i, p)| Point {
+            x: p.x(),
+            y: p.y(),
+            id: i as u64,
+        })
+        .collect();
+    let hull = concave_hull_borrowed(&points, k, iterate)?;
+    Ok(hull.iter().map(|p| p.id as usize).collect())
+}
+
+/// Checks that `dataset` has finite coordinates and more than `k` points.
+fn check_input(dataset: &[Point], k: usize) -> Result<(), ConcaveHullError> {
+    if let Some(p) = dataset
+        .iter()
+        .find(|p| !(p.x.is_finite() && p.y.is_finite()))
+    {
+        return Err(ConcaveHullError::InvalidCoordinate { id: p.id });
+    }
+    if dataset.len() <= k {
+        return Err(ConcaveHullError::InsufficientPoints {
+            required: k + 1,
+            found: dataset.len(),
+        });
     }
+    Ok(())
+}
 
-    Vec::<Point>::new()
+/// Raises `k` up to `max_k` until a hull is accepted, as [`concave_hull`] does.
+fn try_concave_hull(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+    max_k: usize,
+    options: &Options,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    search(dataset, k, iterate, max_k, options, false).map(|(hull, _)| hull)
 }
 
-fn concave_hull_inner(point_list: &mut Vec<Point>, k: usize, hull: &mut Vec<Point>) -> bool {
-    hull.clear();
+/// Raises `k` as [`try_concave_hull`] does, counting the attempts and, with `history` set,
+/// recording every one of them.
+///
+/// The vertices of the hulls that left points outside are removed from `dataset`.
+fn search(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+    max_k: usize,
+    options: &Options,
+    history: bool,
+) -> Result<(Vec<Point>, HullStats), ConcaveHullError> {
+    let mut index = PointIndex::new(dataset);
+    let found = index.search(k, iterate, max_k, options, history);
+    let dropped = index.dropped().to_vec();
+    remove_vertices(dataset, &dropped);
+    found
+}
+
+/// Raises `k` up to `max_k` by `options.escalation` until a hull of `points` is accepted
+/// into `hull`, counting the attempts and, with `history` set, recording every one of them.
+///
+/// Every hull variant searches in here. `tree` holds `points` with their positions as ids,
+/// as in `positions`. The vertices of a hull that leaves points outside are taken out of
+/// the tree for the later attempts, and put back before this returns. A step past the
+/// largest `k` worth trying tries that one instead.
+///
+/// Fails with [`ConcaveHullError::Cancelled`] once `options.interrupt` fires or
+/// `options.observe` abandons a hull.
+#[allow(clippy::too_many_arguments)]
+fn search_in(
+    points: &[Point],
+    positions: &[Point],
+    tree: &mut dyn NeighborIndex,
+    mut k: usize,
+    iterate: bool,
+    max_k: usize,
+    options: &Options,
+    history: bool,
+    hull: &mut Vec<Point>,
+    workspace: &mut Workspace,
+) -> Result<HullStats, ConcaveHullError> {
+    check_input(points, k)?;
 
-    if let 0..=3 = point_list.len() {
-        hull.extend(point_list.iter().cloned());
-        return true;
+    // sorted positions of the vertices of rejected hulls, which are left out from then on
+    let mut dropped = std::mem::take(&mut workspace.dropped);
+    dropped.clear();
+    let mut max_k_tried = k;
+    let mut stats = HullStats::default();
+    let found = loop {
+        if k >= points.len() - dropped.len() || k > max_k {
+            break Err(ConcaveHullError::NoValidHullFound { max_k_tried });
+        }
+        if options.interrupted() {
+            break Err(ConcaveHullError::Cancelled);
+        }
+        max_k_tried = k;
+
+        let checked = walk(
+            points, positions, &dropped, tree, k, options, hull, workspace,
+        )
+        .and_then(|()| {
+            check_required(hull, options)?;
+            if let Some(coverage) = &options.coverage {
+                // too little weight is raised past like a crossing, keeping every point
+                return coverage.check(hull, &workspace.vertices).map(Ok);
+            }
+            let rest = points
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| {
+                    workspace.vertices.binary_search(i).is_err()
+                        && dropped.binary_search(i).is_err()
+                })
+                .map(|(_, p)| p);
+            Ok(check_contained(rest, hull, options))
+        });
+        #[cfg(feature = "tracing")]
+        match &checked {
+            Ok(Ok(())) => tracing::debug!(k, vertices = hull.len(), "hull accepted"),
+            Ok(Err(failure)) | Err(failure) => tracing::debug!(k, ?failure, "hull rejected"),
+        }
+        let tests = options.edge_tests.take();
+        stats.attempts += 1;
+        stats.edge_tests.performed += tests.performed;
+        stats.edge_tests.pruned += tests.pruned;
+        if history {
+            stats.history.push(Attempt {
+                k,
+                failure: checked.and_then(|contained| contained).err(),
+                hull_size: hull.len(),
+            });
+        }
+        match checked {
+            Ok(Ok(())) => {
+                stats.k = Some(k);
+                break Ok(stats);
+            }
+            Ok(Err(_)) => {
+                for &vertex in workspace.vertices.iter() {
+                    tree.remove(&positions[vertex]);
+                }
+                dropped.extend_from_slice(&workspace.vertices);
+                dropped.sort_unstable();
+            }
+            Err(Failure::Interrupted) => break Err(ConcaveHullError::Cancelled),
+            Err(_) => {}
+        }
+        let next = iterate.then(|| options.escalation.next(k)).flatten();
+        let Some(next) = next else {
+            break Err(ConcaveHullError::NoValidHullFound { max_k_tried });
+        };
+        let last = max_k.min((points.len() - dropped.len()).saturating_sub(1));
+        k = if next > last && k < last { last } else { next };
+    };
+
+    for &position in dropped.iter() {
+        tree.add(&positions[position]);
     }
+    workspace.dropped = dropped;
+    found
+}
 
-    // build a kd tree so we can do the spatial queries
-    let mut tree: KdTree<_, 2> = KdTree::new(); //(&entries).into();
-    for point in point_list.iter() {
-        tree.add(&[point.x, point.y], point.id)
+/// Computes the concave hull of a given set of points and reports how it was found.
+///
+/// This works like [`concave_hull`], and additionally returns the `k` of the returned hull
+/// and the number of hulls attempted. With `history` set, every attempt is recorded with
+/// its `k`, the reason it was rejected and the size of the partial hull, which shows why a
+/// dataset needed a large `k`.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+/// * `history` - whether to record every attempt in [`HullStats::history`].
+///
+/// # Returns
+///
+/// Returns the hull and the search statistics, or the error, as [`concave_hull`] does.
+pub fn concave_hull_with_stats(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+    history: bool,
+) -> Result<(Vec<Point>, HullStats), ConcaveHullError> {
+    search(
+        dataset,
+        k,
+        iterate,
+        usize::MAX,
+        &Options::default(),
+        history,
+    )
+}
+
+/// Computes the concave hull with the given metric, raising `k` like [`concave_hull_with_stats`].
+pub(crate) fn concave_hull_with_metric(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+    metric: Metric,
+) -> Result<(Vec<Point>, HullStats), ConcaveHullError> {
+    let options = Options {
+        metric,
+        ..Default::default()
+    };
+    search(dataset, k, iterate, usize::MAX, &options, true)
+}
+
+/// Weighting thresholds used by [`concave_hull_weighted`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weighting {
+    /// points with a weight below this value are never used as hull vertices
+    pub min_vertex_weight: f64,
+    /// fraction (0 to 1) of the total weight that must lie on or inside the hull
+    pub min_coverage: f64,
+}
+
+impl Default for Weighting {
+    fn default() -> Self {
+        Weighting {
+            min_vertex_weight: f64::NEG_INFINITY,
+            min_coverage: 1.0,
+        }
     }
+}
 
-    // map id to points for lookup
-    let mut point_map: HashMap<u64, Point> = point_list
-        .iter()
-        .map(|point| (point.id, point.clone()))
+/// Computes the concave hull of a weighted set of points.
+///
+/// This works like [`concave_hull`], except that only points whose weight reaches
+/// `weighting.min_vertex_weight` may become hull vertices, and a hull is accepted once
+/// it covers at least `weighting.min_coverage` of the total weight. Low-confidence
+/// stragglers therefore neither define the boundary nor force the hull to grow around them.
+///
+/// # Arguments
+///
+/// * `dataset` - 2D point cloud.
+/// * `weights` - one weight per point of `dataset`.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+/// * `weighting` - vertex and coverage thresholds.
+///
+/// # Returns
+///
+/// Returns the hull, or the error as [`concave_hull`] does; too few points reaching the
+/// vertex weight are [`ConcaveHullError::InsufficientPoints`], and no hull covering enough
+/// of the weight is [`ConcaveHullError::NoValidHullFound`].
+///
+/// # Panics
+///
+/// Panics if `weights` and `dataset` have different lengths.
+pub fn concave_hull_weighted(
+    dataset: &[Point],
+    weights: &[f64],
+    k: usize,
+    iterate: bool,
+    weighting: &Weighting,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    assert_eq!(
+        dataset.len(),
+        weights.len(),
+        "a weight is required for every point"
+    );
+
+    // positions in `dataset` of the points that may be vertices
+    let candidates: Vec<usize> = (0..dataset.len())
+        .filter(|&i| weights[i] >= weighting.min_vertex_weight)
         .collect();
+    let mut walked: Vec<Point> = candidates.iter().map(|&i| dataset[i].clone()).collect();
+    check_input(dataset, k)?;
+    let options = Options {
+        coverage: Some(Coverage {
+            points: dataset,
+            weights,
+            candidates,
+            needed: weighting.min_coverage * weights.iter().sum::<f64>(),
+        }),
+        ..Default::default()
+    };
+
+    try_concave_hull(&mut walked, k, iterate, usize::MAX, &options)
+}
+
+/// The weight a hull of [`concave_hull_weighted`] must cover, instead of every point.
+struct Coverage<'a> {
+    /// the whole dataset
+    points: &'a [Point],
+    /// one weight per point of `points`
+    weights: &'a [f64],
+    /// increasing positions in `points` of the points walked over
+    candidates: Vec<usize>,
+    /// the weight that must lie on or inside the hull
+    needed: f64,
+}
+
+impl Coverage<'_> {
+    /// Checks that `hull`, with the sorted walked `vertices`, covers the needed weight.
+    fn check(&self, hull: &[Point], vertices: &[usize]) -> Result<(), Failure> {
+        if covered_weight(self, hull, vertices) >= self.needed {
+            Ok(())
+        } else {
+            Err(Failure::PointsOutside)
+        }
+    }
+}
+
+/// Computes the concave hull of a set of points, routing the boundary through required vertices.
+///
+/// Whenever one of the `required` points is among the nearest neighbours of the walk, it is
+/// preferred over the other candidates. A hull is only accepted if every required point ended
+/// up as one of its vertices, so with `iterate` set, `k` grows until that is the case.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud.
+/// * `required` - ids of the points that must be hull vertices.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull, or the error, as [`concave_hull`] does.
+pub fn concave_hull_with_required(
+    dataset: &mut Vec<Point>,
+    required: &[u64],
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let mut required = required.to_vec();
+    required.sort_unstable();
+    required.dedup();
+    let options = Options {
+        required,
+        ..Default::default()
+    };
+
+    try_concave_hull(dataset, k, iterate, usize::MAX, &options)
+}
+
+/// How many points a robust hull may leave outside, see [`concave_hull_robust`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outliers {
+    /// at most this many points
+    Count(usize),
+    /// at most this fraction (0 to 1) of the points
+    Fraction(f64),
+}
+
+impl Outliers {
+    /// number of points out of `n` that may be left outside
+    pub fn allowance(&self, n: usize) -> usize {
+        match *self {
+            Outliers::Count(count) => count.min(n),
+            Outliers::Fraction(fraction) => (fraction.clamp(0.0, 1.0) * n as f64).floor() as usize,
+        }
+    }
+}
+
+/// Computes the concave hull of a given set of points, tolerating outliers.
+///
+/// A regular hull must contain every point, so a few gross outliers force `k` up until the
+/// hull balloons around them. Here an attempted hull is accepted as long as no more than
+/// `outliers` points remain outside of it.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+/// * `outliers` - the number or fraction of points that may be left outside.
+///
+/// # Returns
+///
+/// Returns the hull, or the error, as [`concave_hull`] does.
+pub fn concave_hull_robust(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+    outliers: Outliers,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let options = Options {
+        max_outside: outliers.allowance(dataset.len()),
+        ..Default::default()
+    };
+
+    try_concave_hull(dataset, k, iterate, usize::MAX, &options)
+}
+
+/// Computes the concave hull of the densest part of a point cloud, its "core area".
+///
+/// Density is estimated from the distance of every point to its `density_k`-th nearest
+/// neighbour, and the hull is computed over the `fraction` of points with the smallest such
+/// distances. With a fraction of 0.5 this gives the polygon of the 50% core in the sense of
+/// a utilization distribution, without filtering the points beforehand.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud.
+/// * `fraction` - the share (0 to 1) of the points to keep, densest first.
+/// * `density_k` - the neighbour whose distance estimates the density around a point.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull of the core points, or the error, as [`concave_hull`] does.
+pub fn concave_hull_core(
+    dataset: &[Point],
+    fraction: f64,
+    density_k: usize,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let distances = stats::knn_distances(dataset, density_k);
+    let mut order: Vec<usize> = (0..dataset.len()).collect();
+    order.sort_by(|&a, &b| distances[a].total_cmp(&distances[b]));
+
+    let keep = (fraction.clamp(0.0, 1.0) * dataset.len() as f64).ceil() as usize;
+    let mut core: Vec<Point> = order[..keep].iter().map(|&i| dataset[i].clone()).collect();
+    concave_hull(&mut core, k, iterate)
+}
+
+/// Computes both the concave and the convex hull of a given set of points.
+///
+/// The convex hull of the points is the convex hull of any polygon that encloses them all,
+/// so once the concave hull is found, the convex hull is taken from its vertices rather than
+/// from the whole dataset.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the concave hull, as [`concave_hull`] does, and the convex hull, as
+/// [`convex::convex_hull`] does, or the error of the concave hull.
+pub fn hulls(
+    dataset: &[Point],
+    k: usize,
+    iterate: bool,
+) -> Result<(Vec<Point>, Vec<Point>), ConcaveHullError> {
+    let concave = concave_hull(&mut dataset.to_vec(), k, iterate)?;
+    let convex = convex::convex_hull(&concave);
+    Ok((concave, convex))
+}
+
+/// Computes the concave hull of points given as longitude (x) and latitude (y) in degrees.
+///
+/// Neighbour queries use great-circle distances and the walk turns by great-circle bearings,
+/// so hulls at high latitudes are not stretched along the parallels the way planar degrees
+/// would be. Edges are still tested for intersection as straight lines in degrees, which is
+/// accurate while they stay short compared to the Earth and don't cross the antimeridian;
+/// see [`antimeridian::concave_hull_antimeridian`] for data straddling it.
+///
+/// # Arguments
+///
+/// * `dataset` -  longitude/latitude point cloud.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull, or the error, as [`concave_hull`] does.
+pub fn concave_hull_geographic(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let options = Options {
+        metric: Metric::Haversine,
+        ..Default::default()
+    };
+
+    try_concave_hull(dataset, k, iterate, usize::MAX, &options)
+}
+
+/// Computes the concave hull of a given set of points with a choice of index precision.
+///
+/// With the `experimental-f16` feature, [`IndexPrecision::Half`] stores the neighbour index
+/// coordinates as half-precision floats, a quarter of the memory of the default `f64`
+/// coordinates, which is what bounds footprint extraction over billions of points. The
+/// index only proposes neighbours; angles, intersections and the returned hull all use the
+/// full precision points.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+/// * `precision` - coordinate storage of the neighbour index.
+///
+/// # Returns
+///
+/// Returns the hull, or the error, as [`concave_hull`] does.
+pub fn concave_hull_with_precision(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+    precision: IndexPrecision,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let options = Options {
+        precision,
+        ..Default::default()
+    };
+
+    try_concave_hull(dataset, k, iterate, usize::MAX, &options)
+}
+
+/// Computes the hull like [`concave_hull`] (or [`concave_hull_geographic`] for the
+/// `Haversine` metric), polling `interrupt` every few hundred steps of the walk and splitting
+/// long scans among `threads` threads as [`concave_hull_batch_with_threads`] does.
+///
+/// Fails with [`ConcaveHullError::Cancelled`] as soon as `interrupt` returns `true`.
+#[cfg(any(feature = "python", test))]
+pub(crate) fn concave_hull_interruptible(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+    metric: Metric,
+    threads: usize,
+    interrupt: Box<dyn Fn() -> bool + Send + Sync>,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let options = Options {
+        metric,
+        threads,
+        interrupt: Some(interrupt),
+        ..Default::default()
+    };
+    try_concave_hull(dataset, k, iterate, usize::MAX, &options)
+}
+
+/// Computes the hull like [`concave_hull`], passing the partial hull to `observe` after every
+/// step of the walk.
+///
+/// Fails with [`ConcaveHullError::Cancelled`] as soon as `observe` returns `false`.
+#[cfg(any(feature = "python", test))]
+pub(crate) fn concave_hull_observed(
+    dataset: &mut Vec<Point>,
+    k: usize,
+    iterate: bool,
+    observe: Observer,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let options = Options {
+        observe: Some(observe),
+        ..Default::default()
+    };
+    try_concave_hull(dataset, k, iterate, usize::MAX, &options)
+}
+
+/// Called with the partial hull after every step of the walk; `false` abandons the hull.
+pub(crate) type Observer = Box<dyn Fn(&[Point]) -> bool + Send + Sync>;
+
+/// Called with the progress of the walk every few hundred steps and when a walk ends.
+pub(crate) type ProgressCallback<'a> = Box<dyn FnMut(Progress) + 'a>;
+
+/// Settings shared by the hull variants for every attempted hull.
+#[derive(Default)]
+struct Options<'a> {
+    /// sorted ids of the points that must be hull vertices
+    required: Vec<u64>,
+    /// number of points that may be left outside the hull
+    max_outside: usize,
+    /// how neighbours and turning angles are measured
+    metric: Metric,
+    /// below which the crossing and containment tests treat values as zero
+    tolerance: Tolerance,
+    /// the test the points left out of a hull are checked to be inside it by
+    containment: Containment,
+    /// coordinate storage of the neighbour index
+    precision: IndexPrecision,
+    /// the neighbour index to search
+    backend: IndexBackend,
+    /// threads the long crossing and containment scans of the `parallel` feature are split
+    /// among, 0 for as many as the machine runs at once
+    threads: usize,
+    /// how `k` is raised after a rejected hull
+    escalation: Escalation,
+    /// the weight a hull must cover instead of every point, see [`concave_hull_weighted`]
+    coverage: Option<Coverage<'a>>,
+    /// polled during the walk; the hull is abandoned once it returns `true`
+    interrupt: Option<Box<dyn Fn() -> bool + Send + Sync>>,
+    /// called with the partial hull after every step of the walk; the hull is abandoned
+    /// once it returns `false`
+    observe: Option<Observer>,
+    /// told how far the walk has got
+    progress: Option<RefCell<ProgressCallback<'a>>>,
+    /// the candidates of the step the last walk got stuck on, and the edges they cross
+    crossings: RefCell<Vec<Crossing>>,
+    /// the edge intersection tests of the walks so far
+    edge_tests: Cell<EdgeTests>,
+}
+
+impl Options<'_> {
+    fn interrupted(&self) -> bool {
+        self.interrupt.as_ref().is_some_and(|interrupt| interrupt())
+    }
+
+    fn report(&self, progress: Progress) {
+        if let Some(callback) = &self.progress {
+            (callback.borrow_mut())(progress);
+        }
+    }
+
+    fn count_edge_tests(&self, tests: EdgeTests) {
+        let total = self.edge_tests.get();
+        self.edge_tests.set(EdgeTests {
+            performed: total.performed + tests.performed,
+            pruned: total.pruned + tests.pruned,
+        });
+    }
+}
+
+/// `threads`, or for 0 as many threads as the machine runs at once
+#[cfg(feature = "parallel")]
+pub(crate) fn thread_count(threads: usize) -> usize {
+    match threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        threads => threads,
+    }
+}
+
+/// below this many edges a path is scanned for crossings on the walking thread
+#[cfg(feature = "parallel")]
+const PARALLEL_EDGES: usize = 1 << 15;
+
+/// Whether `segment` crosses an edge of the polyline `path`, counting the tests in `tests`.
+///
+/// The edges are tested newest first by [`last_crossing`], each only if its extent overlaps
+/// that of `segment`. With the `parallel` feature, long paths are split among the
+/// `options.threads` threads, which stop once one of them finds a crossing; the answer is
+/// the same, but the counts include the tests of edges beyond the crossing.
+fn crosses_path(
+    segment: (&Point, &Point),
+    path: &[Point],
+    options: &Options,
+    tests: &mut EdgeTests,
+) -> bool {
+    let tolerance = &options.tolerance;
+    #[cfg(feature = "parallel")]
+    if path.len() >= PARALLEL_EDGES {
+        let threads = thread_count(options.threads);
+        if threads > 1 {
+            return crosses_path_parallel(segment, path, tolerance, tests, threads);
+        }
+    }
+
+    last_crossing(segment, path, tolerance, tests).is_some()
+}
+
+/// [`crosses_path`] on `threads` threads, each taking a contiguous run of edges.
+#[cfg(feature = "parallel")]
+fn crosses_path_parallel(
+    segment: (&Point, &Point),
+    path: &[Point],
+    tolerance: &Tolerance,
+    tests: &mut EdgeTests,
+    threads: usize,
+) -> bool {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let found = AtomicBool::new(false);
+    let edges = path.len() - 1;
+    let share = edges.div_ceil(threads);
+    let counts: Vec<EdgeTests> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..edges)
+            .step_by(share)
+            .map(|start| {
+                // the run shares its last point with the next, so no edge is left out
+                let run = &path[start..(start + share + 1).min(path.len())];
+                let found = &found;
+                scope.spawn(move || {
+                    let mut tests = EdgeTests::default();
+                    // in pieces of 256 edges, looking for crossings found by the others between
+                    for piece in (0..run.len() - 1).step_by(256) {
+                        if found.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        let piece = &run[piece..(piece + 257).min(run.len())];
+                        if last_crossing(segment, piece, tolerance, &mut tests).is_some() {
+                            found.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+                    tests
+                })
+            })
+            .collect();
+        workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+    for count in counts {
+        tests.performed += count.performed;
+        tests.pruned += count.pruned;
+    }
+    found.into_inner()
+}
+
+fn concave_hull_inner(
+    point_list: &mut Vec<Point>,
+    k: usize,
+    options: &Options,
+    hull: &mut Vec<Point>,
+) -> Result<(), Failure> {
+    let vertices = march(point_list, k, options, hull)?;
+    check_required(hull, options)?;
+
+    remove_vertices(point_list, &vertices);
+    check_contained(point_list.iter(), hull, options)
+}
+
+/// Checks that the required points are among the vertices of `hull`.
+fn check_required(hull: &[Point], options: &Options) -> Result<(), Failure> {
+    if options.required.is_empty() {
+        return Ok(());
+    }
+    let mut ids: Vec<u64> = hull.iter().map(|p| p.id).collect();
+    ids.sort_unstable();
+    if options
+        .required
+        .iter()
+        .all(|id| ids.binary_search(id).is_ok())
+    {
+        Ok(())
+    } else {
+        Err(Failure::MissingRequired)
+    }
+}
+
+/// Checks that `rest`, the points that are not hull vertices, lie inside `hull`, but for
+/// the `options.max_outside` points that may stay outside.
+fn check_contained<'a>(
+    rest: impl Iterator<Item = &'a Point>,
+    hull: &[Point],
+    options: &Options,
+) -> Result<(), Failure> {
+    let limit = options.max_outside + 1;
+    let outside = count_outside(
+        rest,
+        hull,
+        options.containment,
+        &options.tolerance,
+        options.threads,
+        limit,
+    );
+    let contained = outside <= options.max_outside;
+    if contained {
+        Ok(())
+    } else {
+        Err(Failure::PointsOutside)
+    }
+}
+
+/// Walks the k-nearest neighbour boundary of `point_list` into `hull`.
+///
+/// The walk identifies points by their position in `point_list`, so their ids are only
+/// labels carried into the hull and need not be unique. Candidates whose id is in the
+/// sorted `options.required` list are tried first.
+///
+/// Returns the sorted positions of the hull vertices in `point_list`. Fails if the walk
+/// runs into a self-intersection or `options.interrupt` fires.
+fn march(
+    point_list: &[Point],
+    k: usize,
+    options: &Options,
+    hull: &mut Vec<Point>,
+) -> Result<Vec<usize>, Failure> {
+    let mut workspace = Workspace::default();
+    march_in(point_list, k, options, hull, &mut workspace)?;
+    Ok(workspace.vertices)
+}
+
+/// Walks the boundary as [`march`] does, in the buffers of `workspace`, leaving the sorted
+/// vertex positions in `workspace.vertices`.
+fn march_in(
+    point_list: &[Point],
+    k: usize,
+    options: &Options,
+    hull: &mut Vec<Point>,
+    workspace: &mut Workspace,
+) -> Result<(), Failure> {
+    // the walk's copy of the points, with their positions as ids
+    let mut positions = std::mem::take(&mut workspace.positions);
+    positions.clear();
+    positions.extend(point_list.iter().enumerate().map(|(i, p)| Point {
+        x: p.x,
+        y: p.y,
+        id: i as u64,
+    }));
+
+    // build a kd tree so we can do the spatial queries
+    let mut tree = index::build_index(&positions, options);
+
+    let walked = walk(
+        point_list,
+        &positions,
+        &[],
+        tree.as_mut(),
+        k,
+        options,
+        hull,
+        workspace,
+    );
+    workspace.positions = positions;
+    walked
+}
+
+/// Walks the boundary of the points of `point_list` but those at the sorted `dropped`
+/// positions, as [`march_in`] does.
+///
+/// `tree` holds the points walked over, with their positions as ids as in `positions`.
+/// The walk takes its vertices out of the tree as it goes, and puts them back before it
+/// returns, so a tree can be reused by any number of walks, see [`index::PointIndex`].
+#[allow(clippy::too_many_arguments)]
+fn walk(
+    point_list: &[Point],
+    positions: &[Point],
+    dropped: &[usize],
+    tree: &mut dyn NeighborIndex,
+    k: usize,
+    options: &Options,
+    hull: &mut Vec<Point>,
+    workspace: &mut Workspace,
+) -> Result<(), Failure> {
+    let required = &options.required;
+    let metric = options.metric;
+    let Workspace {
+        nearest,
+        candidates: c_points,
+        vertices,
+        ..
+    } = workspace;
+    hull.clear();
+    vertices.clear();
+    let live = point_list.len() - dropped.len();
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("walk", k, points = live).entered();
+
+    if let 0..=3 = live {
+        hull.extend(without_vertices(point_list, dropped).cloned());
+        vertices.extend((0..point_list.len()).filter(|i| dropped.binary_search(i).is_err()));
+        return Ok(());
+    }
 
     // Initialize hull with the min-y point
-    let mut first_point = find_min_y_point(point_list);
-    hull.push(first_point.clone());
+    let first = find_min_y_point(without_vertices(positions, dropped)).id as usize;
+    hull.push(point_list[first].clone());
+    vertices.push(first);
 
     // Until the hull is of size > 3 we want to ignore the first point from nearest neighbour searches
-    let mut current_point = first_point.clone();
-    let cp = current_point.clone();
-    tree.remove(&[cp.x, cp.y], cp.id);
+    tree.remove(&positions[first]);
+
+    // the walk keeps to ids in the tree, positions but for the first point, which re-enters
+    // the tree under an unused position once it may close the hull; points are only cloned
+    // into the hull
+    let closing = u32::try_from(point_list.len()).expect("fewer than 2^32 points");
+    let closing_point = Point {
+        id: closing as u64,
+        ..positions[first].clone()
+    };
+    let position_of = |id: u32| if id == closing { first } else { id as usize };
+    let entry = |id: u32| {
+        if id == closing {
+            &closing_point
+        } else {
+            &positions[id as usize]
+        }
+    };
+    let mut current = first as u32;
 
     let mut prev_angle = 0.0f64;
     let mut step = 1usize;
+    let mut edge_tests = EdgeTests::default();
 
-    // Iterate until we reach the start, or until there's no points left to process
-    while (!(current_point == first_point) || step == 1) && hull.len() != point_list.len() {
-        if step == 4 {
-            first_point.id = point_list.len() as u64;
-            let p = first_point.clone();
-            tree.add(&[p.x, p.y], p.id);
-            point_map.insert(first_point.id, first_point.clone());
-        }
-
-        let cp = current_point.clone();
-        let knn = tree.nearest_n::<SquaredEuclidean>(&[cp.x, cp.y], k);
-        let mut nearest: Vec<PointValue> = knn
-            .iter()
-            .map(|p| {
-                let point = point_map.get(&p.item).unwrap();
-                PointValue {
-                    point: point.clone(),
-                    distance: p.distance,
-                    angle: current_point.angle(point),
+    let walked = 'walk: {
+        // Iterate until we reach the start, or until there's no points left to process
+        while current != closing && hull.len() != live {
+            if step.is_multiple_of(256) {
+                if options.interrupted() {
+                    break 'walk Err(Failure::Interrupted);
                 }
-            })
-            .collect();
-        let c_points = sort_by_angle(&mut nearest, &current_point, prev_angle);
+                options.report(Progress {
+                    step: step - 1,
+                    k,
+                    hull_size: hull.len(),
+                });
+            }
 
-        let mut its = true;
-        let mut i = 0usize;
+            if step == 4 {
+                tree.add(&closing_point);
+            }
 
-        while its && i < c_points.len() {
-            let mut last_point = 0;
-            if *c_points.get(i).unwrap() == first_point {
-                last_point = 1;
+            let current_point = entry(current);
+            let knn = tree.nearest(current_point, k);
+            nearest.clear();
+            nearest.extend(knn.iter().map(|&id| {
+                let point = entry(id as u32);
+                Neighbour {
+                    id: id as u32,
+                    distance: metric.distance(current_point, point),
+                    angle: normalise_angle(metric.angle(current_point, point) - prev_angle),
+                }
+            }));
+            sort_by_angle(nearest, c_points);
+            if !required.is_empty() {
+                // stable, so required candidates keep their angle order among themselves
+                c_points.sort_by_key(|&id| {
+                    required
+                        .binary_search(&point_list[position_of(id)].id)
+                        .is_err()
+                });
             }
 
-            let mut j = 2;
-            its = false;
+            let mut its = true;
+            let mut i = 0usize;
 
-            while !its && j < hull.len() - last_point {
-                let line1 = (hull.get(step - 1).unwrap(), c_points.get(i).unwrap());
-                let line2 = (hull.get(step - j - 1).unwrap(), hull.get(step - j).unwrap());
-                its = intersects(line1, line2);
-                j += 1;
+            while its && i < c_points.len() {
+                let mut last_point = 0;
+                if c_points[i] == closing {
+                    last_point = 1;
+                }
+
+                // every edge but the one ending at the last vertex, and the first edge if
+                // the candidate closes the hull onto it
+                let line1 = (hull.get(step - 1).unwrap(), entry(c_points[i]));
+                its = crosses_path(line1, &hull[last_point..step - 1], options, &mut edge_tests);
+
+                if its {
+                    i += 1;
+                }
             }
 
+            options.count_edge_tests(edge_tests);
+            edge_tests = EdgeTests::default();
             if its {
-                i += 1;
+                let candidates: Vec<Point> = c_points
+                    .iter()
+                    .map(|&id| point_list[position_of(id)].clone())
+                    .collect();
+                let closing = &point_list[first];
+                options.crossings.replace(crossings(
+                    hull,
+                    &candidates,
+                    closing,
+                    &options.tolerance,
+                ));
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    step,
+                    hull_size = hull.len(),
+                    "walk crossed its own boundary"
+                );
+                options.report(Progress {
+                    step: step - 1,
+                    k,
+                    hull_size: hull.len(),
+                });
+                break 'walk Err(Failure::SelfIntersection);
             }
-        }
 
-        if its {
-            return false;
-        }
+            current = c_points[i];
 
-        current_point = c_points[i].clone();
+            let vertex = position_of(current);
+            hull.push(point_list[vertex].clone());
+            vertices.push(vertex);
+            if options
+                .observe
+                .as_ref()
+                .is_some_and(|observe| !observe(hull))
+            {
+                break 'walk Err(Failure::Interrupted);
+            }
 
-        hull.push(current_point.clone());
+            prev_angle = metric.angle(&hull[step], &hull[step - 1]);
 
-        prev_angle = hull[step].angle(&hull[step - 1]);
+            tree.remove(entry(current));
 
-        let cp = current_point.clone();
-        tree.remove(&[cp.x, cp.y], cp.id);
+            step += 1;
+            #[cfg(feature = "tracing")]
+            if step.is_multiple_of(10_000) {
+                tracing::trace!(step, hull_size = hull.len(), "walking");
+            }
+        }
+        options.report(Progress {
+            step: step - 1,
+            k,
+            hull_size: hull.len(),
+        });
+
+        vertices.sort_unstable();
+        vertices.dedup();
+        Ok(())
+    };
 
-        step += 1;
+    // put back what the walk took out of the tree: the closing point comes out, unless
+    // the walk closed on it or never added it, and each vertex, listed once, goes back in
+    tree.remove(&closing_point);
+    for &vertex in vertices.iter() {
+        tree.add(&positions[vertex]);
     }
+    walked
+}
 
-    let new_end = remove_hull(point_list, hull);
+/// For each candidate of a step that crosses the hull, the first hull edge it crosses.
+fn crossings(
+    hull: &[Point],
+    candidates: &[Point],
+    first_point: &Point,
+    tolerance: &Tolerance,
+) -> Vec<Crossing> {
+    let last = &hull[hull.len() - 1];
+    candidates
+        .iter()
+        .filter_map(|candidate| {
+            let closing = usize::from(candidate == first_point);
+            (2..hull.len() - closing)
+                .map(|j| (&hull[hull.len() - j - 1], &hull[hull.len() - j]))
+                .find(|&edge| intersects_within((last, candidate), edge, tolerance))
+                .map(|(a, b)| Crossing {
+                    candidate: candidate.clone(),
+                    edge: (a.clone(), b.clone()),
+                })
+        })
+        .collect()
+}
 
-    multiple_point_in_polygon(&new_end, hull)
+/// Winding order of hull vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Orientation {
+    /// clockwise, the order the walk traces hulls in
+    #[default]
+    Clockwise,
+    /// counter-clockwise, as GeoJSON expects of outer rings
+    CounterClockwise,
 }
 
-fn find_min_y_point(points: &[Point]) -> Point {
-    assert!(!points.is_empty());
+/// The hull with its vertices in the given winding order, reversed if need be.
+///
+/// # Arguments
+///
+/// * `hull` - ordered hull vertices, open or closed.
+/// * `orientation` - the winding order to return.
+///
+/// # Returns
+///
+/// Returns the hull running in `orientation`; a hull without area is returned unchanged.
+pub fn orient(mut hull: Vec<Point>, orientation: Orientation) -> Vec<Point> {
+    let area = buffer::signed_area(&hull);
+    if (orientation == Orientation::CounterClockwise && area < 0.0)
+        || (orientation == Orientation::Clockwise && area > 0.0)
+    {
+        hull.reverse();
+    }
+    hull
+}
 
+/// The hull as a closed ring, repeating its first vertex at the end unless it already does.
+///
+/// Hulls are returned as the walk traced them, which is closed unless it used up every
+/// point; GIS formats such as GeoJSON and WKT expect the closing vertex.
+///
+/// # Arguments
+///
+/// * `hull` - ordered hull vertices, open or closed.
+///
+/// # Returns
+///
+/// Returns the closed ring, or `hull` unchanged if it has fewer than two vertices.
+pub fn close_ring(mut hull: Vec<Point>) -> Vec<Point> {
+    if hull.len() > 1 && open_ring(&hull).len() == hull.len() {
+        hull.push(hull[0].clone());
+    }
+    hull
+}
+
+/// The hull vertices without the closing vertex, if the ring repeats its first vertex.
+fn open_ring(hull: &[Point]) -> &[Point] {
+    match (hull.first(), hull.last()) {
+        (Some(first), Some(last)) if hull.len() > 1 && first.x == last.x && first.y == last.y => {
+            &hull[..hull.len() - 1]
+        }
+        _ => hull,
+    }
+}
+
+fn find_min_y_point<'a>(points: impl IntoIterator<Item = &'a Point>) -> &'a Point {
     points
-        .iter()
+        .into_iter()
         .min_by(|a, b| {
             if a.y == b.y {
                 greater_than(a.x, b.x)
@@ -162,7 +1402,6 @@ fn find_min_y_point(points: &[Point]) -> Point {
             }
         })
         .expect("No minimum element found")
-        .clone()
 }
 
 fn greater_than(a: f64, b: f64) -> std::cmp::Ordering {
@@ -181,63 +1420,631 @@ fn less_than(a: f64, b: f64) -> std::cmp::Ordering {
     }
 }
 
-fn sort_by_angle(values: &mut [PointValue], from: &Point, prev_angle: f64) -> Vec<Point> {
-    // Calculate angles
-    for to in values.iter_mut() {
-        to.angle = normalise_angle(from.angle(&to.point) - prev_angle);
-    }
+/// Orders the ids of the neighbours `values` by their turn into `sorted`, taking equal
+/// turns nearest first and equal distances by id.
+fn sort_by_angle(values: &mut [Neighbour], sorted: &mut Vec<u32>) {
+    // Sort by angle; among collinear candidates the nearest comes first, so that none of
+    // them is skipped and left on an edge, and the id settles what is left
+    values.sort_by(|a, b| {
+        a.angle
+            .total_cmp(&b.angle)
+            .then(a.distance.total_cmp(&b.distance))
+            .then(a.id.cmp(&b.id))
+    });
 
-    // Sort in descending order of angle
-    values.sort_by(|a, b| greater_than(a.angle, b.angle));
+    sorted.clear();
+    sorted.extend(values.iter().map(|n| n.id));
+}
 
-    // Extract points from PointValue and collect into a vector
-    values.iter().map(|pv| pv.point.clone()).collect()
+/// Removes the points at the sorted `vertices` positions.
+fn remove_vertices(points: &mut Vec<Point>, vertices: &[usize]) {
+    let mut position = 0;
+    points.retain(|_| {
+        position += 1;
+        vertices.binary_search(&(position - 1)).is_err()
+    });
 }
 
-fn remove_hull(points: &mut Vec<Point>, hull: &[Point]) -> Vec<Point> {
-    let ids: Vec<u64> = hull.iter().map(|p| p.id).collect();
+/// The points other than those at the sorted `vertices` positions.
+fn without_vertices<'a>(
+    points: &'a [Point],
+    vertices: &'a [usize],
+) -> impl Iterator<Item = &'a Point> {
+    points
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| vertices.binary_search(i).is_err())
+        .map(|(_, p)| p)
+}
 
-    points.retain(|p| ids.binary_search(&p.id).is_err());
+/// The weight of the points of `coverage` that are vertices of `hull`, by the sorted walked
+/// `vertices`, or lie inside it.
+fn covered_weight(coverage: &Coverage, hull: &[Point], vertices: &[usize]) -> f64 {
+    // sorted, as the candidates increase
+    let on_hull: Vec<usize> = vertices.iter().map(|&v| coverage.candidates[v]).collect();
 
-    points.to_vec()
+    coverage
+        .points
+        .iter()
+        .zip(coverage.weights)
+        .enumerate()
+        .filter(|(i, (p, _))| on_hull.binary_search(i).is_ok() || point_in_polygon(p, hull))
+        .map(|(_, (_, w))| w)
+        .sum()
 }
 
-fn multiple_point_in_polygon(points: &[Point], hull: &[Point]) -> bool {
-    points.iter().all(|p| point_in_polygon(p, hull))
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// uniformly scattered points in the unit square (deterministic LCG)
+    fn random_points(n: usize, mut seed: u64) -> Vec<Point> {
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        (0..n)
+            .map(|i| Point {
+                x: next(),
+                y: next(),
+                id: i as u64,
+            })
+            .collect()
+    }
 
-fn point_in_polygon(point: &Point, polygon: &[Point]) -> bool {
-    if polygon.len() <= 2 {
-        return false;
+    #[test]
+    fn test_concave_hull_random() {
+        let mut point_list = random_points(500, 7);
+        let hull = concave_hull(&mut point_list, 3, true).unwrap();
+        assert!(hull.len() > 3);
     }
 
-    let x = point.x;
-    let y = point.y;
+    #[test]
+    fn test_concave_hull_with_stats() {
+        let point_list = random_points(500, 7);
+        let expected = concave_hull(&mut point_list.clone(), 3, true).unwrap();
+
+        let (hull, stats) =
+            concave_hull_with_stats(&mut point_list.clone(), 3, true, true).unwrap();
+        assert_eq!(hull, expected);
+        assert_eq!(stats.attempts, stats.history.len());
+        let last = stats.history.last().unwrap();
+        assert_eq!(Some(last.k), stats.k);
+        assert_eq!((last.failure, last.hull_size), (None, hull.len()));
+        assert!(stats.history[..stats.attempts - 1]
+            .iter()
+            .all(|a| a.failure.is_some()));
+        assert!(stats.edge_tests.performed > 0 && stats.edge_tests.pruned > 0);
 
-    let mut inout = 0;
-    let mut v0 = &polygon[0];
+        let (_, stats) = concave_hull_with_stats(&mut point_list.clone(), 3, true, false).unwrap();
+        assert!(stats.history.is_empty() && stats.attempts > 0);
+        assert_eq!(
+            concave_hull_with_stats(&mut point_list.clone(), 3, false, true).map(|(h, _)| h),
+            concave_hull(&mut point_list.clone(), 3, false)
+        );
 
-    for v1 in polygon.iter() {
-        if (((v0.y <= y) && (y < v1.y)) || ((v1.y <= y) && (y < v0.y)))
-            && ((v1.y - v0.y).abs() >= 1E-10)
-        {
-            let tdbl1 = (y - v0.y) / (v1.y - v0.y);
-            let tdbl2 = v1.x - v0.x;
+        // the search of the builder, with the hulls and attempts of the free functions
+        let (_, built) = builder::ConcaveHull::builder()
+            .k(3)
+            .build()
+            .compute_with_stats(&point_list)
+            .unwrap();
+        assert_eq!((built.k, built.attempts), (stats.k, stats.attempts));
+        let mut doubled = Options {
+            escalation: Escalation::Double,
+            ..Default::default()
+        };
+        let (_, stats) =
+            search(&mut point_list.clone(), 3, true, usize::MAX, &doubled, true).unwrap();
+        assert!(stats.history.windows(2).all(|a| a[1].k == 2 * a[0].k));
+        doubled.escalation = Escalation::Schedule(Vec::new());
+        assert_eq!(
+            search(
+                &mut point_list.clone(),
+                3,
+                true,
+                usize::MAX,
+                &doubled,
+                false
+            )
+            .map(|(h, _)| h),
+            concave_hull(&mut point_list.clone(), 3, false)
+        );
+    }
+
+    #[test]
+    fn test_concave_hull_interruptible() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
 
-            if x < v0.x + (tdbl2 * tdbl1) {
-                inout += 1;
+        let point_list = random_points(2000, 3);
+        let expected = concave_hull(&mut point_list.clone(), 3, true).unwrap();
+        let never = Box::new(|| false);
+        let hull = concave_hull_interruptible(
+            &mut point_list.clone(),
+            3,
+            true,
+            Metric::Euclidean,
+            0,
+            never,
+        );
+        assert_eq!(hull, Ok(expected));
+
+        let polls = Arc::new(AtomicUsize::new(0));
+        let counter = polls.clone();
+        let soon = Box::new(move || counter.fetch_add(1, Ordering::Relaxed) >= 1);
+        let hull = concave_hull_interruptible(
+            &mut point_list.clone(),
+            3,
+            true,
+            Metric::Euclidean,
+            0,
+            soon,
+        );
+        assert_eq!(hull, Err(ConcaveHullError::Cancelled));
+        assert_eq!(polls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_concave_hull_observed() {
+        use std::sync::{Arc, Mutex};
+
+        let point_list = random_points(300, 3);
+        let expected = concave_hull(&mut point_list.clone(), 3, true).unwrap();
+        let sizes = Arc::new(Mutex::new(Vec::new()));
+        let seen = sizes.clone();
+        let observe = Box::new(move |hull: &[Point]| {
+            seen.lock().unwrap().push(hull.len());
+            true
+        });
+        let hull = concave_hull_observed(&mut point_list.clone(), 3, true, observe);
+        assert_eq!(hull.as_ref(), Ok(&expected));
+        // the last walk grew by one vertex per step up to the finished hull
+        let sizes = sizes.lock().unwrap();
+        assert_eq!(sizes.last(), Some(&expected.len()));
+        assert!(sizes.ends_with(&(2..=expected.len()).collect::<Vec<_>>()));
+
+        let observe = Box::new(|hull: &[Point]| hull.len() < 10);
+        assert_eq!(
+            concave_hull_observed(&mut point_list.clone(), 3, true, observe),
+            Err(ConcaveHullError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_concave_hull_core() {
+        // a dense unit square in a sparse 10 x 10 halo
+        let mut point_list = random_points(400, 3);
+        for mut p in random_points(100, 4) {
+            p.x = p.x * 10.0 - 4.5;
+            p.y = p.y * 10.0 - 4.5;
+            p.id += 400;
+            point_list.push(p);
+        }
+
+        let hull = concave_hull_core(&point_list, 0.75, 8, 3, true).unwrap();
+        assert!(!hull.is_empty());
+        assert!(hull
+            .iter()
+            .all(|p| (-0.5..1.5).contains(&p.x) && (-0.5..1.5).contains(&p.y)));
+        let all = concave_hull_core(&point_list, 1.0, 8, 3, true).unwrap();
+        assert!(all.iter().any(|p| p.id >= 400));
+    }
+
+    #[test]
+    fn test_hulls() {
+        let point_list = random_points(300, 6);
+        let (concave, convex) = hulls(&point_list, 3, true).unwrap();
+        assert_eq!(
+            concave,
+            concave_hull(&mut point_list.clone(), 3, true).unwrap()
+        );
+        assert_eq!(convex, convex::convex_hull(&point_list));
+        assert!(concave.len() > convex.len());
+
+        assert_eq!(
+            hulls(&point_list[..3], 3, true),
+            Err(ConcaveHullError::InsufficientPoints {
+                required: 4,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_concave_hull_with_required() {
+        let point_list = random_points(300, 5);
+        let hull = concave_hull(&mut point_list.clone(), 3, true).unwrap();
+
+        // the interior point that sits closest to the unconstrained boundary
+        let prepared = prepared::PreparedHull::new(&hull);
+        let target = point_list
+            .iter()
+            .filter(|p| hull.iter().all(|h| h.id != p.id))
+            .min_by(|a, b| prepared.distance(a).total_cmp(&prepared.distance(b)))
+            .unwrap()
+            .id;
+
+        let hull = concave_hull_with_required(&mut point_list.clone(), &[target], 3, true).unwrap();
+        assert!(hull.iter().any(|p| p.id == target));
+        assert_eq!(
+            concave_hull_with_required(&mut point_list[..3].to_vec(), &[target], 3, true),
+            Err(ConcaveHullError::InsufficientPoints {
+                required: 4,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_concave_hull_robust() {
+        let mut point_list = random_points(300, 3);
+        for (i, (x, y)) in [(5.0, 0.5), (-4.0, 0.5)].iter().enumerate() {
+            point_list.push(Point {
+                x: *x,
+                y: *y,
+                id: 300 + i as u64,
+            });
+        }
+
+        let hull =
+            concave_hull_robust(&mut point_list.clone(), 3, true, Outliers::Count(2)).unwrap();
+        assert!(hull.len() > 3);
+        assert!(hull.iter().all(|p| p.id < 300));
+        point_list[7].y = f64::NAN;
+        assert_eq!(
+            concave_hull_robust(&mut point_list, 3, true, Outliers::Count(2)),
+            Err(ConcaveHullError::InvalidCoordinate { id: 7 })
+        );
+
+        assert_eq!(Outliers::Fraction(0.01).allowance(302), 3);
+        assert_eq!(Outliers::Count(5).allowance(2), 2);
+    }
+
+    #[test]
+    fn test_concave_hull_geographic() {
+        // a patch of GPS fixes near 70°N, twice as wide in degrees as it is tall
+        let mut point_list = random_points(300, 13);
+        for p in point_list.iter_mut() {
+            p.x = 25.0 + 2.0 * p.x;
+            p.y += 70.0;
+        }
+
+        let hull = concave_hull_geographic(&mut point_list.clone(), 3, true).unwrap();
+        assert!(hull.len() > 3);
+        let prepared = prepared::PreparedHull::new(&hull);
+        assert!(point_list
+            .iter()
+            .all(|p| hull.iter().any(|h| h.id == p.id) || prepared.contains(p)));
+        point_list[0].x = f64::INFINITY;
+        assert_eq!(
+            concave_hull_geographic(&mut point_list, 3, true),
+            Err(ConcaveHullError::InvalidCoordinate { id: 0 })
+        );
+    }
+
+    #[cfg(feature = "experimental-f16")]
+    #[test]
+    fn test_concave_hull_half_precision() {
+        let point_list = random_points(500, 7);
+        let hull =
+            concave_hull_with_precision(&mut point_list.clone(), 3, true, IndexPrecision::Half)
+                .unwrap();
+        assert!(hull.len() > 3);
+        // vertices keep their full precision coordinates
+        assert!(hull.iter().all(|h| point_list.contains(h)));
+        assert_eq!(
+            concave_hull_with_precision(&mut point_list.clone(), 500, true, IndexPrecision::Half),
+            Err(ConcaveHullError::InsufficientPoints {
+                required: 501,
+                found: 500
+            })
+        );
+    }
+
+    #[test]
+    fn test_concave_hull_weighted() {
+        let mut point_list = random_points(300, 11);
+        point_list.push(Point {
+            x: 3.0,
+            y: 3.0,
+            id: 300,
+        });
+        let mut weights = vec![1.0; 300];
+        weights.push(0.01);
+
+        let weighting = Weighting {
+            min_vertex_weight: 0.5,
+            min_coverage: 0.99,
+        };
+        let hull = concave_hull_weighted(&point_list, &weights, 3, true, &weighting).unwrap();
+        assert!(hull.len() > 3);
+        assert!(hull.iter().all(|p| p.id != 300));
+
+        // the straggler cannot be left out if every bit of weight must be covered
+        let weighting = Weighting {
+            min_vertex_weight: 0.5,
+            min_coverage: 1.0,
+        };
+        assert!(matches!(
+            concave_hull_weighted(&point_list, &weights, 3, true, &weighting),
+            Err(ConcaveHullError::NoValidHullFound { .. })
+        ));
+
+        // nor by sharing its id with the vertices
+        let same_ids: Vec<Point> = point_list
+            .iter()
+            .map(|p| Point { id: 0, ..p.clone() })
+            .collect();
+        assert!(matches!(
+            concave_hull_weighted(&same_ids, &weights, 3, true, &weighting),
+            Err(ConcaveHullError::NoValidHullFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_concave_hull_borrowed() {
+        // both have attempts that leave points outside and remove the hull vertices
+        for (n, seed) in [(300, 0), (50, 6)] {
+            let point_list = random_points(n, seed);
+            let copy = point_list.clone();
+            assert_eq!(
+                concave_hull_borrowed(&point_list, 3, true),
+                concave_hull(&mut point_list.clone(), 3, true)
+            );
+            assert_eq!(point_list, copy);
+        }
+        assert_eq!(
+            concave_hull_borrowed(&random_points(500, 7), 3, false),
+            Err(ConcaveHullError::NoValidHullFound { max_k_tried: 3 })
+        );
+    }
+
+    #[test]
+    fn test_concave_hull_batch() {
+        let datasets: Vec<Vec<Point>> = (0..20)
+            .map(|seed| random_points(20 + 7 * seed as usize, seed))
+            .chain([random_points(3, 0)])
+            .collect();
+        let hulls = concave_hull_batch(&datasets, 3, true);
+        assert_eq!(hulls.len(), datasets.len());
+        for (hull, dataset) in hulls.iter().zip(datasets.iter()) {
+            assert_eq!(*hull, concave_hull_borrowed(dataset, 3, true));
+        }
+        assert!(hulls[..20].iter().all(Result::is_ok));
+        assert!(hulls[20].is_err());
+        assert!(concave_hull_batch::<Vec<Point>>(&[], 3, true).is_empty());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_concave_hull_batch_with_threads() {
+        let datasets: Vec<Vec<Point>> = (0..40)
+            .map(|seed| random_points(10 + 13 * (seed as usize % 9), seed))
+            .chain([random_points(2, 0)])
+            .collect();
+        let sequential = concave_hull_batch(&datasets, 3, true);
+        for threads in [0, 1, 3, 100] {
+            assert_eq!(
+                concave_hull_batch_with_threads(&datasets, 3, true, threads),
+                sequential
+            );
+        }
+        assert!(concave_hull_batch_with_threads::<Vec<Point>>(&[], 3, true, 4).is_empty());
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_scans() {
+        // a zigzag long enough to be split among threads
+        let path: Vec<Point> = (0..PARALLEL_EDGES as u64 + 100)
+            .map(|id| Point {
+                x: id as f64,
+                y: (id % 2) as f64,
+                id,
+            })
+            .collect();
+        let tolerance = Tolerance::default();
+        let point = |x: f64, y: f64| Point { x, y, id: 0 };
+        let options = |threads| Options {
+            threads,
+            ..Default::default()
+        };
+        for (a, b) in [
+            (point(10.5, -1.0), point(10.5, 2.0)),
+            (point(30000.5, 0.5), point(30000.5, 3.0)),
+            (point(-5.0, 3.0), point(40000.0, 3.0)),
+            (point(-5.0, 0.5), point(-1.0, 0.5)),
+        ] {
+            let expected = path
+                .windows(2)
+                .any(|edge| intersects_within((&a, &b), (&edge[0], &edge[1]), &tolerance));
+            for threads in [0, 1, 3] {
+                let mut tests = EdgeTests::default();
+                assert_eq!(
+                    crosses_path((&a, &b), &path, &options(threads), &mut tests),
+                    expected
+                );
+                assert!(tests.performed + tests.pruned > 0);
             }
         }
+    }
 
-        v0 = v1;
+    #[test]
+    fn test_concave_hull_duplicate_ids() {
+        let point_list = random_points(500, 7);
+        let expected = concave_hull(&mut point_list.clone(), 3, true).unwrap();
+
+        // every id is shared by ten points
+        let labelled: Vec<Point> = point_list
+            .iter()
+            .map(|p| Point {
+                id: p.id / 10,
+                ..p.clone()
+            })
+            .collect();
+        let hull = concave_hull(&mut labelled.clone(), 3, true).unwrap();
+        assert_eq!(hull.len(), expected.len());
+        assert!(hull
+            .iter()
+            .zip(expected.iter())
+            .all(|(h, e)| (h.x, h.y, h.id) == (e.x, e.y, e.id / 10)));
+        assert_eq!(concave_hull_borrowed(&labelled, 3, true), Ok(hull));
     }
 
-    inout % 2 != 0
-}
+    #[test]
+    fn test_tie_breaking() {
+        // two points at the same place and one behind them, in either order
+        let candidates: [(f64, f64, u32); 4] =
+            [(1.0, 0.0, 5), (2.0, 0.0, 1), (1.0, 0.0, 2), (0.0, -1.0, 0)];
+        let origin = Point {
+            x: 0.0,
+            y: 0.0,
+            id: 9,
+        };
+        let order = |reversed: bool| {
+            let mut values: Vec<Neighbour> = candidates
+                .iter()
+                .map(|&(x, y, id)| Neighbour {
+                    id,
+                    distance: x + y.abs(),
+                    angle: normalise_angle(
+                        Metric::Euclidean.angle(&origin, &Point { x, y, id: 0 }),
+                    ),
+                })
+                .collect();
+            if reversed {
+                values.reverse();
+            }
+            let mut sorted = Vec::new();
+            sort_by_angle(&mut values, &mut sorted);
+            sorted
+        };
+        assert_eq!(order(false), [2, 5, 1, 0]);
+        assert_eq!(order(true), order(false));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // a lattice is all ties, and hulls the same with every neighbour index
+        let lattice: Vec<Point> = (0..144)
+            .map(|i| Point {
+                x: (i % 12) as f64,
+                y: (i / 12) as f64,
+                id: i,
+            })
+            .collect();
+        let hull = concave_hull(&mut lattice.clone(), 3, true).unwrap();
+        let brute = builder::ConcaveHull::builder()
+            .index(IndexBackend::BruteForce)
+            .build();
+        assert_eq!(brute.compute(&lattice).unwrap(), hull);
+    }
+
+    #[test]
+    fn test_orient() {
+        let point_list = random_points(300, 0);
+        let hull = concave_hull(&mut point_list.clone(), 3, true).unwrap();
+        assert!(buffer::signed_area(&hull) < 0.0);
+        assert_eq!(orient(hull.clone(), Orientation::Clockwise), hull);
+
+        let ccw = orient(hull.clone(), Orientation::CounterClockwise);
+        assert!(buffer::signed_area(&ccw) > 0.0);
+        assert_eq!(ccw.first(), hull.last());
+    }
+
+    #[test]
+    fn test_close_ring() {
+        let point = |x: f64, y: f64, id: u64| Point { x, y, id };
+        let open = vec![point(0.0, 0.0, 0), point(1.0, 0.0, 1), point(0.0, 1.0, 2)];
+        let closed = close_ring(open.clone());
+        assert_eq!(closed.len(), 4);
+        assert_eq!(closed.first(), closed.last());
+        assert_eq!(close_ring(closed.clone()), closed);
+        assert_eq!(close_ring(open[..1].to_vec()), open[..1]);
+    }
+
+    #[test]
+    fn test_concave_hull_indices() {
+        let point_list = random_points(300, 0);
+        let expected = concave_hull_borrowed(&point_list, 3, true).unwrap();
+
+        let tuples: Vec<(f64, f64)> = point_list.iter().map(|p| (p.x, p.y)).collect();
+        let indices = concave_hull_indices(&tuples, 3, true).unwrap();
+        let ids: Vec<usize> = expected.iter().map(|p| p.id as usize).collect();
+        assert_eq!(indices, ids);
+        assert_eq!(indices.first(), indices.last());
+    }
+
+    #[test]
+    fn test_concave_hull_of() {
+        let point_list = random_points(300, 0);
+        let expected = concave_hull_borrowed(&point_list, 3, true).unwrap();
+
+        let tuples: Vec<(f64, f64)> = point_list.iter().map(|p| (p.x, p.y)).collect();
+        let hull = concave_hull_of(&tuples, 3, true).unwrap();
+        assert!(hull
+            .iter()
+            .zip(expected.iter())
+            .all(|(t, p)| *t == (p.x, p.y)));
+        assert_eq!(hull.len(), expected.len());
+
+        // an application type, hulled without ids
+        #[derive(Clone, Debug, PartialEq)]
+        struct Site {
+            name: String,
+            position: [f64; 2],
+        }
+        impl HullPoint for Site {
+            fn x(&self) -> f64 {
+                self.position[0]
+            }
+            fn y(&self) -> f64 {
+                self.position[1]
+            }
+        }
+        let sites: Vec<Site> = tuples
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| Site {
+                name: format!("site-{}", i),
+                position: [x, y],
+            })
+            .collect();
+        let hull = concave_hull_of(&sites, 3, true).unwrap();
+        assert_eq!(hull[0].name, format!("site-{}", expected[0].id));
+        assert_eq!(hull.len(), expected.len());
+    }
+
+    #[test]
+    fn test_concave_hull_errors() {
+        let mut point_list = random_points(100, 5);
+        assert_eq!(
+            concave_hull(&mut point_list[..3].to_vec(), 3, true),
+            Err(ConcaveHullError::InsufficientPoints {
+                required: 4,
+                found: 3,
+            })
+        );
+        assert_eq!(
+            concave_hull(&mut Vec::new(), 3, true),
+            Err(ConcaveHullError::InsufficientPoints {
+                required: 4,
+                found: 0,
+            })
+        );
+
+        // the first attempt of the random test fails
+        assert_eq!(
+            concave_hull(&mut random_points(500, 7), 3, false),
+            Err(ConcaveHullError::NoValidHullFound { max_k_tried: 3 })
+        );
+
+        point_list[42].y = f64::NAN;
+        assert_eq!(
+            concave_hull(&mut point_list, 3, true),
+            Err(ConcaveHullError::InvalidCoordinate { id: 42 })
+        );
+    }
 
     #[test]
     fn test_concave_hull() {
@@ -258,7 +2065,7 @@ mod tests {
                 id: 2,
             },
         ];
-        let hull = concave_hull(&mut point_list, 1, true);
+        let hull = concave_hull(&mut point_list, 1, true).unwrap();
         assert!(hull.len() == 3);
 
         let mut point_list = vec![
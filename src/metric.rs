@@ -0,0 +1,148 @@
+/// Distance Metrics
+use crate::point::{normalise_angle, Point};
+
+use std::f64::consts::FRAC_PI_2;
+
+/// Mean Earth radius in metres
+pub const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// How distances and directions between points are measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Metric {
+    /// planar coordinates
+    #[default]
+    Euclidean,
+    /// longitude (x) and latitude (y) in degrees on a spherical Earth
+    Haversine,
+}
+
+impl Metric {
+    /// distance from `a` to `b` (in metres for `Haversine`)
+    pub fn distance(&self, a: &Point, b: &Point) -> f64 {
+        match self {
+            Metric::Euclidean => (b.x - a.x).hypot(b.y - a.y),
+            Metric::Haversine => haversine_distance(a, b),
+        }
+    }
+
+    /// direction from `a` to `b`, in the clockwise-from-x convention of [`Point::angle`]
+    ///
+    /// For `Haversine` this is the initial great-circle bearing, rotated so that east is 0.
+    pub fn angle(&self, a: &Point, b: &Point) -> f64 {
+        match self {
+            Metric::Euclidean => a.angle(b),
+            Metric::Haversine => normalise_angle(bearing(a, b) - FRAC_PI_2),
+        }
+    }
+}
+
+/// Tolerances of the geometric predicates.
+///
+/// Below the threshold, two segments count as parallel, so never as crossing, and a polygon
+/// edge counts as horizontal, so never as crossed by the ray of a point-in-polygon test.
+/// The threshold is the larger of `absolute` and `relative` times the size of what is
+/// compared: the product of the two segment lengths, or the edge length.
+///
+/// The default, an absolute `1E-10`, suits coordinates of about unit size; data in metres
+/// at UTM scale or in micro-degrees need a relative tolerance instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tolerance {
+    /// threshold of the cross product of the segment directions, in squared coordinate
+    /// units, and of the rise of an edge, in coordinate units
+    pub absolute: f64,
+    /// threshold relative to the segment lengths: the sine of the angle between segments,
+    /// and the slope of an edge
+    pub relative: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance::absolute(1E-10)
+    }
+}
+
+impl Tolerance {
+    /// A tolerance of `absolute` regardless of size.
+    pub fn absolute(absolute: f64) -> Self {
+        Tolerance {
+            absolute,
+            relative: 0.0,
+        }
+    }
+
+    /// A tolerance of `relative` times the size of what is compared.
+    pub fn relative(relative: f64) -> Self {
+        Tolerance {
+            absolute: 0.0,
+            relative,
+        }
+    }
+
+    /// whether `value` is indistinguishable from zero for geometry of size `scale`
+    pub fn is_zero(&self, value: f64, scale: f64) -> bool {
+        value.abs() < self.absolute.max(self.relative * scale)
+    }
+}
+
+/// Great-circle distance in metres between two (longitude, latitude) points in degrees.
+pub fn haversine_distance(a: &Point, b: &Point) -> f64 {
+    let (lat1, lat2) = (a.y.to_radians(), b.y.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.x - a.x).to_radians();
+
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+}
+
+/// Initial bearing in radians, clockwise from north in [0, 2π), from `a` towards `b`.
+pub fn bearing(a: &Point, b: &Point) -> f64 {
+    let (lat1, lat2) = (a.y.to_radians(), b.y.to_radians());
+    let dlon = (b.x - a.x).to_radians();
+
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    normalise_angle(y.atan2(x))
+}
+
+/// Position of a (longitude, latitude) point on the unit sphere.
+///
+/// Chord lengths between these vectors grow monotonically with great-circle distance, so
+/// a Euclidean kd-tree over them answers great-circle nearest neighbour queries exactly.
+pub(crate) fn unit_vector(p: &Point) -> [f64; 3] {
+    let (lon, lat) = (p.x.to_radians(), p.y.to_radians());
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f64, y: f64) -> Point {
+        Point { x, y, id: 0 }
+    }
+
+    #[test]
+    fn test_haversine() {
+        // a degree of latitude is the same everywhere, a degree of longitude shrinks
+        let d = haversine_distance(&point(0.0, 0.0), &point(0.0, 1.0));
+        assert!((d - EARTH_RADIUS * 1f64.to_radians()).abs() < 1E-6);
+        let d = haversine_distance(&point(0.0, 60.0), &point(1.0, 60.0));
+        assert!((d - 55_597.0).abs() < 10.0);
+
+        let origin = point(10.0, 45.0);
+        let north = Metric::Haversine.angle(&origin, &point(10.0, 46.0));
+        assert!((north - 3.0 * FRAC_PI_2).abs() < 1E-12);
+        let east = bearing(&point(0.0, 0.0), &point(1.0, 0.0));
+        assert!((east - FRAC_PI_2).abs() < 1E-12);
+        assert!(
+            Metric::Haversine
+                .angle(&point(0.0, 0.0), &point(1.0, 0.0))
+                .abs()
+                < 1E-12
+        );
+    }
+}
@@ -0,0 +1,155 @@
+/// Area Moments of Hull Polygons
+use crate::enclosing::Ellipse;
+use crate::point::Point;
+
+/// The area, centroid and second moments of the region a ring encloses.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct AreaMoments {
+    /// enclosed area
+    pub area: f64,
+    /// x coordinate of the area centroid
+    pub cx: f64,
+    /// y coordinate of the area centroid
+    pub cy: f64,
+    /// mean squared x offset from the centroid over the area
+    pub sxx: f64,
+    /// mean product of the x and y offsets from the centroid
+    pub sxy: f64,
+    /// mean squared y offset from the centroid over the area
+    pub syy: f64,
+}
+
+impl AreaMoments {
+    /// The covariance matrix `[[sxx, sxy], [sxy, syy]]` of a point uniform over the area.
+    pub fn covariance(&self) -> [[f64; 2]; 2] {
+        [[self.sxx, self.sxy], [self.sxy, self.syy]]
+    }
+
+    /// The inertia tensor of the area about its centroid, taking the area as unit density.
+    pub fn inertia(&self) -> [[f64; 2]; 2] {
+        [
+            [self.area * self.syy, -self.area * self.sxy],
+            [-self.area * self.sxy, self.area * self.sxx],
+        ]
+    }
+
+    /// The principal variances, larger first.
+    pub fn principal(&self) -> (f64, f64) {
+        let mean = (self.sxx + self.syy) / 2.0;
+        let spread = ((self.sxx - self.syy) / 2.0).hypot(self.sxy);
+        (mean + spread, (mean - spread).max(0.0))
+    }
+
+    /// Direction of the axis of largest variance, radians from the x axis in [0, PI).
+    pub fn orientation(&self) -> f64 {
+        (0.5 * (2.0 * self.sxy).atan2(self.sxx - self.syy)).rem_euclid(std::f64::consts::PI)
+    }
+
+    /// The ellipse with the same centroid and second moments.
+    ///
+    /// A uniform ellipse has a variance of a quarter of its squared semi-axis along each
+    /// axis, so the semi-axes are twice the principal standard deviations. For an
+    /// elliptical region this is the region itself.
+    pub fn equivalent_ellipse(&self) -> Ellipse {
+        let (major, minor) = self.principal();
+        Ellipse {
+            cx: self.cx,
+            cy: self.cy,
+            semi_major: 2.0 * major.sqrt(),
+            semi_minor: 2.0 * minor.sqrt(),
+            angle: self.orientation(),
+        }
+    }
+}
+
+/// Computes the area moments of the polygon a hull encloses.
+///
+/// The moments are sums over the edges (Green's theorem), taken relative to the first
+/// vertex so that coordinates far from the origin lose no precision.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed, in either orientation.
+///
+/// # Returns
+///
+/// Returns the moments, or `None` if the ring encloses no area.
+pub fn area_moments(hull: &[Point]) -> Option<AreaMoments> {
+    let ring = crate::open_ring(hull);
+    let origin = ring.first()?;
+    let n = ring.len();
+
+    let (mut area, mut mx, mut my) = (0.0, 0.0, 0.0);
+    let (mut mxx, mut mxy, mut myy) = (0.0, 0.0, 0.0);
+    for i in 0..n {
+        let (a, b) = (&ring[i], &ring[(i + 1) % n]);
+        let (x0, y0) = (a.x - origin.x, a.y - origin.y);
+        let (x1, y1) = (b.x - origin.x, b.y - origin.y);
+        let cross = x0 * y1 - x1 * y0;
+        area += cross;
+        mx += cross * (x0 + x1);
+        my += cross * (y0 + y1);
+        mxx += cross * (x0 * x0 + x0 * x1 + x1 * x1);
+        myy += cross * (y0 * y0 + y0 * y1 + y1 * y1);
+        mxy += cross * (x0 * y1 + 2.0 * x0 * y0 + 2.0 * x1 * y1 + x1 * y0);
+    }
+    area /= 2.0;
+    if area == 0.0 || !area.is_finite() {
+        return None;
+    }
+
+    // the signed area cancels the orientation out of every ratio
+    let (cx, cy) = (mx / (6.0 * area), my / (6.0 * area));
+    Some(AreaMoments {
+        area: area.abs(),
+        cx: origin.x + cx,
+        cy: origin.y + cy,
+        sxx: mxx / (12.0 * area) - cx * cx,
+        sxy: mxy / (24.0 * area) - cx * cy,
+        syy: myy / (12.0 * area) - cy * cy,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_area_moments() {
+        let point = |x: f64, y: f64| Point { x, y, id: 0 };
+        // a 4 x 2 rectangle centred on (1000, 500), rotated by 30 degrees, clockwise
+        let (s, c) = 30f64.to_radians().sin_cos();
+        let mut rectangle: Vec<Point> = [(-2.0, -1.0), (2.0, -1.0), (2.0, 1.0), (-2.0, 1.0)]
+            .iter()
+            .map(|&(u, v)| point(1000.0 + u * c - v * s, 500.0 + u * s + v * c))
+            .collect();
+        rectangle.reverse();
+
+        let moments = area_moments(&rectangle).unwrap();
+        assert!((moments.area - 8.0).abs() < 1E-9);
+        assert!((moments.cx - 1000.0).abs() < 1E-9 && (moments.cy - 500.0).abs() < 1E-9);
+        let (major, minor) = moments.principal();
+        assert!((major - 16.0 / 12.0).abs() < 1E-9 && (minor - 4.0 / 12.0).abs() < 1E-9);
+        assert!((moments.orientation() - 30f64.to_radians()).abs() < 1E-9);
+        let inertia = moments.inertia();
+        assert!((inertia[0][0] + inertia[1][1] - 8.0 * 20.0 / 12.0).abs() < 1E-9);
+
+        // an elliptical ring has itself as its equivalent ellipse
+        let ellipse: Vec<Point> = (0..2000)
+            .map(|i| {
+                let t = i as f64 / 2000.0 * std::f64::consts::TAU;
+                point(3.0 + 5.0 * t.cos(), -1.0 + 2.0 * t.sin())
+            })
+            .collect();
+        let equivalent = area_moments(&ellipse).unwrap().equivalent_ellipse();
+        assert!((equivalent.semi_major - 5.0).abs() < 1E-4);
+        assert!((equivalent.semi_minor - 2.0).abs() < 1E-4);
+        assert!(
+            equivalent.angle.abs() < 1E-9 || (equivalent.angle - std::f64::consts::PI).abs() < 1E-9
+        );
+
+        assert!(area_moments(&rectangle[..2]).is_none());
+        assert!(area_moments(&[]).is_none());
+    }
+}
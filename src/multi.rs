@@ -0,0 +1,267 @@
+/// Results of Several Rings
+///
+/// Features whose result is more than one ring (offsets that split a hull, hulls cut at the
+/// antimeridian, hulls with holes) return a [`MultiHull`] rather than a `Vec` of rings, so
+/// every such result has the same accessors and exporters.
+use crate::buffer::signed_area;
+use crate::crs::{Crs, CrsMismatch};
+use crate::point::Point;
+use crate::prepared::PreparedHull;
+
+/// A polygon of a [`MultiHull`]: an outer ring and the holes cut out of it.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon {
+    /// the outer ring, open and counter-clockwise
+    pub exterior: Vec<Point>,
+    /// the holes, each open and clockwise
+    pub holes: Vec<Vec<Point>>,
+}
+
+impl Polygon {
+    /// The area of the outer ring less that of the holes.
+    pub fn area(&self) -> f64 {
+        signed_area(&self.exterior).abs()
+            - self
+                .holes
+                .iter()
+                .map(|hole| signed_area(hole).abs())
+                .sum::<f64>()
+    }
+
+    /// Whether `point` lies inside the outer ring and outside every hole.
+    pub fn contains(&self, point: &Point) -> bool {
+        PreparedHull::new(&self.exterior).contains(point)
+            && self
+                .holes
+                .iter()
+                .all(|hole| !PreparedHull::new(hole).contains(point))
+    }
+}
+
+/// A result made of several polygons.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiHull {
+    /// the polygons, in the order they were found
+    pub polygons: Vec<Polygon>,
+    /// the coordinate reference system of the polygons, `None` if not known
+    pub crs: Option<Crs>,
+}
+
+impl MultiHull {
+    /// Sorts rings into polygons by their orientation.
+    ///
+    /// Counter-clockwise rings become outer rings and clockwise rings holes, as
+    /// [`crate::buffer::buffer_rings`] returns them; each hole goes to the smallest outer
+    /// ring containing its first vertex, and a hole no outer ring contains becomes an outer
+    /// ring itself. Rings may be open or closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `rings` - the rings, in any order.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `MultiHull`, with the polygons in the order of their outer rings.
+    pub fn from_rings(rings: Vec<Vec<Point>>) -> Self {
+        let mut polygons = Vec::new();
+        let mut holes = Vec::new();
+        for ring in rings {
+            let ring = crate::open_ring(&ring).to_vec();
+            if ring.is_empty() {
+                continue;
+            }
+            if signed_area(&ring) >= 0.0 {
+                polygons.push(Polygon {
+                    exterior: ring,
+                    holes: Vec::new(),
+                });
+            } else {
+                holes.push(ring);
+            }
+        }
+
+        for mut hole in holes {
+            let owner = polygons
+                .iter()
+                .enumerate()
+                .filter(|(_, polygon)| PreparedHull::new(&polygon.exterior).contains(&hole[0]))
+                .min_by(|(_, a), (_, b)| {
+                    signed_area(&a.exterior).total_cmp(&signed_area(&b.exterior))
+                })
+                .map(|(i, _)| i);
+            match owner {
+                Some(i) => polygons[i].holes.push(hole),
+                None => {
+                    hole.reverse();
+                    polygons.push(Polygon {
+                        exterior: hole,
+                        holes: Vec::new(),
+                    });
+                }
+            }
+        }
+        MultiHull {
+            polygons,
+            crs: None,
+        }
+    }
+
+    /// The result tagged with the coordinate reference system of its coordinates.
+    pub fn with_crs(mut self, crs: Crs) -> Self {
+        self.crs = Some(crs);
+        self
+    }
+
+    /// Adds the polygons of `other` after those of this result.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - the result to add.
+    ///
+    /// # Returns
+    ///
+    /// Returns both results as one, in the system of whichever is tagged, or a
+    /// [`CrsMismatch`] if they are tagged with different systems, see [`crate::crs::common`].
+    pub fn merge(mut self, other: MultiHull) -> Result<Self, CrsMismatch> {
+        self.crs = crate::crs::common([self.crs.as_ref(), other.crs.as_ref()])?;
+        self.polygons.extend(other.polygons);
+        Ok(self)
+    }
+
+    /// The number of polygons.
+    pub fn len(&self) -> usize {
+        self.polygons.len()
+    }
+
+    /// Whether there are no polygons.
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    /// The polygons in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Polygon> {
+        self.polygons.iter()
+    }
+
+    /// Every ring, each outer ring followed by its holes.
+    pub fn rings(&self) -> impl Iterator<Item = &[Point]> {
+        self.polygons.iter().flat_map(|polygon| {
+            std::iter::once(polygon.exterior.as_slice())
+                .chain(polygon.holes.iter().map(|hole| hole.as_slice()))
+        })
+    }
+
+    /// The total area of the polygons.
+    pub fn area(&self) -> f64 {
+        self.polygons.iter().map(Polygon::area).sum()
+    }
+
+    /// Whether `point` lies inside any of the polygons.
+    pub fn contains(&self, point: &Point) -> bool {
+        self.polygons.iter().any(|polygon| polygon.contains(point))
+    }
+
+    /// The polygons as a WKT `MULTIPOLYGON`, with closed rings.
+    pub fn to_wkt(&self) -> String {
+        if self.is_empty() {
+            return "MULTIPOLYGON EMPTY".to_string();
+        }
+        let polygons: Vec<String> = self
+            .polygons
+            .iter()
+            .map(|polygon| {
+                let rings: Vec<String> = closed_rings(polygon)
+                    .map(|ring| {
+                        let coordinates: Vec<String> =
+                            ring.iter().map(|p| format!("{} {}", p.x, p.y)).collect();
+                        format!("({})", coordinates.join(", "))
+                    })
+                    .collect();
+                format!("({})", rings.join(", "))
+            })
+            .collect();
+        format!("MULTIPOLYGON ({})", polygons.join(", "))
+    }
+
+    /// The polygons as a GeoJSON `MultiPolygon` geometry, with closed rings, and a `crs`
+    /// member naming the coordinate reference system if it is known.
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson(&self) -> serde_json::Value {
+        let coordinates: Vec<Vec<Vec<[f64; 2]>>> = self
+            .polygons
+            .iter()
+            .map(|polygon| {
+                closed_rings(polygon)
+                    .map(|ring| ring.iter().map(|p| [p.x, p.y]).collect())
+                    .collect()
+            })
+            .collect();
+        let geometry = serde_json::json!({ "type": "MultiPolygon", "coordinates": coordinates });
+        crate::geojson::with_crs(geometry, self.crs.as_ref())
+    }
+}
+
+impl From<Vec<Point>> for MultiHull {
+    /// A single hull as one polygon without holes, or no polygon if it is empty.
+    fn from(hull: Vec<Point>) -> Self {
+        MultiHull::from_rings(vec![hull])
+    }
+}
+
+impl<'a> IntoIterator for &'a MultiHull {
+    type Item = &'a Polygon;
+    type IntoIter = std::slice::Iter<'a, Polygon>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// the rings of `polygon`, each with its first vertex repeated at the end
+fn closed_rings(polygon: &Polygon) -> impl Iterator<Item = Vec<Point>> + '_ {
+    std::iter::once(&polygon.exterior)
+        .chain(polygon.holes.iter())
+        .map(|ring| {
+            let mut ring = ring.clone();
+            ring.extend(ring.first().cloned());
+            ring
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_hull_from_rings() {
+        let point = |x: f64, y: f64| Point { x, y, id: 0 };
+        let square = |x: f64, y: f64, size: f64| {
+            vec![
+                point(x, y),
+                point(x + size, y),
+                point(x + size, y + size),
+                point(x, y + size),
+            ]
+        };
+        let mut hole = square(1.0, 1.0, 2.0);
+        hole.reverse();
+        let outer = square(0.0, 0.0, 4.0);
+        let multi = MultiHull::from_rings(vec![hole, outer.clone(), square(10.0, 0.0, 1.0)]);
+
+        assert_eq!(multi.len(), 2);
+        assert_eq!(multi.polygons[0].holes.len(), 1);
+        assert!(multi.polygons[1].holes.is_empty());
+        assert_eq!(multi.rings().count(), 3);
+        assert!((multi.area() - 13.0).abs() < 1E-12);
+        assert!(multi.contains(&point(0.5, 0.5)));
+        assert!(!multi.contains(&point(2.0, 2.0)));
+        assert!(multi
+            .to_wkt()
+            .starts_with("MULTIPOLYGON (((0 0, 4 0, 4 4, 0 4, 0 0), (1 3, 3 3, 3 1, 1 1, 1 3))"));
+
+        assert!(MultiHull::from(Vec::new()).is_empty());
+        assert_eq!(MultiHull::from(outer).len(), 1);
+    }
+}
@@ -0,0 +1,166 @@
+/// Hulls in Periodic (Toroidal) Domains
+use crate::error::ConcaveHullError;
+use crate::point::Point;
+
+/// A domain whose coordinates wrap around, such as longitudes in `[0, 360)` or a simulation
+/// box with periodic boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PeriodicDomain {
+    /// period along x, `None` if x doesn't wrap
+    pub x_period: Option<f64>,
+    /// period along y, `None` if y doesn't wrap
+    pub y_period: Option<f64>,
+}
+
+impl PeriodicDomain {
+    /// shortest distance from `a` to `b` over every periodic image of `b`
+    pub fn distance(&self, a: &Point, b: &Point) -> f64 {
+        let delta = |d: f64, period: Option<f64>| match period {
+            Some(period) => {
+                let d = d.rem_euclid(period);
+                d.min(period - d)
+            }
+            None => d,
+        };
+        delta(b.x - a.x, self.x_period).hypot(delta(b.y - a.y, self.y_period))
+    }
+
+    /// `point` moved into `[0, period)` along each periodic axis
+    pub fn wrap(&self, point: &Point) -> Point {
+        let wrap = |v: f64, period: Option<f64>| period.map_or(v, |period| v.rem_euclid(period));
+        Point {
+            x: wrap(point.x, self.x_period),
+            y: wrap(point.y, self.y_period),
+            id: point.id,
+        }
+    }
+
+    /// Moves `points` into one contiguous frame of the domain.
+    ///
+    /// Along each periodic axis the domain is cut at the widest gap between the points, and
+    /// the points are shifted by whole periods so that none lies across the cut. Points that
+    /// are close across the boundary of the domain, say at x = 359 and x = 1, become close
+    /// in the plane, at x = 359 and x = 361.
+    ///
+    /// # Returns
+    ///
+    /// Returns the shifted points in input order. Along each periodic axis they lie within
+    /// one period starting at the first point after the widest gap.
+    pub fn unwrap(&self, points: &[Point]) -> Vec<Point> {
+        let x_start = self.x_period.map(|p| cut(points.iter().map(|q| q.x), p));
+        let y_start = self.y_period.map(|p| cut(points.iter().map(|q| q.y), p));
+        let shift = |v: f64, period: Option<f64>, start: Option<f64>| match (period, start) {
+            (Some(period), Some(start)) => v - ((v - start) / period).floor() * period,
+            _ => v,
+        };
+        points
+            .iter()
+            .map(|p| Point {
+                x: shift(p.x, self.x_period, x_start),
+                y: shift(p.y, self.y_period, y_start),
+                id: p.id,
+            })
+            .collect()
+    }
+}
+
+/// the value after the widest gap between `values` on a circle of circumference `period`
+fn cut(values: impl Iterator<Item = f64>, period: f64) -> f64 {
+    let mut wrapped: Vec<f64> = values.map(|v| v.rem_euclid(period)).collect();
+    wrapped.sort_by(f64::total_cmp);
+    let Some(&first) = wrapped.first() else {
+        return 0.0;
+    };
+
+    // the gap from the last value round to the first
+    let (mut start, mut widest) = (first, first + period - wrapped[wrapped.len() - 1]);
+    for pair in wrapped.windows(2) {
+        if pair[1] - pair[0] > widest {
+            (start, widest) = (pair[1], pair[1] - pair[0]);
+        }
+    }
+    start
+}
+
+/// Computes the concave hull of points in a periodic domain.
+///
+/// The points are first moved into one contiguous frame with [`PeriodicDomain::unwrap`], so
+/// neighbours across the domain boundary are found and the hull doesn't span the whole
+/// domain to connect them. The widest gap along each periodic axis is never bridged, so
+/// data that wraps all the way around an axis, like a band around a torus, is cut open
+/// there.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud, with unique ids.
+/// * `domain` - the periods of the domain.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull as [`crate::concave_hull`] does, in the unwrapped frame: a hull across
+/// the domain boundary keeps contiguous coordinates, e.g. x from 350 to 370 for a period of
+/// 360, rather than jumping back to 10. [`PeriodicDomain::wrap`] maps single vertices back.
+pub fn concave_hull_periodic(
+    dataset: &[Point],
+    domain: &PeriodicDomain,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    crate::concave_hull(&mut domain.unwrap(dataset), k, iterate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_concave_hull_periodic() {
+        // a 20 x 20 patch across the corner of a 360 x 180 torus
+        let mut rng = Rng::new(8);
+        let points: Vec<Point> = (0..300)
+            .map(|id| Point {
+                x: (350.0 + 20.0 * rng.uniform()).rem_euclid(360.0),
+                y: (170.0 + 20.0 * rng.uniform()).rem_euclid(180.0),
+                id,
+            })
+            .collect();
+        let domain = PeriodicDomain {
+            x_period: Some(360.0),
+            y_period: Some(180.0),
+        };
+
+        let hull = concave_hull_periodic(&points, &domain, 3, true).unwrap();
+        assert!(!hull.is_empty());
+        assert!(hull.iter().all(|p| (350.0..370.0).contains(&p.x)));
+        assert!(hull.iter().all(|p| (170.0..190.0).contains(&p.y)));
+        // the vertices are the input points, moved by whole periods
+        let wrapped: Vec<Point> = hull.iter().map(|vertex| domain.wrap(vertex)).collect();
+        let originals: Vec<Point> = hull
+            .iter()
+            .map(|vertex| points[vertex.id as usize].clone())
+            .collect();
+        crate::assert_hull_approx_eq!(wrapped, originals);
+
+        let a = Point {
+            x: 359.0,
+            y: 1.0,
+            id: 0,
+        };
+        let b = Point {
+            x: 2.0,
+            y: 177.0,
+            id: 1,
+        };
+        assert!(ulps_eq(domain.distance(&a, &b), 5.0, DEFAULT_ULPS));
+        assert!(ulps_eq(
+            PeriodicDomain::default().distance(&a, &b),
+            357f64.hypot(176.0),
+            DEFAULT_ULPS
+        ));
+    }
+}
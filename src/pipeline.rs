@@ -0,0 +1,411 @@
+/// Hull Post-Processing Pipelines
+use crate::buffer::buffer;
+use crate::error::ConcaveHullError;
+use crate::metric::Metric;
+use crate::point::Point;
+use crate::predicates::intersects;
+use crate::prepared::PreparedHull;
+use crate::simplify::simplify;
+use crate::smooth::{smooth, smooth_containing};
+
+use std::fmt;
+
+/// Parameters of the hull stage of a [`Pipeline`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct HullConfig {
+    /// number of nearest neighbours to start from
+    pub k: usize,
+    /// whether `k` grows until the hull contains every point
+    pub iterate: bool,
+    /// how neighbours and turning angles are measured
+    pub metric: Metric,
+}
+
+impl Default for HullConfig {
+    fn default() -> Self {
+        HullConfig {
+            k: 3,
+            iterate: true,
+            metric: Metric::Euclidean,
+        }
+    }
+}
+
+impl HullConfig {
+    /// Computes the concave hull of `points` with these parameters.
+    pub fn hull(&self, points: &[Point]) -> Result<Vec<Point>, ConcaveHullError> {
+        let mut dataset = points.to_vec();
+        match self.metric {
+            Metric::Euclidean => crate::concave_hull(&mut dataset, self.k, self.iterate),
+            Metric::Haversine => crate::concave_hull_geographic(&mut dataset, self.k, self.iterate),
+        }
+    }
+}
+
+/// A processing step applied to the hull, in order, after the hull stage.
+///
+/// Serialised steps are tagged by a snake case `step` field, e.g.
+/// `{"step": "validate", "contains_points": true}`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "step", rename_all = "snake_case"))]
+pub enum Step {
+    /// Douglas-Peucker simplification, see [`crate::simplify::simplify`]
+    Simplify { tolerance: f64 },
+    /// Chaikin smoothing, see [`crate::smooth::smooth`]; with `keep_inside` no input point
+    /// is left outside, see [`crate::smooth::smooth_containing`]
+    Smooth {
+        iterations: usize,
+        keep_inside: bool,
+    },
+    /// outward (positive) or inward (negative) offset, see [`crate::buffer::buffer`]
+    Buffer { distance: f64 },
+    /// rejects hulls without an interior or with crossing edges, and, if `contains_points`
+    /// is set, hulls leaving any input point outside
+    Validate { contains_points: bool },
+}
+
+/// Why a [`Pipeline`] rejected a hull.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineError {
+    /// the hull stage found no hull
+    Hull(ConcaveHullError),
+    /// the hull has fewer than three vertices
+    NoInterior,
+    /// two non-adjacent hull edges cross or touch
+    SelfIntersecting,
+    /// this many input points lie outside the hull
+    PointsOutside(usize),
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineError::Hull(error) => write!(f, "{}", error),
+            PipelineError::NoInterior => write!(f, "hull has no interior"),
+            PipelineError::SelfIntersecting => write!(f, "hull intersects itself"),
+            PipelineError::PointsOutside(count) => {
+                write!(f, "{} points lie outside the hull", count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+impl From<ConcaveHullError> for PipelineError {
+    fn from(error: ConcaveHullError) -> Self {
+        PipelineError::Hull(error)
+    }
+}
+
+/// Why a pipeline configuration could not be loaded.
+#[cfg(feature = "config")]
+#[derive(Debug)]
+pub enum ConfigError {
+    /// the file could not be read
+    Io(std::io::Error),
+    /// the JSON is malformed or doesn't describe a pipeline
+    Json(serde_json::Error),
+    /// the TOML is malformed or doesn't describe a pipeline
+    Toml(toml::de::Error),
+    /// the file extension is neither `.json` nor `.toml`
+    UnknownFormat(std::path::PathBuf),
+}
+
+#[cfg(feature = "config")]
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "cannot read configuration: {}", err),
+            ConfigError::Json(err) => write!(f, "invalid JSON configuration: {}", err),
+            ConfigError::Toml(err) => write!(f, "invalid TOML configuration: {}", err),
+            ConfigError::UnknownFormat(path) => {
+                write!(f, "unknown configuration format: {}", path.display())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "config")]
+impl std::error::Error for ConfigError {}
+
+/// A hull computation followed by a chain of post-processing steps.
+///
+/// A pipeline is configured once and then run on any number of datasets, so a batch job
+/// states its whole footprint processing up front:
+///
+/// ```
+/// let pipeline = Pipeline::new(HullConfig { k: 5, ..Default::default() }).validate(true);
+/// for dataset in datasets.iter() {
+///     let footprint = pipeline.run(dataset)?;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Pipeline {
+    /// parameters of the hull stage
+    pub hull: HullConfig,
+    /// steps applied to the hull, in order
+    pub steps: Vec<Step>,
+}
+
+impl Pipeline {
+    /// A pipeline computing the hull only.
+    pub fn new(hull: HullConfig) -> Self {
+        Pipeline {
+            hull,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Appends `step` to the pipeline.
+    pub fn then(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Appends a [`Step::Simplify`].
+    pub fn simplify(self, tolerance: f64) -> Self {
+        self.then(Step::Simplify { tolerance })
+    }
+
+    /// Appends a [`Step::Smooth`].
+    pub fn smooth(self, iterations: usize, keep_inside: bool) -> Self {
+        self.then(Step::Smooth {
+            iterations,
+            keep_inside,
+        })
+    }
+
+    /// Appends a [`Step::Buffer`].
+    pub fn buffer(self, distance: f64) -> Self {
+        self.then(Step::Buffer { distance })
+    }
+
+    /// Appends a [`Step::Validate`].
+    pub fn validate(self, contains_points: bool) -> Self {
+        self.then(Step::Validate { contains_points })
+    }
+
+    /// Parses a pipeline from JSON, e.g.
+    /// `{"hull": {"k": 5}, "steps": [{"step": "validate", "contains_points": true}]}`.
+    ///
+    /// Missing fields take their default values.
+    #[cfg(feature = "config")]
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(json).map_err(ConfigError::Json)
+    }
+
+    /// Parses a pipeline from TOML, with the same layout as [`Pipeline::from_json`]:
+    ///
+    /// ```toml
+    /// [hull]
+    /// k = 5
+    /// metric = "haversine"
+    ///
+    /// [[steps]]
+    /// step = "validate"
+    /// contains_points = true
+    /// ```
+    #[cfg(feature = "config")]
+    pub fn from_toml(toml: &str) -> Result<Self, ConfigError> {
+        toml::from_str(toml).map_err(ConfigError::Toml)
+    }
+
+    /// Loads a pipeline from a `.json` or `.toml` file.
+    #[cfg(feature = "config")]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, ConfigError> {
+        use crate::io::PointSource;
+        use std::io::Read;
+
+        let path = path.as_ref();
+        let mut text = String::new();
+        path.open()
+            .and_then(|mut reader| reader.read_to_string(&mut text))
+            .map_err(ConfigError::Io)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Pipeline::from_json(&text),
+            Some("toml") => Pipeline::from_toml(&text),
+            _ => Err(ConfigError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+
+    /// Runs the pipeline on `points`.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - 2D point cloud.
+    ///
+    /// # Returns
+    ///
+    /// Returns the processed hull as a closed ring, or the reason the hull stage found no
+    /// hull or a validation step rejected it.
+    pub fn run(&self, points: &[Point]) -> Result<Vec<Point>, PipelineError> {
+        let mut hull = self.hull.hull(points)?;
+        for step in self.steps.iter() {
+            match *step {
+                Step::Simplify { tolerance } => hull = simplify(&hull, tolerance),
+                Step::Smooth {
+                    iterations,
+                    keep_inside: false,
+                } => hull = smooth(&hull, iterations),
+                Step::Smooth {
+                    iterations,
+                    keep_inside: true,
+                } => hull = smooth_containing(&hull, iterations, points),
+                Step::Buffer { distance } => hull = buffer(&hull, distance),
+                Step::Validate { contains_points } => validate(&hull, points, contains_points)?,
+            }
+        }
+        Ok(closed(hull))
+    }
+}
+
+/// Checks the ring of `hull`, and optionally that no point of `points` lies outside of it.
+fn validate(hull: &[Point], points: &[Point], contains_points: bool) -> Result<(), PipelineError> {
+    let ring = crate::open_ring(hull);
+    let n = ring.len();
+    if n < 3 {
+        return Err(PipelineError::NoInterior);
+    }
+
+    let edge = |i: usize| (&ring[i], &ring[(i + 1) % n]);
+    for i in 0..n {
+        // edges sharing a vertex always touch, so only test the others
+        for j in i + 2..n {
+            if (j + 1) % n != i && intersects(edge(i), edge(j)) {
+                return Err(PipelineError::SelfIntersecting);
+            }
+        }
+    }
+
+    if contains_points {
+        let prepared = PreparedHull::new(ring);
+        let outside = points
+            .iter()
+            .filter(|p| !prepared.contains(p) && prepared.distance(p) > 1E-10)
+            .count();
+        if outside > 0 {
+            return Err(PipelineError::PointsOutside(outside));
+        }
+    }
+
+    Ok(())
+}
+
+/// Closes `hull` by repeating its first vertex, unless it already is closed.
+fn closed(mut hull: Vec<Point>) -> Vec<Point> {
+    if crate::open_ring(&hull).len() == hull.len() {
+        if let Some(first) = hull.first().cloned() {
+            hull.push(first);
+        }
+    }
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipeline() {
+        let points: Vec<Point> = (0..100)
+            .map(|i| Point {
+                x: (i % 10) as f64,
+                y: (i / 10) as f64 + 0.01 * (i % 3) as f64,
+                id: i,
+            })
+            .collect();
+
+        let pipeline = Pipeline::new(HullConfig::default()).validate(true);
+        let hull = pipeline.run(&points).unwrap();
+        assert_eq!(hull.first(), hull.last());
+        assert_eq!(Ok(hull.clone()), HullConfig::default().hull(&points));
+        // the configured pipeline is reusable
+        assert!(pipeline.run(&points[..50]).is_ok());
+        assert_eq!(
+            pipeline.run(&points[..3]),
+            Err(PipelineError::Hull(ConcaveHullError::InsufficientPoints {
+                required: 4,
+                found: 3
+            }))
+        );
+
+        let simplified = Pipeline::new(HullConfig::default())
+            .simplify(0.1)
+            .run(&points)
+            .unwrap();
+        assert_eq!(simplified, simplify(&hull, 0.1));
+        assert!(simplified.len() < hull.len());
+        let smoothed = Pipeline::new(HullConfig::default())
+            .smooth(2, true)
+            .validate(true)
+            .run(&points);
+        assert!(smoothed.is_ok());
+        let buffered = Pipeline::new(HullConfig::default())
+            .buffer(0.5)
+            .validate(true)
+            .run(&points);
+        assert!(buffered.is_ok());
+
+        let bowtie: Vec<Point> = [(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)]
+            .iter()
+            .map(|&(x, y)| Point { x, y, id: 0 })
+            .collect();
+        assert_eq!(
+            validate(&bowtie, &[], false),
+            Err(PipelineError::SelfIntersecting)
+        );
+        assert_eq!(
+            validate(&bowtie[..2], &[], false),
+            Err(PipelineError::NoInterior)
+        );
+        let square = [
+            bowtie[0].clone(),
+            bowtie[2].clone(),
+            bowtie[1].clone(),
+            bowtie[3].clone(),
+        ];
+        let far = Point {
+            x: 5.0,
+            y: 5.0,
+            id: 1,
+        };
+        assert_eq!(validate(&square, &bowtie, true), Ok(()));
+        assert_eq!(
+            validate(&square, &[far], true),
+            Err(PipelineError::PointsOutside(1))
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_pipeline_config() {
+        let expected = Pipeline::new(HullConfig {
+            k: 5,
+            metric: Metric::Haversine,
+            ..Default::default()
+        })
+        .validate(true);
+
+        let json = r#"{"hull": {"k": 5, "metric": "haversine"},
+                       "steps": [{"step": "validate", "contains_points": true}]}"#;
+        assert_eq!(Pipeline::from_json(json).unwrap(), expected);
+
+        let toml = "[hull]\nk = 5\nmetric = \"haversine\"\n\n\
+                    [[steps]]\nstep = \"validate\"\ncontains_points = true\n";
+        assert_eq!(Pipeline::from_toml(toml).unwrap(), expected);
+
+        assert_eq!(Pipeline::from_json("{}").unwrap(), Pipeline::default());
+        assert!(matches!(
+            Pipeline::from_json(r#"{"steps": [{"step": "unknown"}]}"#),
+            Err(ConfigError::Json(_))
+        ));
+        let round_trip = serde_json::to_string(&expected).unwrap();
+        assert_eq!(Pipeline::from_json(&round_trip).unwrap(), expected);
+    }
+}
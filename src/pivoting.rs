@@ -0,0 +1,184 @@
+/// Ball-Pivoting Boundary Tracing
+use crate::point::Point;
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// points bucketed into square cells of side `2 * radius`, so the candidates the disk
+/// about a point can meet lie in the 3 x 3 cells around it
+struct Cells {
+    size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+impl Cells {
+    fn new(points: &[Point], size: f64) -> Self {
+        let mut cells: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (i, p) in points.iter().enumerate() {
+            cells.entry(Self::key(p, size)).or_default().push(i);
+        }
+        Cells { size, cells }
+    }
+
+    fn key(p: &Point, size: f64) -> (i64, i64) {
+        ((p.x / size).floor() as i64, (p.y / size).floor() as i64)
+    }
+
+    /// indices of the points in the cells around `p`
+    fn around(&self, p: &Point) -> impl Iterator<Item = usize> + '_ {
+        let (i, j) = Self::key(p, self.size);
+        (i - 1..=i + 1)
+            .flat_map(move |ci| (j - 1..=j + 1).map(move |cj| (ci, cj)))
+            .filter_map(|key| self.cells.get(&key))
+            .flatten()
+            .copied()
+    }
+}
+
+/// Traces the boundary swept by a disk rolled around the outside of `points` (2D ball
+/// pivoting).
+///
+/// The disk starts below the lowest point and pivots counter-clockwise about the current
+/// vertex until its rim meets another point, which becomes the next vertex; the disk never
+/// contains a point. Unlike the k nearest neighbours, the disk radius is a length, so the
+/// level of detail stays the same where points are dense and where they are sparse. Gaps
+/// wider than the disk are entered, and the disk turns back at the tip of a spur, which then
+/// appears twice on the ring. Points further than `2 * radius` from the traced component
+/// (another cluster, an isolated outlier) are left outside it.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `radius` - radius of the rolling disk; larger disks give smoother, more convex hulls.
+///
+/// # Returns
+///
+/// Returns the vertices the disk touched as a closed counter-clockwise ring, starting from
+/// the lowest point. Returns an empty vector if `radius` is not positive or the lowest
+/// point has no other point within `2 * radius`.
+pub fn ball_pivoting(points: &[Point], radius: f64) -> Vec<Point> {
+    if points.is_empty() || radius.is_nan() || radius <= 0.0 {
+        return Vec::new();
+    }
+
+    let cells = Cells::new(points, 2.0 * radius);
+    let start = (0..points.len())
+        .min_by(|&a, &b| {
+            (points[a].y.total_cmp(&points[b].y)).then(points[a].x.total_cmp(&points[b].x))
+        })
+        .unwrap();
+
+    // the disk centre is at `angle` from the current vertex, first straight below it
+    let (mut current, mut angle) = (start, -PI / 2.0);
+    let mut ring = vec![points[start].clone()];
+    let mut first_step = None;
+    // the outline walks each edge at most once in each direction, and the edges the disk
+    // can roll along form a planar graph, which has fewer than 3n edges
+    for _ in 0..6 * points.len() {
+        let Some((next, centre_angle)) = pivot(points, &cells, current, angle, radius) else {
+            return Vec::new();
+        };
+        if first_step.is_none() {
+            first_step = Some(next);
+        } else if current == start && first_step == Some(next) {
+            return ring;
+        }
+        ring.push(points[next].clone());
+        // seen from the next vertex, the centre makes the same angle with the edge
+        let (p, q) = (&points[current], &points[next]);
+        let cx = p.x + radius * centre_angle.cos();
+        let cy = p.y + radius * centre_angle.sin();
+        angle = (cy - q.y).atan2(cx - q.x);
+        current = next;
+    }
+    ring
+}
+
+/// The point the disk about `points[pivot]` meets first when its centre turns
+/// counter-clockwise from `angle`, and the angle of the centre when it does.
+fn pivot(
+    points: &[Point],
+    cells: &Cells,
+    pivot: usize,
+    angle: f64,
+    radius: f64,
+) -> Option<(usize, f64)> {
+    let p = &points[pivot];
+    let reach = 2.0 * radius;
+    let mut best: Option<(f64, f64, usize, f64)> = None;
+    for candidate in cells.around(p) {
+        let q = &points[candidate];
+        let distance = (q.x - p.x).hypot(q.y - p.y);
+        if distance == 0.0 || distance > reach {
+            continue;
+        }
+        // the rim reaches q when the centre is half_angle short of the direction to q
+        let half_angle = (distance / reach).min(1.0).acos();
+        let centre_angle = (q.y - p.y).atan2(q.x - p.x) - half_angle;
+        let sweep = (centre_angle - angle).rem_euclid(2.0 * PI);
+        // points met at the same time (on a common rim) are taken nearest first
+        let better = best
+            .is_none_or(|(s, d, _, _)| sweep < s - 1E-12 || (sweep <= s + 1E-12 && distance < d));
+        if better {
+            best = Some((sweep, distance, candidate, centre_angle));
+        }
+    }
+    best.map(|(_, _, next, centre_angle)| (next, centre_angle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
+    use crate::prepared::PreparedHull;
+
+    #[test]
+    fn test_ball_pivoting() {
+        // a C shape: a dense bar along y = 0 and sparse bars along x = 0 and y = 10
+        let mut points = Vec::new();
+        for i in 0..=100 {
+            points.push((i as f64 * 0.1, 0.0));
+        }
+        for i in 1..=10 {
+            points.push((0.0, i as f64));
+            points.push((i as f64, 10.0));
+        }
+        let points: Vec<Point> = points
+            .into_iter()
+            .enumerate()
+            .map(|(id, (x, y))| Point {
+                x,
+                y,
+                id: id as u64,
+            })
+            .collect();
+
+        let ring = ball_pivoting(&points, 1.5);
+        assert_eq!(ring.first(), ring.last());
+        assert!(ulps_eq(ring[0].x, 0.0, DEFAULT_ULPS) && ulps_eq(ring[0].y, 0.0, DEFAULT_ULPS));
+        assert!(ring.iter().all(|p| points.contains(p)));
+        // the disk enters the C through its opening, so both sides of the bars are traced
+        let visits = |x: f64, y: f64| ring[1..].iter().filter(|p| (p.x, p.y) == (x, y)).count();
+        assert_eq!(visits(10.0, 0.0), 1);
+        assert_eq!(visits(10.0, 10.0), 1);
+        assert_eq!(visits(0.0, 5.0), 2);
+        assert_eq!(visits(5.0, 0.0), 2);
+        assert!(!PreparedHull::new(&ring).contains(&Point {
+            x: 5.0,
+            y: 5.0,
+            id: 0,
+        }));
+
+        // a disk larger than the opening rolls over it
+        let ring = ball_pivoting(&points, 20.0);
+        assert_eq!(ring.len(), points.len() + 1);
+        assert!(crate::buffer::signed_area(&ring) > 0.0);
+        assert!(PreparedHull::new(&ring).contains(&Point {
+            x: 5.0,
+            y: 5.0,
+            id: 0,
+        }));
+        assert!(ball_pivoting(&points, 0.0).is_empty());
+        assert!(ball_pivoting(&points, 0.01).is_empty());
+    }
+}
@@ -1,28 +1,30 @@
 use std::f64::consts::PI;
 
 /// Point Primitives
+#[cfg(feature = "float")]
+use num_traits::Float;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
 
 /// 2D Point with Identifier
 /// The identified is used to identify points between data structures
 /// (the points list and the kd-tree)
-#[pyclass]
+#[cfg_attr(feature = "python", pyclass(get_all, set_all))]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     /// x coordinate
-    #[pyo3(get, set)]
     pub x: f64,
     /// y coordinate
-    #[pyo3(get, set)]
     pub y: f64,
     /// identifier
-    #[pyo3(get, set)]
     pub id: u64,
 }
 
-#[pymethods]
+#[cfg_attr(feature = "python", pymethods)]
 impl Point {
     /// constructor for python bindings
+    #[cfg(feature = "python")]
     #[new]
     fn new(x: f64, y: f64, id: u64) -> Self {
         Point { x, y, id }
@@ -34,9 +36,73 @@ impl Point {
     }
 }
 
+/// The dimension of the hull; the neighbour index reads coordinates through
+/// [`Coordinates`] for any dimension, while the walk and its geometry are planar.
+pub const DIM: usize = 2;
+
+/// Anything with `D` coordinates, as the neighbour index reads them.
+pub trait Coordinates<const D: usize> {
+    /// the coordinates in axis order
+    fn coordinates(&self) -> [f64; D];
+}
+
+impl Coordinates<DIM> for Point {
+    fn coordinates(&self) -> [f64; DIM] {
+        [self.x, self.y]
+    }
+}
+
+impl<const D: usize> Coordinates<D> for [f64; D] {
+    fn coordinates(&self) -> [f64; D] {
+        *self
+    }
+}
+
+/// Anything with 2D coordinates that can be hulled, see [`crate::concave_hull_of`].
+pub trait HullPoint {
+    /// x coordinate
+    fn x(&self) -> f64;
+    /// y coordinate
+    fn y(&self) -> f64;
+}
+
+impl HullPoint for Point {
+    fn x(&self) -> f64 {
+        self.x
+    }
+
+    fn y(&self) -> f64 {
+        self.y
+    }
+}
+
+impl HullPoint for (f64, f64) {
+    fn x(&self) -> f64 {
+        self.0
+    }
+
+    fn y(&self) -> f64 {
+        self.1
+    }
+}
+
+/// coordinate pairs of any float type, e.g. `[f32; 2]`, widened to `f64` (NaN if they
+/// don't fit)
+#[cfg(feature = "float")]
+impl<T: Float> HullPoint for [T; 2] {
+    fn x(&self) -> f64 {
+        self[0].to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn y(&self) -> f64 {
+        self[1].to_f64().unwrap_or(f64::NAN)
+    }
+}
+
 /// Point Value -- Neighbor Information
 /// Point value captures a point, with a distance and angle quantity with
 /// respect to another point
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PointValue {
     /// identified point
     pub point: Point,
@@ -46,6 +112,54 @@ pub struct PointValue {
     pub angle: f64,
 }
 
+/// Axis-Aligned Bounding Box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// minimum x coordinate
+    pub min_x: f64,
+    /// minimum y coordinate
+    pub min_y: f64,
+    /// maximum x coordinate
+    pub max_x: f64,
+    /// maximum y coordinate
+    pub max_y: f64,
+}
+
+impl BoundingBox {
+    /// smallest box containing every point (inverted and infinite if there are none)
+    pub fn from_points(points: &[Point]) -> Self {
+        points.iter().fold(
+            BoundingBox {
+                min_x: f64::INFINITY,
+                min_y: f64::INFINITY,
+                max_x: f64::NEG_INFINITY,
+                max_y: f64::NEG_INFINITY,
+            },
+            |b, p| BoundingBox {
+                min_x: b.min_x.min(p.x),
+                min_y: b.min_y.min(p.y),
+                max_x: b.max_x.max(p.x),
+                max_y: b.max_y.max(p.y),
+            },
+        )
+    }
+
+    /// extent along x
+    pub fn width(&self) -> f64 {
+        (self.max_x - self.min_x).max(0.0)
+    }
+
+    /// extent along y
+    pub fn height(&self) -> f64 {
+        (self.max_y - self.min_y).max(0.0)
+    }
+
+    /// enclosed area
+    pub fn area(&self) -> f64 {
+        self.width() * self.height()
+    }
+}
+
 pub fn normalise_angle(radians: f64) -> f64 {
     if radians < 0.0 {
         radians + PI + PI
@@ -57,6 +171,7 @@ pub fn normalise_angle(radians: f64) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
 
     fn to_degrees(radians: f64) -> f64 {
         radians * 180.0 / std::f64::consts::PI
@@ -73,7 +188,7 @@ mod tests {
                     .angle(&p),
             );
             assert!(
-                (actual == expected),
+                ulps_eq(actual, expected, DEFAULT_ULPS),
                 "Test failed for point: ({}, {})",
                 p.x,
                 p.y
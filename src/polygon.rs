@@ -0,0 +1,466 @@
+/// Point in Polygon Tests
+///
+/// These are the containment tests the hull is checked with, so a validation against them
+/// agrees with the hull about every point, including those on its boundary.
+use crate::metric::Tolerance;
+use crate::point::Point;
+use crate::predicates::{orientation_exact, point_on_segment, Turn};
+use crate::prepared::PreparedHull;
+
+/// How a containment test counts a point as inside a ring.
+///
+/// All three cast a ray from the point towards +x and look at the edges it crosses, with
+/// the [`Boundary::HalfOpen`] rule for points on them; on simple rings they agree, but for
+/// rounding and tolerance at the boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Containment {
+    /// inside after an odd number of crossings; edges whose rise is within the tolerance of
+    /// zero are skipped
+    #[default]
+    CrossingNumber,
+    /// inside where the ring winds round the point, crossings upwards counting one way and
+    /// downwards the other; rings that touch or run over themselves keep the regions they
+    /// enclose twice, which the crossing number leaves out
+    WindingNumber,
+    /// the crossing number with the side of each edge the point is on decided by
+    /// [`orientation_exact`], without a tolerance
+    Exact,
+}
+
+impl Containment {
+    /// Whether `point` lies inside `polygon` by this test.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - the point to test.
+    /// * `polygon` - the polygon, open or closed, in either orientation.
+    /// * `tolerance` - below which an edge counts as horizontal, unless exact.
+    ///
+    /// # Returns
+    ///
+    /// Returns whether the point is inside; `false` for fewer than three vertices.
+    pub fn point_in_polygon(self, point: &Point, polygon: &[Point], tolerance: &Tolerance) -> bool {
+        if polygon.len() <= 2 {
+            return false;
+        }
+        // an open ring is closed by its last edge; a closed one starts with an empty edge
+        let mut v0 = &polygon[polygon.len() - 1];
+        let mut winding = 0;
+        for v1 in polygon.iter() {
+            winding += self.crossing(point, v0, v1, tolerance);
+            v0 = v1;
+        }
+        self.inside(winding)
+    }
+
+    /// `1` if the ray from `point` crosses the edge from `v0` to `v1` upwards, `-1` if
+    /// downwards and `0` if not at all
+    pub(crate) fn crossing(
+        self,
+        point: &Point,
+        v0: &Point,
+        v1: &Point,
+        tolerance: &Tolerance,
+    ) -> i32 {
+        let (x, y) = (point.x, point.y);
+        let direction = if v0.y <= y && y < v1.y {
+            1
+        } else if v1.y <= y && y < v0.y {
+            -1
+        } else {
+            return 0;
+        };
+        let left = match self {
+            Containment::Exact => {
+                let turn = orientation_exact(v0, v1, point);
+                turn == if direction > 0 {
+                    Turn::Left
+                } else {
+                    Turn::Right
+                }
+            }
+            _ => {
+                if tolerance.is_zero(v1.y - v0.y, (v1.x - v0.x).hypot(v1.y - v0.y)) {
+                    return 0;
+                }
+                x < v0.x + (v1.x - v0.x) * ((y - v0.y) / (v1.y - v0.y))
+            }
+        };
+        if left {
+            direction
+        } else {
+            0
+        }
+    }
+
+    /// whether the sum of the [`Containment::crossing`]s of a ring puts a point inside it
+    pub(crate) fn inside(self, winding: i32) -> bool {
+        match self {
+            Containment::WindingNumber => winding != 0,
+            Containment::CrossingNumber | Containment::Exact => winding % 2 != 0,
+        }
+    }
+}
+
+/// Whether points on the boundary of a polygon count as inside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Boundary {
+    /// the rule of the crossing-number test the hull is checked with: points on edges
+    /// bounding the polygon from the left or from below are inside, those on edges bounding
+    /// it from the right or from above outside, so that polygons tiling the plane give each
+    /// point between them to exactly one of them
+    #[default]
+    HalfOpen,
+    /// every point on the boundary is inside
+    Inclusive,
+    /// every point on the boundary is outside
+    Exclusive,
+}
+
+/// Whether `point` lies inside `polygon` under the [`Boundary::HalfOpen`] rule.
+///
+/// `polygon` may be open or closed, in either orientation, and `false` is returned for
+/// fewer than three vertices.
+pub fn point_in_polygon(point: &Point, polygon: &[Point]) -> bool {
+    point_in_polygon_within(point, polygon, &Tolerance::default())
+}
+
+/// Crossing-number test of `point` against `polygon`, skipping edges whose rise is within
+/// `tolerance` of zero.
+///
+/// This is [`point_in_polygon`] with the tolerance of the hull computation, see
+/// [`crate::builder::ConcaveHullBuilder::tolerance`], and [`Containment::CrossingNumber`].
+pub fn point_in_polygon_within(point: &Point, polygon: &[Point], tolerance: &Tolerance) -> bool {
+    Containment::CrossingNumber.point_in_polygon(point, polygon, tolerance)
+}
+
+/// Whether `point` lies on an edge of `polygon`: within `tolerance` of it, taking the
+/// threshold relative to the edge length.
+pub fn on_boundary(point: &Point, polygon: &[Point], tolerance: &Tolerance) -> bool {
+    let Some(mut v0) = polygon.last() else {
+        return false;
+    };
+    for v1 in polygon.iter() {
+        if point_on_segment(point, (v0, v1), tolerance) {
+            return true;
+        }
+        v0 = v1;
+    }
+    false
+}
+
+/// Whether `point` lies inside `polygon`, with boundary points in or out by `boundary`.
+///
+/// # Arguments
+///
+/// * `point` - the point to test.
+/// * `polygon` - the polygon, open or closed, in either orientation.
+/// * `containment` - the test for points off the boundary.
+/// * `boundary` - whether points on the boundary are inside.
+/// * `tolerance` - how far from an edge a point still counts as on it, and below which an
+///   edge counts as horizontal.
+///
+/// # Returns
+///
+/// Returns whether the point is inside; `false` for fewer than three vertices.
+pub fn contains(
+    point: &Point,
+    polygon: &[Point],
+    containment: Containment,
+    boundary: Boundary,
+    tolerance: &Tolerance,
+) -> bool {
+    let inside = || containment.point_in_polygon(point, polygon, tolerance);
+    match boundary {
+        Boundary::HalfOpen => inside(),
+        Boundary::Inclusive => {
+            polygon.len() > 2 && (on_boundary(point, polygon, tolerance) || inside())
+        }
+        Boundary::Exclusive => !on_boundary(point, polygon, tolerance) && inside(),
+    }
+}
+
+/// Tests many points against one polygon, see [`contains`].
+///
+/// # Returns
+///
+/// Returns whether each of `points` is inside, in their order.
+pub fn points_in_polygon(
+    points: &[Point],
+    polygon: &[Point],
+    containment: Containment,
+    boundary: Boundary,
+    tolerance: &Tolerance,
+) -> Vec<bool> {
+    points
+        .iter()
+        .map(|p| contains(p, polygon, containment, boundary, tolerance))
+        .collect()
+}
+
+/// Whether every one of `points` lies inside `polygon`, see [`point_in_polygon_within`].
+///
+/// The test stops at the first point outside; long polygons are indexed first, see
+/// [`count_outside`].
+pub fn multiple_point_in_polygon<'a>(
+    points: impl Iterator<Item = &'a Point>,
+    polygon: &[Point],
+    tolerance: &Tolerance,
+) -> bool {
+    count_outside(
+        points,
+        polygon,
+        Containment::CrossingNumber,
+        tolerance,
+        0,
+        1,
+    ) == 0
+}
+
+/// below this many vertices a polygon is tested edge by edge instead of being indexed
+const PREPARED_MIN_VERTICES: usize = 64;
+
+/// below this many points a polygon is tested edge by edge instead of being indexed
+const PREPARED_MIN_POINTS: usize = 16;
+
+/// below this many edge tests the points are tested on the calling thread
+#[cfg(feature = "parallel")]
+const PARALLEL_TESTS: usize = 1 << 22;
+
+/// Counts the `points` outside of `polygon` by `containment`, stopping once `limit` is
+/// reached.
+///
+/// This is the check of every hull against the points it leaves out, so it stops as soon
+/// as the answer is known. Long polygons tested with many points are indexed first as a
+/// [`PreparedHull`], so that each point only meets the edges spanning its horizontal band,
+/// and with the `parallel` feature many points are split among `threads` threads (0 for one
+/// per core), which all stop once `limit` points outside have been found between them.
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+pub(crate) fn count_outside<'a>(
+    points: impl Iterator<Item = &'a Point>,
+    polygon: &[Point],
+    containment: Containment,
+    tolerance: &Tolerance,
+    threads: usize,
+    limit: usize,
+) -> usize {
+    let expected = points.size_hint().1.unwrap_or(usize::MAX);
+    let prepared = (polygon.len() >= PREPARED_MIN_VERTICES && expected >= PREPARED_MIN_POINTS)
+        .then(|| {
+            PreparedHull::new(polygon)
+                .with_tolerance(*tolerance)
+                .with_containment(containment)
+        });
+    let outside = |p: &Point| match &prepared {
+        Some(prepared) => !prepared.contains(p),
+        None => !containment.point_in_polygon(p, polygon, tolerance),
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        // the edges each point meets: those of its band, of about the square root of all
+        let edges = match prepared {
+            Some(_) => (polygon.len() as f64).sqrt() as usize,
+            None => polygon.len(),
+        };
+        let threads = crate::thread_count(threads);
+        if threads > 1 && expected.saturating_mul(edges) >= PARALLEL_TESTS {
+            let points: Vec<&Point> = points.collect();
+            if points.len() * edges >= PARALLEL_TESTS {
+                return count_outside_parallel(&points, &outside, limit, threads);
+            }
+            return points
+                .into_iter()
+                .filter(|p| outside(p))
+                .take(limit)
+                .count();
+        }
+    }
+
+    points.filter(|p| outside(p)).take(limit).count()
+}
+
+/// [`count_outside`] on `threads` threads, each taking a contiguous share of `points`.
+#[cfg(feature = "parallel")]
+fn count_outside_parallel(
+    points: &[&Point],
+    outside: &(impl Fn(&Point) -> bool + Sync),
+    limit: usize,
+    threads: usize,
+) -> usize {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let found = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for share in points.chunks(points.len().div_ceil(threads)) {
+            let found = &found;
+            scope.spawn(move || {
+                for p in share {
+                    if found.load(Ordering::Relaxed) >= limit {
+                        break;
+                    }
+                    if outside(p) {
+                        found.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+    found.into_inner().min(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f64, y: f64) -> Point {
+        Point { x, y, id: 0 }
+    }
+
+    #[test]
+    fn test_contains() {
+        let square = [
+            point(0.0, 0.0),
+            point(2.0, 0.0),
+            point(2.0, 2.0),
+            point(0.0, 2.0),
+        ];
+        let mut closed = square.to_vec();
+        closed.push(square[0].clone());
+        let tolerance = Tolerance::default();
+
+        let queries = [
+            point(1.0, 1.0),
+            point(3.0, 1.0),
+            point(0.0, 1.0),
+            point(2.0, 1.0),
+            point(1.0, 0.0),
+            point(1.0, 2.0),
+        ];
+        let half_open = [true, false, true, false, true, false];
+        for ring in [&square[..], &closed[..]] {
+            assert_eq!(
+                points_in_polygon(
+                    &queries,
+                    ring,
+                    Containment::CrossingNumber,
+                    Boundary::HalfOpen,
+                    &tolerance
+                ),
+                half_open
+            );
+            assert_eq!(
+                points_in_polygon(
+                    &queries,
+                    ring,
+                    Containment::CrossingNumber,
+                    Boundary::Inclusive,
+                    &tolerance
+                ),
+                [true, false, true, true, true, true]
+            );
+            assert_eq!(
+                points_in_polygon(
+                    &queries,
+                    ring,
+                    Containment::CrossingNumber,
+                    Boundary::Exclusive,
+                    &tolerance
+                ),
+                [true, false, false, false, false, false]
+            );
+        }
+        assert!(multiple_point_in_polygon(
+            queries[..1].iter(),
+            &closed,
+            &tolerance
+        ));
+        assert!(!multiple_point_in_polygon(
+            queries.iter(),
+            &closed,
+            &tolerance
+        ));
+        assert!(!contains(
+            &point(0.0, 0.0),
+            &square[..2],
+            Containment::default(),
+            Boundary::Inclusive,
+            &tolerance
+        ));
+    }
+
+    #[test]
+    fn test_containment() {
+        // a pentagram winds twice round its centre and once round its tips
+        let star: Vec<Point> = (0..5)
+            .map(|i| {
+                let angle =
+                    std::f64::consts::FRAC_PI_2 + (2 * i) as f64 * 0.4 * std::f64::consts::PI;
+                point(angle.cos(), angle.sin())
+            })
+            .collect();
+        let tolerance = Tolerance::default();
+        let (centre, tip, away) = (point(0.0, 0.0), point(0.0, 0.8), point(2.0, 0.0));
+        let inside = |containment: Containment| {
+            [&centre, &tip, &away].map(|p| containment.point_in_polygon(p, &star, &tolerance))
+        };
+        assert_eq!(inside(Containment::CrossingNumber), [false, true, false]);
+        assert_eq!(inside(Containment::Exact), [false, true, false]);
+        assert_eq!(inside(Containment::WindingNumber), [true, true, false]);
+        let prepared = PreparedHull::new(&star).with_containment(Containment::WindingNumber);
+        assert!(prepared.contains(&centre) && prepared.contains(&tip) && !prepared.contains(&away));
+
+        // a point a rounding error off an edge, where only the exact test is sure
+        let sliver = [point(0.0, 0.0), point(3.0, 0.1), point(0.0, 0.3)];
+        let below = point(1.5, 0.05f64.next_down());
+        let above = point(1.5, 0.05f64.next_up());
+        assert!(!Containment::Exact.point_in_polygon(&below, &sliver, &tolerance));
+        assert!(Containment::Exact.point_in_polygon(&above, &sliver, &tolerance));
+    }
+
+    #[test]
+    fn test_count_outside() {
+        // a wavy ring long enough to be indexed, with enough points to be split among
+        // threads with the `parallel` feature
+        let ring: Vec<Point> = (0..256)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / 256.0;
+                let radius = 1.0 + 0.2 * (7.0 * angle).sin();
+                point(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        let mut rng = crate::synthetic::Rng::new(5);
+        let points: Vec<Point> = (0..1 << 18)
+            .map(|_| point(2.5 * rng.uniform() - 1.25, 2.5 * rng.uniform() - 1.25))
+            .collect();
+        let tolerance = Tolerance::default();
+        for containment in [Containment::CrossingNumber, Containment::WindingNumber] {
+            let outside = points
+                .iter()
+                .filter(|p| !containment.point_in_polygon(p, &ring, &tolerance))
+                .count();
+            assert!(outside > 1000);
+            let count = |points: &[Point], limit| {
+                count_outside(points.iter(), &ring, containment, &tolerance, 0, limit)
+            };
+            assert_eq!(count(&points, usize::MAX), outside);
+            assert_eq!(count(&points, 7), 7);
+            // too few points to index the ring
+            let few = &points[..10];
+            let expected = few
+                .iter()
+                .filter(|p| !containment.point_in_polygon(p, &ring, &tolerance))
+                .count();
+            assert_eq!(count(few, usize::MAX), expected);
+        }
+
+        let inside: Vec<Point> = points
+            .iter()
+            .filter(|p| p.x.hypot(p.y) < 0.7)
+            .cloned()
+            .collect();
+        assert!(multiple_point_in_polygon(inside.iter(), &ring, &tolerance));
+        assert!(!multiple_point_in_polygon(points.iter(), &ring, &tolerance));
+    }
+}
@@ -0,0 +1,138 @@
+/// Concave Hulls of Polylines
+use crate::error::ConcaveHullError;
+use crate::point::Point;
+
+/// Samples polylines densely enough for a hull to follow their segments.
+///
+/// Every segment longer than `spacing` gets evenly spaced points added between its ends, so
+/// that no two consecutive points along a polyline lie further than `spacing` apart.
+///
+/// # Arguments
+///
+/// * `polylines` - the polylines, each a sequence of vertices with ids unique across all of
+///   them. A vertex shared by several polylines, e.g. a road junction, may appear in each
+///   with the same id; it is kept once.
+/// * `spacing` - the largest gap between consecutive samples; not positive keeps the
+///   vertices only.
+///
+/// # Returns
+///
+/// Returns the vertices followed by the added points. Added points get ids above the largest
+/// vertex id, numbered in order along the polylines.
+pub fn densify(polylines: &[Vec<Point>], spacing: f64) -> Vec<Point> {
+    let mut vertices: Vec<Point> = polylines.iter().flatten().cloned().collect();
+    vertices.sort_by_key(|p| p.id);
+    vertices.dedup_by_key(|p| p.id);
+
+    let mut next_id = vertices.iter().map(|p| p.id + 1).max().unwrap_or(0);
+    let mut added = Vec::new();
+    if spacing > 0.0 {
+        for polyline in polylines {
+            for segment in polyline.windows(2) {
+                let (a, b) = (&segment[0], &segment[1]);
+                let pieces = ((b.x - a.x).hypot(b.y - a.y) / spacing).ceil() as usize;
+                for i in 1..pieces {
+                    let t = i as f64 / pieces as f64;
+                    added.push(Point {
+                        x: a.x + t * (b.x - a.x),
+                        y: a.y + t * (b.y - a.y),
+                        id: next_id,
+                    });
+                    next_id += 1;
+                }
+            }
+        }
+    }
+
+    vertices.extend(added);
+    vertices
+}
+
+/// Computes the concave hull of a set of polylines.
+///
+/// The hull of the vertices alone cuts across long segments; this densifies the polylines
+/// first (see [`densify`]), so the hull wraps around linear features such as road networks
+/// or rivers. A `spacing` near the typical distance between features keeps the hull from
+/// bridging the gaps between them.
+///
+/// # Arguments
+///
+/// * `polylines` - the polylines, with vertex ids unique across all of them.
+/// * `spacing` - the largest gap between samples along a polyline.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the hull as [`crate::concave_hull`] does. Hull vertices on a polyline vertex keep
+/// its id; the others carry the ids given to the added points by [`densify`].
+pub fn concave_hull_polylines(
+    polylines: &[Vec<Point>],
+    spacing: f64,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    crate::concave_hull(&mut densify(polylines, spacing), k, iterate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prepared::PreparedHull;
+
+    fn polyline(vertices: &[(f64, f64)], first_id: u64) -> Vec<Point> {
+        vertices
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| Point {
+                x,
+                y,
+                id: first_id + i as u64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_concave_hull_polylines() {
+        // a ring road around a U-shaped block, split into two roads meeting at (0, 0) and
+        // (8, 10), the bay between the arms of the U lying outside
+        let east = polyline(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (8.0, 10.0)], 0);
+        let mut west = polyline(
+            &[
+                (8.0, 10.0),
+                (8.0, 2.0),
+                (2.0, 2.0),
+                (2.0, 10.0),
+                (0.0, 10.0),
+            ],
+            3,
+        );
+        west.push(east[0].clone());
+        let polylines = vec![east, west];
+
+        let points = densify(&polylines, 0.5);
+        // eight vertices, and one point less than twice its length on each of 8 segments
+        assert_eq!(points.len(), 8 + 2 * 56 - 8);
+        let ids: Vec<u64> = points.iter().map(|p| p.id).collect();
+        assert_eq!(ids, (0..points.len() as u64).collect::<Vec<_>>());
+        assert_eq!(densify(&polylines, 0.0).len(), 8);
+
+        let hull = concave_hull_polylines(&polylines, 0.5, 3, true).unwrap();
+        let prepared = PreparedHull::new(&hull);
+        assert!(points
+            .iter()
+            .all(|p| prepared.contains(p) || prepared.distance(p) < 1E-9));
+        let bay = Point {
+            x: 5.0,
+            y: 6.0,
+            id: 0,
+        };
+        assert!(!prepared.contains(&bay));
+        assert!(prepared.contains(&Point {
+            x: 1.0,
+            y: 5.0,
+            id: 0,
+        }));
+    }
+}
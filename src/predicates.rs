@@ -0,0 +1,626 @@
+/// Geometric Predicates
+///
+/// The tests the walk and the modules around it decide geometry by, each taking a
+/// [`Tolerance`] so that they agree on what counts as parallel, collinear or touching.
+use crate::metric::Tolerance;
+use crate::point::Point;
+use crate::stats::EdgeTests;
+
+/// Determines if two line segments intersect.
+///
+/// This function checks whether the line segment formed by points `a.0` and `a.1`
+/// intersects with the line segment formed by points `b.0` and `b.1`. It uses a
+/// geometric approach to calculate the intersection point and checks if this point
+/// lies within the bounds of both line segments.
+///
+/// # Parameters
+///
+/// * `a`: (&Point, &Point) - A tuple containing two references to `Point` objects,
+///   representing the first line segment.
+/// * `b`: (&Point, &Point) - A tuple containing two references to `Point` objects,
+///   representing the second line segment.
+///
+/// # Returns
+///
+/// * `bool` - `true` if the line segments intersect, `false` otherwise.
+///
+/// # Note
+///
+/// Segments are treated as parallel, so as not intersecting, below the default
+/// [`Tolerance`] of 1E-10; see [`intersects_within`] for another tolerance.
+pub fn intersects(a: (&Point, &Point), b: (&Point, &Point)) -> bool {
+    intersects_within(a, b, &Tolerance::default())
+}
+
+/// Determines if two line segments intersect, treating them as parallel below `tolerance`.
+pub fn intersects_within(a: (&Point, &Point), b: (&Point, &Point), tolerance: &Tolerance) -> bool {
+    let ax1 = a.0.x;
+    let ay1 = a.0.y;
+    let ax2 = a.1.x;
+    let ay2 = a.1.y;
+    let bx1 = b.0.x;
+    let by1 = b.0.y;
+    let bx2 = b.1.x;
+    let by2 = b.1.y;
+
+    let a1 = ay2 - ay1;
+    let b1 = ax1 - ax2;
+    let c1 = a1 * ax1 + b1 * ay1;
+    let a2 = by2 - by1;
+    let b2 = bx1 - bx2;
+    let c2 = a2 * bx1 + b2 * by1;
+    let det = a1 * b2 - a2 * b1;
+
+    if tolerance.is_zero(det, a1.hypot(b1) * a2.hypot(b2)) {
+        false
+    } else {
+        let x = (b2 * c1 - b1 * c2) / det;
+        let y = (a1 * c2 - a2 * c1) / det;
+
+        ax1.min(ax2) <= x
+            && (x <= ax1.max(ax2))
+            && (ay1.min(ay2) <= y)
+            && (y <= ay1.max(ay2))
+            && (bx1.min(bx2) <= x)
+            && (x <= bx1.max(bx2))
+            && (by1.min(by2) <= y)
+            && (y <= by1.max(by2))
+    }
+}
+
+/// The last edge of the polyline `path` that `segment` crosses, by [`intersects_within`].
+///
+/// Edge `i` runs from `path[i]` to `path[i + 1]`. This is the test of one candidate edge
+/// against the hull walked so far, which dominates the walk on dense datasets, so the edges
+/// are tested in batches: with the `simd` feature [`LANES`] at a time with `std::simd`,
+/// those it finds crossing confirmed one by one, so that the answer is always that of
+/// testing the edges one by one from the end.
+///
+/// # Arguments
+///
+/// * `segment` - the segment to test.
+/// * `path` - the vertices of the polyline, in order.
+/// * `tolerance` - below which segments count as parallel.
+///
+/// # Returns
+///
+/// Returns the index of the last edge crossed, `None` if there is none.
+pub fn intersects_many(
+    segment: (&Point, &Point),
+    path: &[Point],
+    tolerance: &Tolerance,
+) -> Option<usize> {
+    last_crossing(segment, path, tolerance, &mut EdgeTests::default())
+}
+
+/// the edges [`intersects_many`] tests at once with the `simd` feature
+pub const LANES: usize = 4;
+
+/// [`intersects_many`], counting in `tests` the edges it tested and those it skipped for
+/// an extent disjoint from that of `segment`, down to the one crossed
+pub(crate) fn last_crossing(
+    segment: (&Point, &Point),
+    path: &[Point],
+    tolerance: &Tolerance,
+    tests: &mut EdgeTests,
+) -> Option<usize> {
+    let edges = path.len().saturating_sub(1);
+    let reach = Extent::of(segment);
+    // whole batches from the end, the edges left over at the start one by one
+    #[cfg(feature = "simd")]
+    let end = {
+        let mut end = edges;
+        while end >= LANES {
+            end -= LANES;
+            let batch = &path[end..];
+            if let Some(edge) = simd::last_crossing(segment, &reach, batch, tolerance, tests) {
+                return Some(end + edge);
+            }
+        }
+        end
+    };
+    #[cfg(not(feature = "simd"))]
+    let end = edges;
+    (0..end).rev().find(|&e| {
+        let edge = (&path[e], &path[e + 1]);
+        crosses_edge(segment, &reach, edge, tolerance, tests)
+    })
+}
+
+/// Whether `segment`, of extent `reach`, crosses `edge`, counted in `tests`.
+fn crosses_edge(
+    segment: (&Point, &Point),
+    reach: &Extent,
+    edge: (&Point, &Point),
+    tolerance: &Tolerance,
+    tests: &mut EdgeTests,
+) -> bool {
+    // segments with disjoint extents cannot cross
+    if reach.overlaps(&Extent::of(edge)) {
+        tests.performed += 1;
+        intersects_within(segment, edge, tolerance)
+    } else {
+        tests.pruned += 1;
+        false
+    }
+}
+
+/// The axis-aligned extent of a segment.
+struct Extent {
+    min: (f64, f64),
+    max: (f64, f64),
+}
+
+impl Extent {
+    fn of(segment: (&Point, &Point)) -> Self {
+        let (a, b) = segment;
+        Extent {
+            min: (a.x.min(b.x), a.y.min(b.y)),
+            max: (a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    fn overlaps(&self, other: &Extent) -> bool {
+        self.min.0 <= other.max.0
+            && other.min.0 <= self.max.0
+            && self.min.1 <= other.max.1
+            && other.min.1 <= self.max.1
+    }
+}
+
+/// The vector test of [`LANES`] edges at once.
+///
+/// It repeats the arithmetic of [`intersects_within`] lane by lane, operation for
+/// operation, so that the intersection points and their bounds checks come out the same;
+/// only the parallel test is left to the scalar confirmation, since `hypot` has no vector
+/// counterpart that rounds like it.
+#[cfg(feature = "simd")]
+mod simd {
+    use std::simd::prelude::*;
+
+    use super::{intersects_within, Extent, LANES};
+    use crate::metric::Tolerance;
+    use crate::point::Point;
+    use crate::stats::EdgeTests;
+
+    type Lanes = Simd<f64, LANES>;
+
+    /// the last of the first [`LANES`] edges of `path` that `segment` crosses, counted as
+    /// [`super::last_crossing`] counts them
+    pub(super) fn last_crossing(
+        segment: (&Point, &Point),
+        reach: &Extent,
+        path: &[Point],
+        tolerance: &Tolerance,
+        tests: &mut EdgeTests,
+    ) -> Option<usize> {
+        let lanes = |f: fn(&Point) -> f64, offset: usize| {
+            Lanes::from_array(std::array::from_fn(|l| f(&path[l + offset])))
+        };
+        let (bx1, by1) = (lanes(|p| p.x, 0), lanes(|p| p.y, 0));
+        let (bx2, by2) = (lanes(|p| p.x, 1), lanes(|p| p.y, 1));
+
+        let overlaps = Lanes::splat(reach.min.0).simd_le(bx1.simd_max(bx2))
+            & bx1.simd_min(bx2).simd_le(Lanes::splat(reach.max.0))
+            & Lanes::splat(reach.min.1).simd_le(by1.simd_max(by2))
+            & by1.simd_min(by2).simd_le(Lanes::splat(reach.max.1));
+
+        let (ax1, ay1, ax2, ay2) = (segment.0.x, segment.0.y, segment.1.x, segment.1.y);
+        let a1 = ay2 - ay1;
+        let b1 = ax1 - ax2;
+        let c1 = a1 * ax1 + b1 * ay1;
+        let (a1, b1, c1) = (Lanes::splat(a1), Lanes::splat(b1), Lanes::splat(c1));
+        let a2 = by2 - by1;
+        let b2 = bx1 - bx2;
+        let c2 = a2 * bx1 + b2 * by1;
+        let det = a1 * b2 - a2 * b1;
+        let x = (b2 * c1 - b1 * c2) / det;
+        let y = (a1 * c2 - a2 * c1) / det;
+
+        let within = |v: Lanes, p: f64, q: f64| {
+            Lanes::splat(p.min(q)).simd_le(v) & v.simd_le(Lanes::splat(p.max(q)))
+        };
+        let crossing = overlaps
+            & within(x, ax1, ax2)
+            & within(y, ay1, ay2)
+            & bx1.simd_min(bx2).simd_le(x)
+            & x.simd_le(bx1.simd_max(bx2))
+            & by1.simd_min(by2).simd_le(y)
+            & y.simd_le(by1.simd_max(by2));
+
+        let (overlaps, mut crossing) = (overlaps.to_bitmask(), crossing.to_bitmask());
+        let mut scanned = (1u64 << LANES) - 1;
+        let found = loop {
+            if crossing == 0 {
+                break None;
+            }
+            let edge = 63 - crossing.leading_zeros() as usize;
+            crossing &= !(1 << edge);
+            if intersects_within(segment, (&path[edge], &path[edge + 1]), tolerance) {
+                // the edges below the crossing are not reached
+                scanned &= !((1 << edge) - 1);
+                break Some(edge);
+            }
+        };
+        let performed = (overlaps & scanned).count_ones() as usize;
+        tests.performed += performed;
+        tests.pruned += scanned.count_ones() as usize - performed;
+        found
+    }
+}
+
+/// Which way a path turns at a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    /// counterclockwise
+    Left,
+    /// clockwise
+    Right,
+    /// straight on, or back, within the tolerance
+    Collinear,
+}
+
+/// Which way the path `a`, `b`, `c` turns at `b`.
+///
+/// The sign of the cross product of `b - a` and `c - a` decides, counted as zero within
+/// `tolerance` of the product of the two lengths, the same threshold [`intersects_within`]
+/// takes segments as parallel by.
+///
+/// # Arguments
+///
+/// * `a` - where the path starts.
+/// * `b` - where it turns.
+/// * `c` - where it ends.
+/// * `tolerance` - below which the three points count as collinear.
+///
+/// # Returns
+///
+/// Returns [`Turn::Left`] for a counterclockwise turn, [`Turn::Right`] for a clockwise one
+/// and [`Turn::Collinear`] otherwise, also when two of the points coincide.
+pub fn orientation(a: &Point, b: &Point, c: &Point, tolerance: &Tolerance) -> Turn {
+    let (ux, uy) = (b.x - a.x, b.y - a.y);
+    let (vx, vy) = (c.x - a.x, c.y - a.y);
+    let cross = ux * vy - uy * vx;
+    if tolerance.is_zero(cross, ux.hypot(uy) * vx.hypot(vy)) {
+        Turn::Collinear
+    } else if cross > 0.0 {
+        Turn::Left
+    } else {
+        Turn::Right
+    }
+}
+
+/// Which way the path `a`, `b`, `c` turns at `b`, decided exactly.
+///
+/// The cross product is expanded into products of the coordinates, each split exactly into
+/// a rounded value and its error, and summed without rounding, so its sign is that of the
+/// exact product; only overflow and underflow of the coordinates can spoil it.
+/// [`Turn::Collinear`] means exactly on one line.
+pub fn orientation_exact(a: &Point, b: &Point, c: &Point) -> Turn {
+    let products = [
+        (b.x, c.y),
+        (-b.x, a.y),
+        (-a.x, c.y),
+        (-b.y, c.x),
+        (b.y, a.x),
+        (a.y, c.x),
+    ];
+    // the exact sum as non-overlapping components of increasing magnitude
+    let mut expansion = [0.0f64; 12];
+    let mut len = 0;
+    for (x, y) in products {
+        let product = x * y;
+        for mut term in [product, x.mul_add(y, -product)] {
+            for component in expansion[..len].iter_mut() {
+                let (sum, error) = two_sum(term, *component);
+                *component = error;
+                term = sum;
+            }
+            expansion[len] = term;
+            len += 1;
+        }
+    }
+    match expansion[..len].iter().rev().find(|&&c| c != 0.0) {
+        Some(&c) if c > 0.0 => Turn::Left,
+        Some(_) => Turn::Right,
+        None => Turn::Collinear,
+    }
+}
+
+/// `a + b` and its rounding error
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_rounded = sum - a;
+    let a_rounded = sum - b_rounded;
+    (sum, (a - a_rounded) + (b - b_rounded))
+}
+
+/// Whether `a`, `b` and `c` lie on one line, see [`orientation`].
+pub fn collinear(a: &Point, b: &Point, c: &Point, tolerance: &Tolerance) -> bool {
+    orientation(a, b, c, tolerance) == Turn::Collinear
+}
+
+/// Whether `point` lies on the segment `segment`: within `tolerance` of it, taking the
+/// threshold in coordinate units and relative to the segment length.
+pub fn point_on_segment(point: &Point, segment: (&Point, &Point), tolerance: &Tolerance) -> bool {
+    let (a, b) = segment;
+    let length = (b.x - a.x).hypot(b.y - a.y);
+    crate::prepared::segment_distance(point, a, b)
+        <= tolerance.absolute.max(tolerance.relative * length)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn test_intersects() {
+        let mut values = HashMap::new();
+        values.insert(
+            'A',
+            Point {
+                x: 0.0,
+                y: 0.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'B',
+            Point {
+                x: -1.5,
+                y: 3.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'C',
+            Point {
+                x: 2.0,
+                y: 2.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'D',
+            Point {
+                x: -2.0,
+                y: 1.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'E',
+            Point {
+                x: -2.5,
+                y: 5.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'F',
+            Point {
+                x: -1.5,
+                y: 7.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'G',
+            Point {
+                x: 1.0,
+                y: 9.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'H',
+            Point {
+                x: -4.0,
+                y: 7.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'I',
+            Point {
+                x: 3.0,
+                y: 10.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'J',
+            Point {
+                x: 2.0,
+                y: 11.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'K',
+            Point {
+                x: -1.0,
+                y: 11.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'L',
+            Point {
+                x: -3.0,
+                y: 11.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'M',
+            Point {
+                x: -5.0,
+                y: 9.5,
+                id: 0,
+            },
+        );
+        values.insert(
+            'N',
+            Point {
+                x: -6.0,
+                y: 7.5,
+                id: 0,
+            },
+        );
+        values.insert(
+            'O',
+            Point {
+                x: -6.0,
+                y: 4.0,
+                id: 0,
+            },
+        );
+        values.insert(
+            'P',
+            Point {
+                x: -5.0,
+                y: 2.0,
+                id: 0,
+            },
+        );
+
+        let test = |a1: char, a2: char, b1: char, b2: char, expected: bool| {
+            let line1 = (&values[&a1], &values[&a2]);
+            let line2 = (&values[&b1], &values[&b2]);
+            assert!(intersects(line1, line2) == expected);
+        };
+
+        test('B', 'D', 'A', 'C', false);
+        test('A', 'B', 'C', 'D', true);
+        test('L', 'K', 'H', 'F', false);
+        test('E', 'C', 'F', 'B', true);
+        test('P', 'C', 'E', 'B', false);
+        test('P', 'C', 'A', 'B', true);
+        test('O', 'E', 'C', 'F', false);
+        test('L', 'C', 'M', 'N', false);
+        test('L', 'C', 'N', 'B', false);
+        test('L', 'C', 'M', 'K', true);
+        test('L', 'C', 'G', 'I', false);
+        test('L', 'C', 'I', 'E', true);
+        test('M', 'O', 'N', 'F', true);
+    }
+
+    #[test]
+    fn test_intersects_function() {
+        test_intersects();
+    }
+
+    #[test]
+    fn test_intersects_within() {
+        // a cross a micro-unit wide, whose cross product is below the default tolerance
+        let point = |x: f64, y: f64| Point { x, y, id: 0 };
+        let (a, b) = (point(0.0, 0.0), point(1E-6, 1E-6));
+        let (c, d) = (point(0.0, 1E-6), point(1E-6, 0.0));
+        assert!(!intersects((&a, &b), (&c, &d)));
+        assert!(intersects_within(
+            (&a, &b),
+            (&c, &d),
+            &Tolerance::relative(1E-9)
+        ));
+        assert!(!intersects_within(
+            (&a, &b),
+            (&a, &b),
+            &Tolerance::relative(1E-9)
+        ));
+    }
+
+    #[test]
+    fn test_intersects_many() {
+        let mut rng = crate::synthetic::Rng::new(8);
+        let mut point = || Point {
+            x: rng.uniform(),
+            y: rng.uniform(),
+            id: 0,
+        };
+        let path: Vec<Point> = (0..103).map(|_| point()).collect();
+        let tolerance = Tolerance::default();
+        for _ in 0..200 {
+            let (a, b) = (point(), point());
+            // one edge at a time from the end, as the walk tested them before batching
+            let (reach, mut expected_tests) = (Extent::of((&a, &b)), EdgeTests::default());
+            let expected = (0..path.len() - 1).rev().find(|&e| {
+                let edge = (&path[e], &path[e + 1]);
+                crosses_edge((&a, &b), &reach, edge, &tolerance, &mut expected_tests)
+            });
+            let mut tests = EdgeTests::default();
+            assert_eq!(
+                last_crossing((&a, &b), &path, &tolerance, &mut tests),
+                expected
+            );
+            assert_eq!(
+                (tests.performed, tests.pruned),
+                (expected_tests.performed, expected_tests.pruned)
+            );
+            let found = intersects_many((&a, &b), &path, &tolerance);
+            assert_eq!(found, expected);
+            if let Some(e) = found {
+                assert!(intersects((&a, &b), (&path[e], &path[e + 1])));
+            }
+        }
+        assert_eq!(
+            intersects_many((&path[0], &path[1]), &path[..1], &tolerance),
+            None
+        );
+    }
+
+    #[test]
+    fn test_orientation() {
+        let point = |x: f64, y: f64| Point { x, y, id: 0 };
+        let tolerance = Tolerance::default();
+        let (a, b) = (point(0.0, 0.0), point(2.0, 0.0));
+        assert_eq!(
+            orientation(&a, &b, &point(1.0, 1.0), &tolerance),
+            Turn::Left
+        );
+        assert_eq!(
+            orientation(&a, &b, &point(1.0, -1.0), &tolerance),
+            Turn::Right
+        );
+        assert_eq!(
+            orientation(&a, &b, &point(3.0, 0.0), &tolerance),
+            Turn::Collinear
+        );
+        assert_eq!(orientation(&a, &a, &b, &tolerance), Turn::Collinear);
+
+        // a kink far below unit size, straight only to an absolute tolerance
+        let c = point(4.0, 1E-12);
+        assert!(collinear(&a, &b, &c, &tolerance));
+        assert!(!collinear(&a, &b, &c, &Tolerance::relative(1E-15)));
+
+        assert!(point_on_segment(&point(1.0, 0.0), (&a, &b), &tolerance));
+        assert!(point_on_segment(&point(2.0, 0.0), (&a, &b), &tolerance));
+        assert!(!point_on_segment(&point(3.0, 0.0), (&a, &b), &tolerance));
+        assert!(!point_on_segment(&point(1.0, 1E-3), (&a, &b), &tolerance));
+        assert!(point_on_segment(
+            &point(1.0, 1E-3),
+            (&a, &b),
+            &Tolerance::relative(1E-3)
+        ));
+
+        // exactly, with no tolerance, down to a unit in the last place
+        assert_eq!(orientation_exact(&a, &b, &c), Turn::Left);
+        assert_eq!(
+            orientation_exact(&a, &b, &point(-1.0, 0.0)),
+            Turn::Collinear
+        );
+        let (p, q) = (point(0.5, 0.5), point(12.0, 12.0));
+        assert_eq!(
+            orientation_exact(&p, &q, &point(24.0, 24.0)),
+            Turn::Collinear
+        );
+        let r = point(24.0, 24f64.next_up());
+        assert_eq!(orientation_exact(&p, &q, &r), Turn::Left);
+        let s = point(1E-20, 1E20);
+        assert_eq!(
+            orientation_exact(&s, &point(0.0, 0.0), &point(-1E-20, -1E20)),
+            Turn::Collinear
+        );
+    }
+}
@@ -0,0 +1,274 @@
+/// Prepared Hull for Repeated Queries
+use crate::metric::Tolerance;
+use crate::point::Point;
+use crate::polygon::Containment;
+
+/// A hull ring with a spatial index over its edges.
+///
+/// Preparing a hull costs O(n) in its vertex count. Afterwards `contains` only tests the
+/// edges spanning the query's horizontal band and `distance` only visits the grid cells
+/// around the query, so membership tests against a fixed hull stay cheap.
+#[derive(Clone)]
+pub struct PreparedHull {
+    /// hull vertices, without a repeated closing vertex
+    ring: Vec<Point>,
+    min_x: f64,
+    min_y: f64,
+    cell_w: f64,
+    cell_h: f64,
+    nx: usize,
+    ny: usize,
+    /// edges spanning each horizontal band (used for containment)
+    bands: Vec<Vec<usize>>,
+    /// edges overlapping each grid cell (used for distance)
+    cells: Vec<Vec<usize>>,
+    /// edges whose rise is within it of zero are skipped by containment
+    tolerance: Tolerance,
+    /// the point-in-polygon test of `contains`
+    containment: Containment,
+}
+
+impl PreparedHull {
+    /// Builds the edge index for `hull`.
+    ///
+    /// The hull may be given as an open ring or closed (first vertex repeated at the end),
+    /// as returned by [`crate::concave_hull`].
+    pub fn new(hull: &[Point]) -> Self {
+        let ring = crate::open_ring(hull).to_vec();
+
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for p in ring.iter() {
+            min_x = min_x.min(p.x);
+            min_y = min_y.min(p.y);
+            max_x = max_x.max(p.x);
+            max_y = max_y.max(p.y);
+        }
+
+        // roughly one edge per cell, and per band a square root of the edges
+        let side = ((ring.len() as f64).sqrt().ceil() as usize).max(1);
+        let (nx, ny) = (side, side);
+        let cell_w = ((max_x - min_x) / nx as f64).max(f64::MIN_POSITIVE);
+        let cell_h = ((max_y - min_y) / ny as f64).max(f64::MIN_POSITIVE);
+
+        let mut prepared = PreparedHull {
+            ring,
+            min_x,
+            min_y,
+            cell_w,
+            cell_h,
+            nx,
+            ny,
+            bands: vec![Vec::new(); ny],
+            cells: vec![Vec::new(); nx * ny],
+            tolerance: Tolerance::default(),
+            containment: Containment::default(),
+        };
+
+        for e in 0..prepared.edge_count() {
+            let (a, b) = prepared.edge(e);
+            let (r0, r1) = (prepared.row(a.y.min(b.y)), prepared.row(a.y.max(b.y)));
+            let (c0, c1) = (prepared.col(a.x.min(b.x)), prepared.col(a.x.max(b.x)));
+            for r in r0..=r1 {
+                prepared.bands[r].push(e);
+                for c in c0..=c1 {
+                    prepared.cells[r * nx + c].push(e);
+                }
+            }
+        }
+
+        prepared
+    }
+
+    /// Sets the tolerance of [`PreparedHull::contains`], the default [`Tolerance`] unless set.
+    pub fn with_tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the test of [`PreparedHull::contains`], the crossing number unless set.
+    pub fn with_containment(mut self, containment: Containment) -> Self {
+        self.containment = containment;
+        self
+    }
+
+    /// The hull vertices, without a repeated closing vertex.
+    pub fn ring(&self) -> &[Point] {
+        &self.ring
+    }
+
+    /// Tests whether `point` lies inside the hull by its [`Containment`] test.
+    pub fn contains(&self, point: &Point) -> bool {
+        if self.ring.len() <= 2 || point.y < self.min_y || point.x < self.min_x {
+            return false;
+        }
+        let row = ((point.y - self.min_y) / self.cell_h).floor() as usize;
+        if row >= self.ny {
+            return false;
+        }
+
+        let winding: i32 = self.bands[row]
+            .iter()
+            .map(|&e| {
+                let (v0, v1) = self.edge(e);
+                self.containment.crossing(point, v0, v1, &self.tolerance)
+            })
+            .sum();
+        self.containment.inside(winding)
+    }
+
+    /// Euclidean distance from `point` to the hull boundary.
+    ///
+    /// The distance is unsigned; combine it with [`PreparedHull::contains`] for a signed
+    /// distance. An empty hull is infinitely far away.
+    pub fn distance(&self, point: &Point) -> f64 {
+        if self.ring.is_empty() {
+            return f64::INFINITY;
+        }
+        if self.ring.len() == 1 {
+            return (point.x - self.ring[0].x).hypot(point.y - self.ring[0].y);
+        }
+
+        // points outside the grid search from their projection onto it, which never
+        // brings unvisited cells closer than they really are
+        let (cr, cc) = (self.row(point.y), self.col(point.x));
+        let step = self.cell_w.min(self.cell_h);
+        let mut best = f64::INFINITY;
+
+        for r in 0..=self.nx.max(self.ny) {
+            let (r0, r1) = (cr.saturating_sub(r), (cr + r).min(self.ny - 1));
+            let (c0, c1) = (cc.saturating_sub(r), (cc + r).min(self.nx - 1));
+            for row in r0..=r1 {
+                for col in c0..=c1 {
+                    // only the cells on the ring at Chebyshev distance r are new
+                    if row.abs_diff(cr) != r && col.abs_diff(cc) != r {
+                        continue;
+                    }
+                    for &e in self.cells[row * self.nx + col].iter() {
+                        let (a, b) = self.edge(e);
+                        best = best.min(segment_distance(point, a, b));
+                    }
+                }
+            }
+            if best <= r as f64 * step {
+                break;
+            }
+        }
+
+        best
+    }
+
+    fn edge_count(&self) -> usize {
+        match self.ring.len() {
+            0 | 1 => 0,
+            2 => 1,
+            n => n,
+        }
+    }
+
+    fn edge(&self, e: usize) -> (&Point, &Point) {
+        (&self.ring[e], &self.ring[(e + 1) % self.ring.len()])
+    }
+
+    fn row(&self, y: f64) -> usize {
+        (((y - self.min_y) / self.cell_h).floor().max(0.0) as usize).min(self.ny - 1)
+    }
+
+    fn col(&self, x: f64) -> usize {
+        (((x - self.min_x) / self.cell_w).floor().max(0.0) as usize).min(self.nx - 1)
+    }
+}
+
+/// Keeps the points within `width` of the boundary of `hull`, inside or outside.
+///
+/// Interior points far from the boundary never become hull vertices, so a tighter hull
+/// recomputed from the band alone follows the same boundary, and an archived band keeps
+/// the outline of the data at a fraction of its size.
+///
+/// # Arguments
+///
+/// * `points` - the point cloud.
+/// * `hull` - the hull, open or closed.
+/// * `width` - the largest distance from the boundary of a kept point.
+///
+/// # Returns
+///
+/// Returns the points in the band, in their original order.
+pub fn boundary_band(points: &[Point], hull: &[Point], width: f64) -> Vec<Point> {
+    let prepared = PreparedHull::new(hull);
+    points
+        .iter()
+        .filter(|p| prepared.distance(p) <= width)
+        .cloned()
+        .collect()
+}
+
+/// Distance from `p` to the segment `a`-`b`.
+pub(crate) fn segment_distance(p: &Point, a: &Point, b: &Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 {
+        (((p.x - a.x) * dx + (p.y - a.y) * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (p.x - (a.x + t * dx)).hypot(p.y - (a.y + t * dy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: f64, y: f64) -> Point {
+        Point { x, y, id: 0 }
+    }
+
+    #[test]
+    fn test_prepared_hull() {
+        // an L-shaped ring, closed like the output of concave_hull
+        let hull = vec![
+            point(0.0, 0.0),
+            point(2.0, 0.0),
+            point(2.0, 1.0),
+            point(1.0, 1.0),
+            point(1.0, 2.0),
+            point(0.0, 2.0),
+            point(0.0, 0.0),
+        ];
+        let prepared = PreparedHull::new(&hull);
+        assert_eq!(prepared.ring().len(), 6);
+
+        assert!(prepared.contains(&point(0.5, 0.5)));
+        assert!(prepared.contains(&point(1.5, 0.5)));
+        assert!(!prepared.contains(&point(1.5, 1.5)));
+        assert!(!prepared.contains(&point(-1.0, 0.5)));
+
+        assert!((prepared.distance(&point(1.5, 1.5)) - 0.5).abs() < 1E-12);
+        assert!((prepared.distance(&point(4.0, 0.5)) - 2.0).abs() < 1E-12);
+        assert!((prepared.distance(&point(0.5, 0.25)) - 0.25).abs() < 1E-12);
+
+        // agree with the unprepared crossing test and a brute-force distance
+        for i in 0..30 {
+            for j in 0..30 {
+                let q = point(i as f64 * 0.1 - 0.45, j as f64 * 0.1 - 0.45);
+                assert_eq!(
+                    prepared.contains(&q),
+                    crate::polygon::point_in_polygon(&q, &hull)
+                );
+                let brute = (0..6)
+                    .map(|e| segment_distance(&q, &hull[e], &hull[e + 1]))
+                    .fold(f64::INFINITY, f64::min);
+                assert!((prepared.distance(&q) - brute).abs() < 1E-12);
+            }
+        }
+
+        let grid: Vec<Point> = (0..=20)
+            .flat_map(|i| (0..=20).map(move |j| point(i as f64 * 0.1, j as f64 * 0.1)))
+            .collect();
+        let band = boundary_band(&grid, &hull, 0.15);
+        assert!(band.iter().all(|p| prepared.distance(p) <= 0.15));
+        assert!(!band.contains(&point(0.5, 0.5)));
+        assert!(band.contains(&point(0.1, 0.5)) && band.contains(&point(1.5, 1.1)));
+        assert!(boundary_band(&grid, &[], 1.0).is_empty());
+    }
+}
@@ -0,0 +1,442 @@
+/// Recipes for Common GIS Tasks
+///
+/// Small functions for the workflows the crate is most often used for, each composed from
+/// the building blocks elsewhere in the crate, to call directly or to copy and adapt:
+///
+/// * [`track_footprint`] - the area covered along a GPS track.
+/// * [`cluster_outlines`] - one outline per cluster of detections.
+/// * [`mask_outline`] - the outline of a raster mask.
+/// * [`read_shapefile_points`] and [`write_shapefile`] - shapefile input and output.
+///
+/// ```
+/// use concave_hull::recipes::{read_shapefile_points, write_shapefile};
+///
+/// let points = read_shapefile_points(Path::new("sightings.shp"))?;
+/// let hull = concave_hull_borrowed(&points, 5, true)?;
+/// write_shapefile(Path::new("range"), &hull)?;
+/// ```
+use crate::error::ConcaveHullError;
+use crate::io::{HullSink, PointSource};
+use crate::multi::MultiHull;
+use crate::point::Point;
+use crate::Orientation;
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// samples on the circle of half the width around every point of a track
+const FOOTPRINT_SAMPLES: usize = 8;
+
+/// Computes the footprint of a GPS track: the area within about `width / 2` of it.
+///
+/// The track is densified to fixes no further than `width / 2` apart, each fix is replaced
+/// by points on a circle of radius `width / 2` around it, and the concave hull of those is
+/// the corridor swept along the track.
+///
+/// # Arguments
+///
+/// * `track` - the fixes in order.
+/// * `width` - the width of the corridor, in coordinate units.
+/// * `k` - number of nearest neighbors.
+///
+/// # Returns
+///
+/// Returns the footprint as [`crate::concave_hull`] does, its vertices numbered in sample
+/// order, or the error of [`crate::concave_hull_borrowed`].
+pub fn track_footprint(
+    track: &[Point],
+    width: f64,
+    k: usize,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    let radius = width / 2.0;
+    // densify tells vertices apart by id, which fixes needn't have
+    let track: Vec<Point> = track
+        .iter()
+        .enumerate()
+        .map(|(id, p)| Point {
+            id: id as u64,
+            ..p.clone()
+        })
+        .collect();
+    let fixes = crate::polyline::densify(&[track], radius);
+    let samples: Vec<Point> = fixes
+        .iter()
+        .flat_map(|fix| {
+            (0..FOOTPRINT_SAMPLES).map(move |i| {
+                let t = 2.0 * std::f64::consts::PI * i as f64 / FOOTPRINT_SAMPLES as f64;
+                (fix.x + radius * t.cos(), fix.y + radius * t.sin())
+            })
+        })
+        .enumerate()
+        .map(|(id, (x, y))| Point {
+            x,
+            y,
+            id: id as u64,
+        })
+        .collect();
+    crate::concave_hull_borrowed(&samples, k, true)
+}
+
+/// Computes an outline around each cluster of detections.
+///
+/// Detections closer than `radius` belong to the same cluster (single linkage). Clusters
+/// of fewer than `min_points` are dropped as noise; the others get their concave hull, or
+/// their convex hull if they are too small for one.
+///
+/// # Arguments
+///
+/// * `detections` - the detections, e.g. object positions from consecutive frames.
+/// * `radius` - the largest gap within a cluster.
+/// * `min_points` - the fewest detections of a cluster.
+/// * `k` - number of nearest neighbors.
+///
+/// # Returns
+///
+/// Returns one polygon per cluster, counter-clockwise, largest cluster first.
+pub fn cluster_outlines(
+    detections: &[Point],
+    radius: f64,
+    min_points: usize,
+    k: usize,
+) -> MultiHull {
+    let mut polygons: Vec<(usize, Vec<Point>)> = clusters(detections, radius)
+        .into_iter()
+        .filter(|cluster| cluster.len() >= min_points.max(1))
+        .map(|cluster| {
+            let points: Vec<Point> = cluster.iter().map(|&i| detections[i].clone()).collect();
+            let hull = crate::concave_hull_borrowed(&points, k, true)
+                .unwrap_or_else(|_| crate::convex::convex_hull(&points));
+            (
+                cluster.len(),
+                crate::orient(hull, Orientation::CounterClockwise),
+            )
+        })
+        .collect();
+    polygons.sort_by_key(|(size, _)| std::cmp::Reverse(*size));
+    MultiHull::from_rings(polygons.into_iter().map(|(_, hull)| hull).collect())
+}
+
+/// The positions of the points in each single-linkage cluster at distance `radius`.
+fn clusters(points: &[Point], radius: f64) -> Vec<Vec<usize>> {
+    let radius = radius.max(f64::MIN_POSITIVE);
+    let cell = |p: &Point| ((p.x / radius).floor() as i64, (p.y / radius).floor() as i64);
+    let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (i, p) in points.iter().enumerate() {
+        grid.entry(cell(p)).or_default().push(i);
+    }
+
+    let mut cluster_of = vec![usize::MAX; points.len()];
+    let mut clusters = Vec::new();
+    for start in 0..points.len() {
+        if cluster_of[start] != usize::MAX {
+            continue;
+        }
+        cluster_of[start] = clusters.len();
+        let mut members = vec![start];
+        let mut next = 0;
+        while next < members.len() {
+            let p = &points[members[next]];
+            let (ci, cj) = cell(p);
+            for di in -1..=1 {
+                for dj in -1..=1 {
+                    for &j in grid.get(&(ci + di, cj + dj)).into_iter().flatten() {
+                        let q = &points[j];
+                        if cluster_of[j] == usize::MAX && (q.x - p.x).hypot(q.y - p.y) < radius {
+                            cluster_of[j] = clusters.len();
+                            members.push(j);
+                        }
+                    }
+                }
+            }
+            next += 1;
+        }
+        clusters.push(members);
+    }
+    clusters
+}
+
+/// Traces the outline of a raster mask.
+///
+/// # Arguments
+///
+/// * `mask` - the pixels row by row from the top left, `true` where the feature is.
+/// * `width`, `height` - pixels per row and number of rows.
+///
+/// # Returns
+///
+/// Returns the outline of the largest connected region as [`crate::grid::grid_hull`] does,
+/// in pixel corner coordinates with y counting rows down from the top. Each vertex carries
+/// the index of a pixel of the region beside it.
+///
+/// # Panics
+///
+/// Panics if `mask` doesn't have `width * height` pixels.
+pub fn mask_outline(mask: &[bool], width: usize, height: usize) -> Vec<Point> {
+    assert_eq!(
+        mask.len(),
+        width * height,
+        "the mask must have width * height pixels"
+    );
+    // the grid runs bottom up
+    let mut count = vec![0usize; mask.len()];
+    let mut ids = vec![0u64; mask.len()];
+    for (index, _) in mask.iter().enumerate().filter(|(_, &set)| set) {
+        let (row, column) = (index / width, index % width);
+        let cell = (height - 1 - row) * width + column;
+        count[cell] = 1;
+        ids[cell] = index as u64;
+    }
+    crate::grid::trace(&count, &mut ids, width, height, (0.0, 0.0), 1.0)
+        .into_iter()
+        .map(|p| Point {
+            y: height as f64 - p.y,
+            ..p
+        })
+        .collect()
+}
+
+/// Reads the points of an ESRI shapefile (`.shp`).
+///
+/// Point and multipoint shapes give their points, polyline and polygon shapes their
+/// vertices; Z and M values are ignored. Only the `.shp` file is read, so attributes in the
+/// `.dbf` file are left to the caller, joined on the record number.
+///
+/// # Arguments
+///
+/// * `source` - the `.shp` file, or its contents.
+///
+/// # Returns
+///
+/// Returns the points in file order, with their record number (from 1) as id, or an
+/// `InvalidData` error for a file that is not a shapefile.
+pub fn read_shapefile_points(source: impl PointSource) -> io::Result<Vec<Point>> {
+    let mut bytes = Vec::new();
+    source.open()?.read_to_end(&mut bytes)?;
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    if bytes.len() < 100 || be_i32(&bytes, 0) != 9994 {
+        return Err(invalid("not a shapefile"));
+    }
+
+    let mut points = Vec::new();
+    let mut offset = 100;
+    while offset + 8 <= bytes.len() {
+        let record = be_i32(&bytes, offset) as u64;
+        let truncated = || invalid("truncated shapefile record");
+        let span = byte_range(offset + 8, be_i32(&bytes, offset + 4), 2).ok_or_else(truncated)?;
+        offset = span.end;
+        let content = bytes.get(span).ok_or_else(truncated)?;
+        let coordinates = match le_i32(content, 0) {
+            0 => Some(0..0),
+            1 | 11 | 21 => Some(4..20),
+            8 | 18 | 28 if content.len() >= 40 => byte_range(40, le_i32(content, 36), 16),
+            3 | 5 | 13 | 15 | 23 | 25 if content.len() >= 44 => {
+                byte_range(44, le_i32(content, 36), 4)
+                    .and_then(|parts| byte_range(parts.end, le_i32(content, 40), 16))
+            }
+            _ => return Err(invalid("unsupported shape type")),
+        };
+        let coordinates = coordinates
+            .and_then(|range| content.get(range))
+            .ok_or_else(truncated)?;
+        points.extend(coordinates.chunks_exact(16).map(|xy| Point {
+            x: le_f64(xy, 0),
+            y: le_f64(xy, 8),
+            id: record,
+        }));
+    }
+    Ok(points)
+}
+
+/// the bytes of `count` items of `size` bytes from `start` on, or `None` for a negative
+/// count or a range past the address space
+fn byte_range(start: usize, count: i32, size: usize) -> Option<std::ops::Range<usize>> {
+    let length = usize::try_from(count).ok()?.checked_mul(size)?;
+    Some(start..start.checked_add(length)?)
+}
+
+/// Writes a hull as a polygon shapefile.
+///
+/// The `.shp`, `.shx` and `.dbf` files are written next to each other, with one polygon
+/// record whose only attribute is `ID` 1. The ring is written clockwise and closed, as the
+/// format requires of outer rings.
+///
+/// # Arguments
+///
+/// * `base` - the path of the files without extension, e.g. `out/range`.
+/// * `hull` - the hull, open or closed.
+///
+/// # Returns
+///
+/// Returns the error of the first file that could not be written.
+pub fn write_shapefile(base: &Path, hull: &[Point]) -> io::Result<()> {
+    let ring = crate::close_ring(crate::orient(hull.to_vec(), Orientation::Clockwise));
+    let bbox = crate::point::BoundingBox::from_points(&ring);
+    let bounds = [bbox.min_x, bbox.min_y, bbox.max_x, bbox.max_y];
+
+    let mut content = Vec::with_capacity(48 + 16 * ring.len());
+    content.extend(5i32.to_le_bytes());
+    content.extend(bounds.iter().flat_map(|v| v.to_le_bytes()));
+    content.extend(1i32.to_le_bytes());
+    content.extend((ring.len() as i32).to_le_bytes());
+    content.extend(0i32.to_le_bytes());
+    content.extend(
+        ring.iter()
+            .flat_map(|p| [p.x, p.y])
+            .flat_map(f64::to_le_bytes),
+    );
+
+    let words = |bytes: usize| (bytes / 2) as i32;
+    let mut shp = header(words(100 + 8 + content.len()), &bounds);
+    shp.extend(1i32.to_be_bytes());
+    shp.extend(words(content.len()).to_be_bytes());
+    shp.extend(&content);
+    let mut shx = header(words(100 + 8), &bounds);
+    shx.extend(words(100).to_be_bytes());
+    shx.extend(words(content.len()).to_be_bytes());
+
+    // one numeric field `ID` of 10 digits
+    let mut dbf = vec![0x03, 124, 1, 1];
+    dbf.extend(1u32.to_le_bytes());
+    dbf.extend(65u16.to_le_bytes());
+    dbf.extend(11u16.to_le_bytes());
+    dbf.extend([0u8; 20]);
+    let mut field = [0u8; 32];
+    field[..2].copy_from_slice(b"ID");
+    field[11] = b'N';
+    field[16] = 10;
+    dbf.extend(field);
+    dbf.push(0x0D);
+    dbf.extend(b"          1");
+    dbf.push(0x1A);
+
+    for (extension, bytes) in [("shp", shp), ("shx", shx), ("dbf", dbf)] {
+        let mut writer = base.with_extension(extension).create()?;
+        writer.write_all(&bytes)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// the 100 byte header of a polygon `.shp` or `.shx` file of `words` 16-bit words
+fn header(words: i32, bounds: &[f64; 4]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(100);
+    header.extend(9994i32.to_be_bytes());
+    header.extend([0u8; 20]);
+    header.extend(words.to_be_bytes());
+    header.extend(1000i32.to_le_bytes());
+    header.extend(5i32.to_le_bytes());
+    header.extend(bounds.iter().flat_map(|v| v.to_le_bytes()));
+    header.extend([0u8; 32]);
+    header
+}
+
+fn be_i32(bytes: &[u8], at: usize) -> i32 {
+    i32::from_be_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn le_i32(bytes: &[u8], at: usize) -> i32 {
+    bytes
+        .get(at..at + 4)
+        .map_or(-1, |b| i32::from_le_bytes(b.try_into().unwrap()))
+}
+
+fn le_f64(bytes: &[u8], at: usize) -> f64 {
+    f64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prepared::PreparedHull;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_recipes() {
+        let point = |x: f64, y: f64| Point { x, y, id: 0 };
+
+        // an L-shaped track, 20 units each way
+        let track = vec![point(0.0, 0.0), point(20.0, 0.0), point(20.0, 20.0)];
+        let footprint = PreparedHull::new(&track_footprint(&track, 2.0, 5).unwrap());
+        assert!(footprint.contains(&point(10.0, 0.5)));
+        assert!(footprint.contains(&point(20.5, 10.0)));
+        assert!(!footprint.contains(&point(10.0, 10.0)));
+
+        // two blobs of detections and a stray one
+        let mut rng = Rng::new(6);
+        let mut detections: Vec<Point> = (0..300)
+            .map(|i| {
+                let centre = if i < 200 { 0.0 } else { 10.0 };
+                point(centre + rng.uniform(), centre + rng.uniform())
+            })
+            .collect();
+        detections.push(point(5.0, -5.0));
+        let outlines = cluster_outlines(&detections, 0.5, 3, 5);
+        assert_eq!(outlines.len(), 2);
+        assert!(outlines.polygons[0].contains(&point(0.5, 0.5)));
+        assert!(outlines.polygons[1].contains(&point(10.5, 10.5)));
+
+        // a 3 x 2 block in the top left of a 5 x 4 mask
+        let mask: Vec<bool> = (0..20).map(|i| i % 5 < 3 && i / 5 < 2).collect();
+        let outline = mask_outline(&mask, 5, 4);
+        let corners: Vec<(f64, f64)> = outline.iter().map(|p| (p.x, p.y)).collect();
+        assert_eq!(corners.len(), 5);
+        assert!([(0.0, 0.0), (3.0, 0.0), (3.0, 2.0), (0.0, 2.0)]
+            .iter()
+            .all(|c| corners.contains(c)));
+        assert!(outline.iter().all(|p| mask[p.id as usize]));
+    }
+
+    #[test]
+    fn test_shapefile_round_trip() {
+        let hull = crate::close_ring(vec![
+            Point {
+                x: 0.0,
+                y: 0.0,
+                id: 0,
+            },
+            Point {
+                x: 2.0,
+                y: 0.0,
+                id: 1,
+            },
+            Point {
+                x: 1.0,
+                y: 3.0,
+                id: 2,
+            },
+        ]);
+        let base = std::env::temp_dir().join(format!("concave-hull-shp-{}", std::process::id()));
+        write_shapefile(&base, &hull).unwrap();
+
+        let read = read_shapefile_points(base.with_extension("shp")).unwrap();
+        assert_eq!(read.len(), 4);
+        assert_eq!(read.first(), read.last());
+        assert!(crate::buffer::signed_area(&read) < 0.0);
+        assert!(read.iter().all(|p| p.id == 1));
+        let shx = std::fs::read(base.with_extension("shx")).unwrap();
+        assert_eq!(shx.len(), 108);
+        let dbf = std::fs::read(base.with_extension("dbf")).unwrap();
+        assert_eq!(dbf.len(), 65 + 11 + 1);
+
+        // a negative record length, and negative or huge counts of parts and points
+        let shp = std::fs::read(base.with_extension("shp")).unwrap();
+        for (at, value) in [
+            (104, (-1i32).to_be_bytes()),
+            (144, (-1i32).to_le_bytes()),
+            (144, i32::MAX.to_le_bytes()),
+            (148, (-1i32).to_le_bytes()),
+            (148, i32::MAX.to_le_bytes()),
+        ] {
+            let mut malformed = shp.clone();
+            malformed[at..at + 4].copy_from_slice(&value);
+            let err = read_shapefile_points(&malformed[..]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        for extension in ["shp", "shx", "dbf"] {
+            std::fs::remove_file(base.with_extension(extension)).unwrap();
+        }
+
+        assert!(read_shapefile_points(&b"not a shapefile"[..]).is_err());
+    }
+}
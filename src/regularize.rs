@@ -0,0 +1,230 @@
+/// Footprint Regularisation
+use crate::point::Point;
+
+use std::f64::consts::FRAC_PI_2;
+
+/// An infinite line `p + t * (cos angle, sin angle)` a run of edges is straightened onto.
+struct Line {
+    px: f64,
+    py: f64,
+    angle: f64,
+}
+
+impl Line {
+    /// the point of the line nearest to `p`
+    fn project(&self, p: &Point) -> (f64, f64) {
+        let (s, c) = self.angle.sin_cos();
+        let t = (p.x - self.px) * c + (p.y - self.py) * s;
+        (self.px + t * c, self.py + t * s)
+    }
+
+    /// where the line meets `other`, `None` if they are parallel
+    fn intersect(&self, other: &Line) -> Option<(f64, f64)> {
+        let (s1, c1) = self.angle.sin_cos();
+        let (s2, c2) = other.angle.sin_cos();
+        let denominator = c1 * s2 - s1 * c2;
+        if denominator.abs() < 1E-9 {
+            return None;
+        }
+        let t = ((other.px - self.px) * s2 - (other.py - self.py) * c2) / denominator;
+        Some((self.px + t * c1, self.py + t * s1))
+    }
+}
+
+/// The dominant direction of the edges of `ring` modulo a right angle, in [0, PI/2).
+///
+/// Edge directions are averaged with their angles quadrupled, which makes directions a
+/// right angle apart agree, each weighted by its edge length.
+pub fn dominant_direction(ring: &[Point]) -> f64 {
+    let n = ring.len();
+    let (mut sx, mut sy) = (0.0, 0.0);
+    for i in 0..n {
+        let (a, b) = (&ring[i], &ring[(i + 1) % n]);
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let angle = 4.0 * dy.atan2(dx);
+        let length = dx.hypot(dy);
+        sx += length * angle.cos();
+        sy += length * angle.sin();
+    }
+    (sy.atan2(sx) / 4.0).rem_euclid(FRAC_PI_2)
+}
+
+/// Regularises a hull into a rectilinear outline, e.g. the footprint of a building.
+///
+/// Edges within `tolerance` of the dominant direction of the hull (see
+/// [`dominant_direction`]) or of its perpendicular are turned onto that direction, and
+/// consecutive edges that end up parallel are merged into one wall, placed at the
+/// length-weighted mean offset of its edges. Edges further off either direction keep their
+/// own, so diagonal walls survive. The corners are where consecutive walls meet.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed.
+/// * `tolerance` - the largest angle in radians between an edge and the direction it is
+///   snapped to.
+///
+/// # Returns
+///
+/// Returns the regularised ring in the orientation of `hull`, closed if `hull` was closed.
+/// Each corner carries the id of the vertex it replaces, the last vertex of the first of its
+/// walls. A hull that regularises to fewer than three corners is returned unchanged.
+pub fn orthogonalize(hull: &[Point], tolerance: f64) -> Vec<Point> {
+    let ring = crate::open_ring(hull);
+    let n = ring.len();
+    if n < 3 {
+        return hull.to_vec();
+    }
+    let dominant = dominant_direction(ring);
+
+    // the direction of each edge, snapped where close enough, with the number of right
+    // angles it was snapped to from the dominant direction
+    let directions: Vec<(f64, Option<i64>)> = (0..n)
+        .map(|i| {
+            let (a, b) = (&ring[i], &ring[(i + 1) % n]);
+            let angle = (b.y - a.y).atan2(b.x - a.x);
+            let quarters = ((angle - dominant) / FRAC_PI_2).round();
+            let snapped = dominant + quarters * FRAC_PI_2;
+            if (angle - snapped).abs() <= tolerance {
+                (snapped, Some((quarters as i64).rem_euclid(4)))
+            } else {
+                (angle, None)
+            }
+        })
+        .collect();
+    let same_wall =
+        |i: usize, j: usize| directions[i].1.is_some() && directions[i].1 == directions[j].1;
+
+    // walls as runs of edges, starting after a change of wall so none wraps
+    let Some(first) = (0..n).find(|&i| !same_wall((i + n - 1) % n, i)) else {
+        return hull.to_vec();
+    };
+    let mut walls: Vec<(Line, usize)> = Vec::new();
+    let mut i = first;
+    while walls.is_empty() || i != first {
+        let (angle, _) = directions[i];
+        let (s, c) = angle.sin_cos();
+        let (mut offset, mut along, mut weight) = (0.0, 0.0, 0.0);
+        let mut last = i;
+        loop {
+            let (a, b) = (&ring[last], &ring[(last + 1) % n]);
+            let length = (b.x - a.x).hypot(b.y - a.y).max(f64::MIN_POSITIVE);
+            let (mx, my) = ((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+            offset += length * (my * c - mx * s);
+            along += length * (mx * c + my * s);
+            weight += length;
+            if !same_wall(last, (last + 1) % n) {
+                break;
+            }
+            last = (last + 1) % n;
+        }
+        let (offset, along) = (offset / weight, along / weight);
+        let line = Line {
+            px: along * c - offset * s,
+            py: along * s + offset * c,
+            angle,
+        };
+        walls.push((line, (last + 1) % n));
+        i = (last + 1) % n;
+    }
+
+    let count = walls.len();
+    let mut regularised: Vec<Point> = Vec::with_capacity(count + 1);
+    for w in 0..count {
+        let ((line, end), (next, _)) = (&walls[w], &walls[(w + 1) % count]);
+        let corner = &ring[*end];
+        let point = |(x, y): (f64, f64)| Point {
+            x,
+            y,
+            id: corner.id,
+        };
+        match line.intersect(next) {
+            Some(meet) => regularised.push(point(meet)),
+            // a wall folding back on itself, joined by a step at the old corner
+            None => {
+                regularised.push(point(line.project(corner)));
+                regularised.push(point(next.project(corner)));
+            }
+        }
+    }
+    if regularised.len() < 3 {
+        return hull.to_vec();
+    }
+
+    if ring.len() < hull.len() {
+        regularised.push(regularised[0].clone());
+    }
+    regularised
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_orthogonalize() {
+        // an L-shaped footprint rotated by 20 degrees, sampled with noise along its walls
+        let mut rng = Rng::new(5);
+        let (s, c) = 20f64.to_radians().sin_cos();
+        let corners = [
+            (0.0, 0.0),
+            (6.0, 0.0),
+            (6.0, 3.0),
+            (3.0, 3.0),
+            (3.0, 5.0),
+            (0.0, 5.0),
+        ];
+        let mut hull = Vec::new();
+        for i in 0..corners.len() {
+            let ((x0, y0), (x1, y1)) = (corners[i], corners[(i + 1) % corners.len()]);
+            for step in 0..4 {
+                let t = step as f64 / 4.0;
+                let noise = if step == 0 { 0.0 } else { 0.05 };
+                let (u, v) = (
+                    x0 + t * (x1 - x0) + noise * (rng.uniform() - 0.5),
+                    y0 + t * (y1 - y0) + noise * (rng.uniform() - 0.5),
+                );
+                hull.push(Point {
+                    x: u * c - v * s,
+                    y: u * s + v * c,
+                    id: hull.len() as u64,
+                });
+            }
+        }
+        hull.push(hull[0].clone());
+
+        assert!((dominant_direction(&hull[..hull.len() - 1]) - 20f64.to_radians()).abs() < 0.02);
+        let regularised = orthogonalize(&hull, 0.3);
+        assert_eq!(regularised.len(), 7);
+        assert_eq!(regularised.first(), regularised.last());
+        let ring = &regularised[..6];
+        for i in 0..6 {
+            let (a, b, d) = (&ring[i], &ring[(i + 1) % 6], &ring[(i + 2) % 6]);
+            let dot = (b.x - a.x) * (d.x - b.x) + (b.y - a.y) * (d.y - b.y);
+            assert!(dot.abs() < 1E-9, "corner {} is not square", i);
+        }
+        let area = crate::buffer::signed_area(ring).abs();
+        assert!((area - 24.0).abs() < 0.5, "area {}", area);
+
+        // a diagonal wall within no tolerance of the axes stays
+        let triangle = [
+            Point {
+                x: 0.0,
+                y: 0.0,
+                id: 0,
+            },
+            Point {
+                x: 4.0,
+                y: 0.0,
+                id: 1,
+            },
+            Point {
+                x: 0.0,
+                y: 4.0,
+                id: 2,
+            },
+        ];
+        assert_eq!(orthogonalize(&triangle, 0.1).len(), 3);
+        assert_eq!(orthogonalize(&triangle[..2], 0.1), triangle[..2]);
+    }
+}
@@ -0,0 +1,210 @@
+/// Point Sources in Object Stores
+///
+/// With the `object_store` feature, point files are read straight from S3, GCS or Azure
+/// URLs, or any other URL the object_store crate parses, without a download step:
+///
+/// ```
+/// use concave_hull::remote::ObjectUrl;
+///
+/// let features = Features::read(ObjectUrl::parse("s3://bucket/points.geojson")?)?;
+/// let points = read_gpkg_points(ObjectUrl::parse("az://container/survey.gpkg")?, None)?;
+/// ```
+///
+/// An [`ObjectUrl`] is a [`PointSource`] whose reader fetches the object in ranged requests
+/// of [`CHUNK`] bytes and holds one chunk at a time. The GeoJSON, GeoPackage and shapefile
+/// readers of the crate take the whole file into memory, so they fetch an object in full.
+/// An [`ObjectReader`] also seeks, and the first read after a seek fetches the range from
+/// there on, for readers that need only part of an object.
+use crate::io::PointSource;
+
+use object_store::path::Path;
+use object_store::ObjectStore;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// bytes fetched per ranged request
+pub const CHUNK: usize = 8 << 20;
+
+/// An object in an object store, read as a [`PointSource`].
+#[derive(Debug, Clone)]
+pub struct ObjectUrl {
+    /// the store holding the object
+    store: Arc<dyn ObjectStore>,
+    /// where the object is in the store
+    path: Path,
+}
+
+impl ObjectUrl {
+    /// The object at `url`, e.g. `s3://bucket/points.csv`, `gs://bucket/points.csv` or
+    /// `az://container/points.csv`.
+    ///
+    /// Credentials and regions come from the environment variables the object_store
+    /// builders know, e.g. `AWS_ACCESS_KEY_ID`, `GOOGLE_SERVICE_ACCOUNT` or
+    /// `AZURE_STORAGE_ACCOUNT_NAME`.
+    pub fn parse(url: &str) -> io::Result<Self> {
+        let url =
+            url::Url::parse(url).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        let options = std::env::vars().map(|(key, value)| (key.to_ascii_lowercase(), value));
+        let (store, path) = object_store::parse_url_opts(&url, options).map_err(to_io)?;
+        Ok(ObjectUrl::new(store.into(), path))
+    }
+
+    /// The object at `path` in a configured `store`.
+    pub fn new(store: Arc<dyn ObjectStore>, path: Path) -> Self {
+        ObjectUrl { store, path }
+    }
+
+    /// Opens the object for ranged reads, asking the store for its size first.
+    pub fn reader(&self) -> io::Result<ObjectReader> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let size = runtime
+            .block_on(self.store.head(&self.path))
+            .map_err(to_io)?
+            .size as u64;
+        Ok(ObjectReader {
+            runtime,
+            store: self.store.clone(),
+            path: self.path.clone(),
+            size,
+            position: 0,
+            chunk: Vec::new(),
+            chunk_start: 0,
+        })
+    }
+}
+
+impl PointSource for ObjectUrl {
+    type Reader = BufReader<ObjectReader>;
+
+    fn open(self) -> io::Result<Self::Reader> {
+        self.reader().map(BufReader::new)
+    }
+}
+
+/// A blocking, seekable reader of an object, fetching [`CHUNK`] bytes per ranged request.
+#[derive(Debug)]
+pub struct ObjectReader {
+    /// drives the requests of the store
+    runtime: Runtime,
+    /// the store holding the object
+    store: Arc<dyn ObjectStore>,
+    /// where the object is in the store
+    path: Path,
+    /// length of the object in bytes
+    size: u64,
+    /// offset of the next byte read
+    position: u64,
+    /// the last range fetched
+    chunk: Vec<u8>,
+    /// offset of the first byte of `chunk`
+    chunk_start: u64,
+}
+
+impl ObjectReader {
+    /// length of the object in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Read for ObjectReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.size || buf.is_empty() {
+            return Ok(0);
+        }
+        let chunk_end = self.chunk_start + self.chunk.len() as u64;
+        if !(self.chunk_start..chunk_end).contains(&self.position) {
+            let end = (self.position + CHUNK as u64).min(self.size);
+            let range = self.position as usize..end as usize;
+            let bytes = self
+                .runtime
+                .block_on(self.store.get_range(&self.path, range))
+                .map_err(to_io)?;
+            self.chunk = bytes.to_vec();
+            self.chunk_start = self.position;
+        }
+        let offset = (self.position - self.chunk_start) as usize;
+        let n = buf.len().min(self.chunk.len() - offset);
+        buf[..n].copy_from_slice(&self.chunk[offset..offset + n]);
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for ObjectReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the object",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+/// an object store error as an I/O error, keeping a missing object apart
+fn to_io(err: object_store::Error) -> io::Error {
+    let kind = match err {
+        object_store::Error::NotFound { .. } => io::ErrorKind::NotFound,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+    use std::io::BufRead;
+
+    #[test]
+    fn test_object_url() {
+        let store = Arc::new(InMemory::new());
+        let text: String = (0..CHUNK / 4)
+            .map(|i| format!("{} {}\n", i, i + 1))
+            .collect();
+        let path = Path::from("points.txt");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(store.put(&path, text.clone().into_bytes().into()))
+            .unwrap();
+
+        // more than one chunk, read front to back
+        let object = ObjectUrl::new(store.clone(), path);
+        assert!(text.len() > CHUNK);
+        let lines = object.clone().open().unwrap().lines().count();
+        assert_eq!(lines, CHUNK / 4);
+
+        // ranged reads from either end
+        let mut reader = object.reader().unwrap();
+        assert_eq!(reader.size(), text.len() as u64);
+        let mut tail = String::new();
+        reader.seek(SeekFrom::End(-6)).unwrap();
+        reader.read_to_string(&mut tail).unwrap();
+        assert!(text.ends_with(&tail) && tail.len() == 6);
+        let mut head = [0; 4];
+        reader.seek(SeekFrom::Start(0)).unwrap();
+        reader.read_exact(&mut head).unwrap();
+        assert_eq!(&head, b"0 1\n");
+        assert!(reader.seek(SeekFrom::Current(-5)).is_err());
+
+        let missing = ObjectUrl::new(store, Path::from("missing.txt"));
+        assert_eq!(
+            missing.reader().unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        assert!(ObjectUrl::parse("memory:///points.txt").is_ok());
+        assert!(ObjectUrl::parse("no url").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+/// Failure Hooks and Retry Policies
+use crate::point::Point;
+use crate::stats::Failure;
+use crate::Options;
+
+/// A step of the walk that would cross the hull built so far.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crossing {
+    /// the candidate next vertex
+    pub candidate: Point,
+    /// the first hull edge the step to the candidate crosses
+    pub edge: (Point, Point),
+}
+
+/// A rejected hull, as passed to the hook of [`concave_hull_with_hook`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedAttempt<'a> {
+    /// number of nearest neighbours used
+    pub k: usize,
+    /// why the hull was rejected
+    pub failure: Failure,
+    /// the hull as far as it got, up to the vertex the walk got stuck on
+    pub hull: &'a [Point],
+    /// for [`Failure::PointsOutside`], the points left outside the hull
+    pub outside: Vec<Point>,
+    /// for [`Failure::SelfIntersection`], every candidate of the step the walk got stuck on
+    /// and the edge it crosses
+    pub crossings: Vec<Crossing>,
+}
+
+/// What [`concave_hull_with_hook`] does after a rejected hull.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Retry {
+    /// try again with the next `k`, as [`crate::concave_hull`] does when iterating
+    Next,
+    /// try again with the given `k`, if it is larger than the rejected one, else the next
+    WithK(usize),
+    /// remove the points with these ids from the dataset and try the same `k` again; if
+    /// none of them is in the dataset this is [`Retry::Next`]
+    Drop(Vec<u64>),
+    /// give up and return an empty hull
+    Stop,
+}
+
+/// Computes the concave hull of a given set of points, asking `hook` how to go on after
+/// every rejected hull.
+///
+/// The hook receives the partial hull and the points or edges that made it fail, so it can
+/// log a snapshot of the failure, or drop the outliers that cause it instead of raising
+/// `k` until the hull takes them in. A hook that always returns [`Retry::Next`] gives the
+/// hull of [`crate::concave_hull`] with `iterate` set, and one that returns [`Retry::Stop`]
+/// the hull with `iterate` unset.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud; the points dropped by the hook are removed from it.
+/// * `k` - number of nearest neighbors of the first attempt.
+/// * `hook` - called with every rejected hull, returns how to go on.
+///
+/// # Returns
+///
+/// Returns the first accepted hull as [`crate::concave_hull`] does, or an empty vector
+/// once the hook stops or `k` reaches the number of points.
+pub fn concave_hull_with_hook(
+    dataset: &mut Vec<Point>,
+    mut k: usize,
+    mut hook: impl FnMut(&FailedAttempt) -> Retry,
+) -> Vec<Point> {
+    let options = Options::default();
+    while k < dataset.len() {
+        let mut hull = Vec::<Point>::new();
+        let failure = match crate::concave_hull_inner(dataset, k, &options, &mut hull) {
+            Ok(()) => return hull,
+            Err(failure) => failure,
+        };
+        let outside = match failure {
+            Failure::PointsOutside => dataset
+                .iter()
+                .filter(|p| !crate::polygon::point_in_polygon(p, &hull))
+                .cloned()
+                .collect(),
+            _ => Vec::new(),
+        };
+        let attempt = FailedAttempt {
+            k,
+            failure,
+            hull: &hull,
+            outside,
+            crossings: options.crossings.take(),
+        };
+
+        match hook(&attempt) {
+            Retry::Next => k += 1,
+            Retry::WithK(next) => k = next.max(k + 1),
+            Retry::Drop(ids) => {
+                let before = dataset.len();
+                dataset.retain(|p| !ids.contains(&p.id));
+                if dataset.len() == before {
+                    k += 1;
+                }
+            }
+            Retry::Stop => break,
+        }
+    }
+
+    Vec::<Point>::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_concave_hull_with_hook() {
+        let mut rng = Rng::new(7);
+        let points: Vec<Point> = (0..500)
+            .map(|id| Point {
+                x: rng.uniform(),
+                y: rng.uniform(),
+                id,
+            })
+            .collect();
+        let expected = crate::concave_hull(&mut points.clone(), 3, true).unwrap();
+
+        let mut failures = Vec::new();
+        let hull = concave_hull_with_hook(&mut points.clone(), 3, |attempt| {
+            match attempt.failure {
+                Failure::SelfIntersection => {
+                    let last = attempt.hull.last().unwrap();
+                    assert!(!attempt.crossings.is_empty());
+                    assert!(attempt
+                        .crossings
+                        .iter()
+                        .all(|c| crate::predicates::intersects(
+                            (last, &c.candidate),
+                            (&c.edge.0, &c.edge.1)
+                        )));
+                }
+                _ => assert!(!attempt.outside.is_empty()),
+            }
+            failures.push(attempt.k);
+            Retry::Next
+        });
+        assert_eq!(hull, expected);
+        assert!(!failures.is_empty());
+
+        let hull = concave_hull_with_hook(&mut points.clone(), 3, |_| Retry::Stop);
+        assert!(hull.is_empty());
+    }
+}
@@ -0,0 +1,308 @@
+/// Hull Simplification
+use crate::buffer::signed_area;
+use crate::point::{BoundingBox, Point};
+use crate::predicates::intersects;
+use crate::prepared::segment_distance;
+
+/// Reduces the vertex count of a hull (Douglas-Peucker).
+///
+/// The ring is split at its first vertex and the vertex furthest from it, and each half is
+/// simplified so that no removed vertex lies further than `tolerance` from the simplified
+/// boundary. Nearly collinear runs of vertices, common on hulls of dense point clouds,
+/// collapse into single edges. Simplification can make a concave ring touch itself; add a
+/// validation step to a [`crate::pipeline::Pipeline`] to catch that.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed.
+/// * `tolerance` - the largest distance a removed vertex may have from the result.
+///
+/// # Returns
+///
+/// Returns the kept vertices in their original order, closed if `hull` was closed.
+pub fn simplify(hull: &[Point], tolerance: f64) -> Vec<Point> {
+    let ring = crate::open_ring(hull);
+    let n = ring.len();
+    if n <= 3 {
+        return hull.to_vec();
+    }
+
+    let far = (1..n)
+        .max_by(|&a, &b| {
+            let da = (ring[a].x - ring[0].x).hypot(ring[a].y - ring[0].y);
+            let db = (ring[b].x - ring[0].x).hypot(ring[b].y - ring[0].y);
+            da.total_cmp(&db)
+        })
+        .unwrap_or(1);
+
+    let mut keep = vec![false; n + 1];
+    keep[0] = true;
+    keep[far] = true;
+    keep[n] = true;
+    // index n stands for the first vertex again, closing the second half
+    let vertex = |i: usize| &ring[i % n];
+
+    let mut stack = vec![(0, far), (far, n)];
+    while let Some((start, end)) = stack.pop() {
+        let (a, b) = (vertex(start), vertex(end));
+        let furthest = (start + 1..end)
+            .map(|i| (i, segment_distance(vertex(i), a, b)))
+            .max_by(|p, q| p.1.total_cmp(&q.1));
+        if let Some((i, distance)) = furthest {
+            if distance > tolerance {
+                keep[i] = true;
+                stack.push((start, i));
+                stack.push((i, end));
+            }
+        }
+    }
+
+    let mut simplified: Vec<Point> = (0..n)
+        .filter(|&i| keep[i])
+        .map(|i| ring[i].clone())
+        .collect();
+    if hull.len() > n {
+        simplified.push(ring[0].clone());
+    }
+    simplified
+}
+
+/// Reduces a hull to at most `max_vertices` vertices that still enclose it.
+///
+/// Vertices are dropped one at a time, always the one whose removal adds the least area:
+/// a reflex vertex is cut off along the chord between its neighbours, and on convex runs
+/// two neighbouring vertices are replaced by the point where the edges before and after
+/// them meet. Neither move shrinks the hull or lets it cross itself, so the result
+/// encloses every point the hull enclosed. Points of the second kind are new, with id 0.
+///
+/// If no move is left before the budget is met, which only happens on rings as regular as
+/// a square cut to three vertices, the bounding box, or a triangle around it, is returned.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed.
+/// * `max_vertices` - the most vertices of the result, at least 3.
+///
+/// # Returns
+///
+/// Returns the reduced ring in the orientation of `hull`, closed if `hull` was closed.
+pub fn reduce_vertices(hull: &[Point], max_vertices: usize) -> Vec<Point> {
+    let max_vertices = max_vertices.max(3);
+    let open = crate::open_ring(hull);
+    if open.len() <= max_vertices {
+        return hull.to_vec();
+    }
+
+    // the moves below are worked out counter-clockwise
+    let clockwise = signed_area(open) < 0.0;
+    let mut ring = open.to_vec();
+    if clockwise {
+        ring.reverse();
+    }
+    while ring.len() > max_vertices {
+        match cheapest_reduction(&ring) {
+            Some(Reduction::Remove(i)) => {
+                ring.remove(i);
+            }
+            Some(Reduction::Merge(i, point)) => {
+                let next = (i + 1) % ring.len();
+                ring[i] = point;
+                ring.remove(next);
+            }
+            None => {
+                ring = enclosing_polygon(&ring, max_vertices);
+                break;
+            }
+        }
+    }
+    if clockwise {
+        ring.reverse();
+    }
+    if hull.len() > open.len() {
+        ring.push(ring[0].clone());
+    }
+    ring
+}
+
+/// A step of [`reduce_vertices`] on a counter-clockwise ring.
+enum Reduction {
+    /// drop the reflex vertex at the index
+    Remove(usize),
+    /// replace the vertex at the index and the next one by the point
+    Merge(usize, Point),
+}
+
+/// the valid reduction of `ring` that adds the least area
+fn cheapest_reduction(ring: &[Point]) -> Option<Reduction> {
+    let n = ring.len();
+    let at = |i: usize| &ring[i % n];
+    let cross =
+        |o: &Point, a: &Point, b: &Point| (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+
+    let mut candidates: Vec<(f64, Reduction)> = Vec::new();
+    for i in 0..n {
+        let (t, u, v, w) = (at(i + n - 1), at(i), at(i + 1), at(i + 2));
+        // a right turn at u: cutting it off through t-v only adds area
+        let turn = cross(t, u, v);
+        if turn <= 0.0 {
+            candidates.push((-turn / 2.0, Reduction::Remove(i)));
+        }
+        // the lines through t-u and w-v, meeting beyond u-v on its outside
+        let (d1, d2, e) = (
+            (u.x - t.x, u.y - t.y),
+            (v.x - w.x, v.y - w.y),
+            (v.x - u.x, v.y - u.y),
+        );
+        let det = d1.0 * d2.1 - d1.1 * d2.0;
+        if n > 3 && det != 0.0 {
+            let s = (e.0 * d2.1 - e.1 * d2.0) / det;
+            let r = (e.0 * d1.1 - e.1 * d1.0) / det;
+            let point = Point {
+                x: u.x + s * d1.0,
+                y: u.y + s * d1.1,
+                id: 0,
+            };
+            let added = -cross(u, v, &point);
+            if s > 0.0 && r > 0.0 && added > 0.0 && added.is_finite() {
+                candidates.push((added / 2.0, Reduction::Merge(i, point)));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // the new edges must not cross any edge that doesn't end where they start
+    let crosses = |a: &Point, b: &Point, ends: [usize; 2]| {
+        (0..n).any(|j| {
+            let edge = (j, (j + 1) % n);
+            !ends.iter().any(|&end| end == edge.0 || end == edge.1)
+                && intersects((a, b), (&ring[edge.0], &ring[edge.1]))
+        })
+    };
+    candidates
+        .into_iter()
+        .map(|(_, reduction)| reduction)
+        .find(|reduction| match reduction {
+            Reduction::Remove(i) => {
+                let (before, after) = ((i + n - 1) % n, (i + 1) % n);
+                !crosses(&ring[before], &ring[after], [before, after])
+            }
+            Reduction::Merge(i, point) => {
+                let (u, v) = (*i, (i + 1) % n);
+                !crosses(&ring[u], point, [u, v]) && !crosses(point, &ring[v], [u, v])
+            }
+        })
+}
+
+/// the bounding box of `ring` counter-clockwise, or for fewer than 4 vertices a triangle
+/// around it
+fn enclosing_polygon(ring: &[Point], max_vertices: usize) -> Vec<Point> {
+    let bbox = BoundingBox::from_points(ring);
+    let point = |x: f64, y: f64| Point { x, y, id: 0 };
+    let (w, h) = (bbox.width(), bbox.height());
+    if max_vertices >= 4 {
+        vec![
+            point(bbox.min_x, bbox.min_y),
+            point(bbox.max_x, bbox.min_y),
+            point(bbox.max_x, bbox.max_y),
+            point(bbox.min_x, bbox.max_y),
+        ]
+    } else {
+        // the top corners of the box lie on the sides
+        vec![
+            point(bbox.min_x - w / 2.0, bbox.min_y),
+            point(bbox.max_x + w / 2.0, bbox.min_y),
+            point((bbox.min_x + bbox.max_x) / 2.0, bbox.min_y + 2.0 * h),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify() {
+        // a closed square with jittered points along every side
+        let mut hull = Vec::new();
+        for side in 0..4 {
+            for i in 0..10 {
+                let t = i as f64 / 10.0;
+                let jitter = if i % 2 == 1 { 0.01 } else { 0.0 };
+                let (x, y) = match side {
+                    0 => (t, -jitter),
+                    1 => (1.0 + jitter, t),
+                    2 => (1.0 - t, 1.0 + jitter),
+                    _ => (-jitter, 1.0 - t),
+                };
+                hull.push(Point {
+                    x,
+                    y,
+                    id: hull.len() as u64,
+                });
+            }
+        }
+        hull.push(hull[0].clone());
+
+        let simplified = simplify(&hull, 0.05);
+        let ids: Vec<u64> = simplified.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![0, 10, 20, 30, 0]);
+
+        // nothing moves further than the tolerance allows
+        assert_eq!(simplify(&hull, 0.001), hull);
+        let open = simplify(&hull[..40], 0.05);
+        assert_eq!(open.len(), 4);
+    }
+
+    #[test]
+    fn test_reduce_vertices() {
+        use crate::prepared::PreparedHull;
+        use crate::synthetic::Rng;
+
+        // a disc with a wedge cut out, whose hull has reflex and convex runs
+        let mut rng = Rng::new(4);
+        let mut points = Vec::new();
+        while points.len() < 2000 {
+            let (x, y) = (2.0 * rng.uniform() - 1.0, 2.0 * rng.uniform() - 1.0);
+            if x.hypot(y) < 1.0 && !(x > 0.0 && y.abs() < x / 2.0) {
+                points.push(Point {
+                    x,
+                    y,
+                    id: points.len() as u64,
+                });
+            }
+        }
+        let hull = crate::concave_hull(&mut points.clone(), 5, true).unwrap();
+        let encloses = |ring: &[Point], points: &[Point]| {
+            let prepared = PreparedHull::new(ring);
+            points
+                .iter()
+                .all(|p| prepared.contains(p) || prepared.distance(p) < 1E-9)
+        };
+        // the vertices of hulls rejected on the way are not always enclosed
+        let prepared = PreparedHull::new(&hull);
+        points.retain(|p| prepared.contains(p) || prepared.distance(p) < 1E-9);
+        for max_vertices in [40, 12, 6, 3] {
+            let reduced = reduce_vertices(&hull, max_vertices);
+            assert!(reduced.len() <= max_vertices + 1);
+            assert_eq!(reduced.first(), reduced.last());
+            assert!(signed_area(&reduced) < 0.0);
+            assert!(encloses(&reduced, &points));
+        }
+        // the wedge survives a generous budget
+        let reduced = reduce_vertices(&hull, 40);
+        assert!(signed_area(&reduced).abs() < 0.95 * std::f64::consts::PI);
+
+        // a square can't be cut to a triangle by either move
+        let square: Vec<Point> = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+            .iter()
+            .map(|&(x, y)| Point { x, y, id: 0 })
+            .collect();
+        let triangle = reduce_vertices(&square, 3);
+        assert_eq!(triangle.len(), 3);
+        let prepared = PreparedHull::new(&triangle);
+        assert!(square
+            .iter()
+            .all(|p| prepared.contains(p) || prepared.distance(p) < 1E-12));
+        assert_eq!(reduce_vertices(&square, 4), square);
+    }
+}
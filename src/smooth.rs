@@ -0,0 +1,147 @@
+/// Hull Smoothing
+use crate::point::Point;
+use crate::prepared::PreparedHull;
+
+/// Smooths a hull by Chaikin corner cutting.
+///
+/// Every iteration replaces each edge by the points at a quarter and three quarters of its
+/// length, which cuts every corner and doubles the vertex count. The ring converges to a
+/// quadratic B-spline, so a few iterations turn the jagged k-NN boundary into a smooth one.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed.
+/// * `iterations` - number of corner cutting rounds.
+///
+/// # Returns
+///
+/// Returns the smoothed ring, closed if `hull` was closed. New vertices carry the id of the
+/// original vertex they are closest to.
+pub fn smooth(hull: &[Point], iterations: usize) -> Vec<Point> {
+    chaikin(hull, iterations, &[])
+}
+
+/// Smooths a hull by Chaikin corner cutting without leaving any of `points` outside.
+///
+/// This works like [`smooth`], except that a corner is left uncut whenever cutting it would
+/// leave one of `points` outside the ring. Convex corners at hull vertices therefore stay
+/// sharp, while concave corners and the rest of the boundary are smoothed.
+///
+/// # Arguments
+///
+/// * `hull` - the hull, open or closed.
+/// * `iterations` - number of corner cutting rounds.
+/// * `points` - the points that must stay on or inside the ring, normally the points the
+///   hull was computed from.
+///
+/// # Returns
+///
+/// Returns the smoothed ring, closed if `hull` was closed.
+pub fn smooth_containing(hull: &[Point], iterations: usize, points: &[Point]) -> Vec<Point> {
+    chaikin(hull, iterations, points)
+}
+
+fn chaikin(hull: &[Point], iterations: usize, points: &[Point]) -> Vec<Point> {
+    let mut ring = crate::open_ring(hull).to_vec();
+    if ring.len() < 3 {
+        return hull.to_vec();
+    }
+
+    for _ in 0..iterations {
+        let n = ring.len();
+        let lerp = |a: &Point, b: &Point, t: f64, id: u64| Point {
+            x: a.x + t * (b.x - a.x),
+            y: a.y + t * (b.y - a.y),
+            id,
+        };
+        // the cut at corner i runs from the end of edge i - 1 to the start of edge i
+        let cut = |i: usize| {
+            let (prev, corner, next) = (&ring[(i + n - 1) % n], &ring[i], &ring[(i + 1) % n]);
+            (
+                lerp(corner, prev, 0.25, corner.id),
+                lerp(corner, next, 0.25, corner.id),
+            )
+        };
+        let mut keep = vec![false; n];
+
+        if !points.is_empty() {
+            let mut cut_ring = Vec::with_capacity(2 * n);
+            for i in 0..n {
+                let (a, b) = cut(i);
+                cut_ring.push(a);
+                cut_ring.push(b);
+            }
+            let prepared = PreparedHull::new(&cut_ring);
+            let outside: Vec<&Point> = points
+                .iter()
+                .filter(|p| !prepared.contains(p) && prepared.distance(p) > 1E-10)
+                .collect();
+            for (i, kept) in keep.iter_mut().enumerate() {
+                let (a, b) = cut(i);
+                *kept = outside.iter().any(|p| in_triangle(p, &a, &ring[i], &b));
+            }
+        }
+
+        let mut next = Vec::with_capacity(3 * n);
+        for i in 0..n {
+            let (a, b) = cut(i);
+            next.push(a);
+            if keep[i] {
+                next.push(ring[i].clone());
+            }
+            next.push(b);
+        }
+        ring = next;
+    }
+
+    if hull.len() > crate::open_ring(hull).len() {
+        ring.push(ring[0].clone());
+    }
+    ring
+}
+
+/// Whether `p` lies in the closed triangle `a`, `b`, `c` of either orientation.
+fn in_triangle(p: &Point, a: &Point, b: &Point, c: &Point) -> bool {
+    let side = |u: &Point, v: &Point| (v.x - u.x) * (p.y - u.y) - (v.y - u.y) * (p.x - u.x);
+    let (d0, d1, d2) = (side(a, b), side(b, c), side(c, a));
+    let eps = 1E-12;
+    (d0 >= -eps && d1 >= -eps && d2 >= -eps) || (d0 <= eps && d1 <= eps && d2 <= eps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smooth() {
+        let square: Vec<Point> = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)]
+            .iter()
+            .enumerate()
+            .map(|(id, &(x, y))| Point {
+                x,
+                y,
+                id: id as u64 % 4,
+            })
+            .collect();
+
+        let smoothed = smooth(&square, 3);
+        assert_eq!(smoothed.len(), 4 * 8 + 1);
+        assert_eq!(smoothed.first(), smoothed.last());
+        // the cut corner loses area, but the sides stay in place
+        let prepared = PreparedHull::new(&smoothed);
+        assert!(!prepared.contains(&square[0]));
+        assert!(prepared.contains(&Point {
+            x: 0.5,
+            y: 0.01,
+            id: 0,
+        }));
+
+        let points = square[..4].to_vec();
+        let kept = smooth_containing(&square, 3, &points);
+        let prepared = PreparedHull::new(&kept);
+        assert!(points
+            .iter()
+            .all(|p| prepared.contains(p) || prepared.distance(p) < 1E-10));
+        assert_eq!(smooth(&square[..2], 3), square[..2].to_vec());
+    }
+}
@@ -0,0 +1,391 @@
+/// Dataset and Hull Diagnostics
+use crate::index::{IndexPrecision, SearchTree};
+use crate::metric::Metric;
+use crate::point::{BoundingBox, Point};
+use crate::triangulation::delaunay;
+
+/// Summary of a point cloud used to guide parameter choice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetStats {
+    /// number of points
+    pub count: usize,
+    /// axis-aligned extent of the points
+    pub bbox: BoundingBox,
+    /// mean distance from each point to its nearest neighbour
+    pub mean_nn_distance: f64,
+    /// points per unit area of the bounding box
+    pub density: f64,
+    /// number of points sharing their coordinates with an earlier point
+    pub duplicates: usize,
+    /// Clark-Evans ratio of the observed to the expected (uniform) nearest neighbour
+    /// distance: about 1 for uniform data, below 1 for clustered data
+    pub clustering: f64,
+    /// suggested starting k for [`crate::concave_hull`]
+    pub recommended_k: usize,
+}
+
+/// Why an attempted hull was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// every candidate step of the walk crossed the hull built so far
+    SelfIntersection,
+    /// a required point did not become a hull vertex
+    MissingRequired,
+    /// more points than allowed lie outside the hull
+    PointsOutside,
+    /// the computation was interrupted before the hull was complete
+    Interrupted,
+}
+
+/// One hull attempted while searching for `k`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attempt {
+    /// number of nearest neighbours used
+    pub k: usize,
+    /// why the hull was rejected, `None` if it was accepted
+    pub failure: Option<Failure>,
+    /// number of vertices the hull had when it was accepted or rejected
+    pub hull_size: usize,
+}
+
+/// How the hull returned by [`crate::concave_hull_with_stats`] was found.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HullStats {
+    /// `k` of the returned hull, `None` if no hull was returned
+    pub k: Option<usize>,
+    /// number of hulls attempted
+    pub attempts: usize,
+    /// every attempt in order, if the history was requested
+    pub history: Vec<Attempt>,
+    /// the edge intersection tests of all attempts
+    pub edge_tests: EdgeTests,
+}
+
+/// How far the walk of a hull attempt has got, reported to a progress callback.
+///
+/// The step count restarts with every attempted `k`; the walk ends after at most as many
+/// steps as there are points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// steps of the walk so far
+    pub step: usize,
+    /// number of nearest neighbours of the attempt
+    pub k: usize,
+    /// number of hull vertices so far
+    pub hull_size: usize,
+}
+
+/// How many hull edges the walk tested candidate steps against.
+///
+/// Before a step is tested against a hull edge, the extents of the two are compared, and
+/// the test is skipped (pruned) if they are disjoint. A high share of pruned tests shows the
+/// acceleration pays off on a dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EdgeTests {
+    /// exact intersection tests run
+    pub performed: usize,
+    /// tests skipped because the extents were disjoint
+    pub pruned: usize,
+}
+
+/// Computes bounding box, nearest neighbour and density diagnostics for `points`.
+///
+/// The recommended k starts at 3 (the smallest useful value) and grows as the data gets
+/// more clustered, since gaps between clusters need more neighbours to bridge.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+///
+/// # Returns
+///
+/// Returns a `DatasetStats` summary. Distances and density are zero for fewer than 2 points.
+pub fn dataset_stats(points: &[Point]) -> DatasetStats {
+    let count = points.len();
+    let bbox = BoundingBox::from_points(points);
+    let duplicates = count_duplicates(points);
+
+    let mean_nn_distance = if count < 2 {
+        0.0
+    } else {
+        knn_distances(points, 1).iter().sum::<f64>() / count as f64
+    };
+
+    let area = bbox.area();
+    let density = if count < 2 || area <= 0.0 {
+        0.0
+    } else {
+        count as f64 / area
+    };
+
+    let clustering = if density > 0.0 {
+        mean_nn_distance / (0.5 / density.sqrt())
+    } else {
+        1.0
+    };
+
+    let recommended_k = if count < 4 {
+        3
+    } else {
+        let k = (3.0 / clustering.clamp(1E-3, 1.0)).ceil() as usize;
+        k.clamp(3, count - 1)
+    };
+
+    DatasetStats {
+        count,
+        bbox,
+        mean_nn_distance,
+        density,
+        duplicates,
+        clustering,
+        recommended_k,
+    }
+}
+
+/// Distance from every point to its k-th nearest neighbour (itself excluded).
+///
+/// Points with fewer than `k` other points around them report the distance to the farthest
+/// point there is.
+pub fn knn_distances(points: &[Point], k: usize) -> Vec<f64> {
+    let tree = SearchTree::new(points, Metric::Euclidean, IndexPrecision::Full);
+    points
+        .iter()
+        .map(|p| {
+            // the point itself is among its own neighbours
+            tree.nearest_distances(p, k + 1)
+                .last()
+                .copied()
+                .unwrap_or(0.0)
+        })
+        .collect()
+}
+
+/// Equal-width histogram of values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// bin boundaries, one more than there are bins
+    pub edges: Vec<f64>,
+    /// number of values in each bin (the last bin includes its upper edge)
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// bins `values` into `bins` equal-width bins spanning their range
+    pub fn new(values: &[f64], bins: usize) -> Self {
+        let bins = bins.max(1);
+        let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let (lo, hi) = if values.is_empty() {
+            (0.0, 0.0)
+        } else {
+            (lo, hi)
+        };
+        let width = (hi - lo) / bins as f64;
+
+        let edges = (0..=bins).map(|i| lo + width * i as f64).collect();
+        let mut counts = vec![0; bins];
+        for v in values.iter() {
+            let bin = if width > 0.0 {
+                (((v - lo) / width) as usize).min(bins - 1)
+            } else {
+                0
+            };
+            counts[bin] += 1;
+        }
+
+        Histogram { edges, counts }
+    }
+}
+
+/// Histogram of the k-th nearest neighbour distances of `points`.
+///
+/// This distribution is the usual starting point for picking `k`, radii and edge-length
+/// thresholds: gaps between its modes separate within-cluster spacing from the gaps a hull
+/// should not bridge.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `k` - which neighbour to measure the distance to.
+/// * `bins` - number of equal-width bins.
+pub fn knn_distance_histogram(points: &[Point], k: usize, bins: usize) -> Histogram {
+    Histogram::new(&knn_distances(points, k), bins)
+}
+
+/// Lengths of the edges of the Delaunay triangulation of `points`, each edge once.
+///
+/// The non-k hull flavours prune this triangulation by edge length or circumradius, so its
+/// edge lengths are the values their thresholds are picked from.
+pub fn delaunay_edge_lengths(points: &[Point]) -> Vec<f64> {
+    let triangulation = delaunay(points);
+    triangulation
+        .edges()
+        .iter()
+        .map(|&(a, b)| {
+            let (a, b) = (&triangulation.points[a], &triangulation.points[b]);
+            (b.x - a.x).hypot(b.y - a.y)
+        })
+        .collect()
+}
+
+/// Histogram of the Delaunay edge lengths of `points`, see [`delaunay_edge_lengths`].
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `bins` - number of equal-width bins.
+pub fn edge_length_histogram(points: &[Point], bins: usize) -> Histogram {
+    Histogram::new(&delaunay_edge_lengths(points), bins)
+}
+
+/// Parameters suggested at a gap in the Delaunay edge lengths.
+///
+/// All three describe the same cut: edges up to `max_edge_length` are kept and longer ones
+/// are treated as bridging a gap the hull should follow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdSuggestion {
+    /// the longest edge to keep, midway across the gap
+    pub max_edge_length: f64,
+    /// alpha-shape (and [`crate::pivoting::ball_pivoting`]) radius, half the edge length
+    pub alpha: f64,
+    /// chi-shape lambda, the edge length normalised between the shortest (0) and the longest
+    /// (1) edge
+    pub chi_lambda: f64,
+    /// ratio of the lengths either side of the gap, the larger the more natural the cut
+    pub gap: f64,
+    /// fraction of the edges kept by the cut
+    pub kept: f64,
+}
+
+/// Suggests edge-length thresholds at the natural gaps in the Delaunay edge lengths.
+///
+/// Candidate cuts lie between consecutive sorted lengths and are ranked by the ratio of the
+/// lengths either side, so a cut between 1 and 10 beats one between 10 and 20. Only cuts
+/// keeping at least half of the edges are considered: the edges inside the point cloud far
+/// outnumber those spanning its gaps and concavities.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `count` - the most suggestions to return.
+///
+/// # Returns
+///
+/// Returns up to `count` suggestions, the most natural cut first. Returns an empty vector
+/// if `points` has no triangulation or all its edges are the same length.
+pub fn suggest_thresholds(points: &[Point], count: usize) -> Vec<ThresholdSuggestion> {
+    let mut lengths = delaunay_edge_lengths(points);
+    lengths.sort_by(f64::total_cmp);
+    let (Some(&shortest), Some(&longest)) = (lengths.first(), lengths.last()) else {
+        return Vec::new();
+    };
+
+    let mut cuts: Vec<(f64, usize)> = (lengths.len().div_ceil(2)..lengths.len())
+        .filter(|&i| lengths[i] > lengths[i - 1] && lengths[i - 1] > 0.0)
+        .map(|i| (lengths[i] / lengths[i - 1], i))
+        .collect();
+    cuts.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    cuts.iter()
+        .take(count)
+        .map(|&(gap, i)| {
+            let max_edge_length = (lengths[i - 1] + lengths[i]) / 2.0;
+            ThresholdSuggestion {
+                max_edge_length,
+                alpha: max_edge_length / 2.0,
+                chi_lambda: (max_edge_length - shortest) / (longest - shortest),
+                gap,
+                kept: i as f64 / lengths.len() as f64,
+            }
+        })
+        .collect()
+}
+
+fn count_duplicates(points: &[Point]) -> usize {
+    let mut coords: Vec<(f64, f64)> = points.iter().map(|p| (p.x, p.y)).collect();
+    coords.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    coords.windows(2).filter(|w| w[0] == w[1]).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
+
+    #[test]
+    fn test_dataset_stats() {
+        // a 10x10 unit grid plus two duplicated corners
+        let mut points: Vec<Point> = (0..100)
+            .map(|i| Point {
+                x: (i % 10) as f64,
+                y: (i / 10) as f64,
+                id: i,
+            })
+            .collect();
+        points.push(Point {
+            x: 0.0,
+            y: 0.0,
+            id: 100,
+        });
+        points.push(Point {
+            x: 9.0,
+            y: 9.0,
+            id: 101,
+        });
+
+        let stats = dataset_stats(&points);
+        assert_eq!(stats.count, 102);
+        assert_eq!(stats.duplicates, 2);
+        assert!(ulps_eq(stats.bbox.area(), 81.0, DEFAULT_ULPS));
+        // the four duplicated points have a zero nearest neighbour distance
+        assert!((stats.mean_nn_distance - 98.0 / 102.0).abs() < 1E-12);
+        assert!((stats.density - 102.0 / 81.0).abs() < 1E-12);
+        // a grid is more regular than random, so k stays at the minimum
+        assert_eq!(stats.recommended_k, 3);
+
+        // only the 64 interior grid points have their 4th neighbour one unit away
+        let histogram = knn_distance_histogram(&points[..100], 4, 4);
+        assert_eq!(histogram.counts.iter().sum::<usize>(), 100);
+        assert_eq!(histogram.edges.len(), 5);
+        assert!(ulps_eq(histogram.edges[0], 1.0, DEFAULT_ULPS));
+        assert_eq!(histogram.counts[0], 64);
+
+        let empty = dataset_stats(&[]);
+        assert_eq!(empty.count, 0);
+        assert_eq!(empty.recommended_k, 3);
+    }
+
+    #[test]
+    fn test_suggest_thresholds() {
+        // two unit grids 20 apart
+        let points: Vec<Point> = (0..200)
+            .map(|i| Point {
+                x: (i % 10) as f64 + if i < 100 { 0.0 } else { 29.0 },
+                y: (i / 10 % 10) as f64,
+                id: i,
+            })
+            .collect();
+
+        let lengths = delaunay_edge_lengths(&points);
+        let histogram = edge_length_histogram(&points, 10);
+        assert_eq!(histogram.counts.iter().sum::<usize>(), lengths.len());
+        assert!(ulps_eq(histogram.edges[0], 1.0, DEFAULT_ULPS));
+
+        let suggestions = suggest_thresholds(&points, 3);
+        assert_eq!(suggestions.len(), 3);
+        // the gap between the grids comes first, then the one between unit and diagonal edges
+        assert!(suggestions[0].max_edge_length > 2.0 && suggestions[0].max_edge_length < 20.0);
+        assert!(suggestions[0].gap > 10.0);
+        assert!(suggestions[0].kept > 0.9);
+        assert!((suggestions[1].max_edge_length - (1.0 + 2f64.sqrt()) / 2.0).abs() < 1E-12);
+        assert!(ulps_eq(
+            suggestions[1].alpha,
+            suggestions[1].max_edge_length / 2.0,
+            DEFAULT_ULPS
+        ));
+        assert!(suggestions[1].chi_lambda > 0.0 && suggestions[1].chi_lambda < 0.1);
+        assert!(suggestions.windows(2).all(|w| w[0].gap >= w[1].gap));
+
+        assert!(suggest_thresholds(&points[..2], 3).is_empty());
+    }
+}
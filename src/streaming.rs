@@ -0,0 +1,617 @@
+/// Streaming Hull over Unbounded Point Streams
+use crate::point::Point;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// points binned into one grid cell
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    /// points in the cell
+    count: usize,
+    /// id of the first point in the cell
+    id: u64,
+}
+
+/// When a [`StreamingHull`] change callback fires, see [`StreamingHull::on_change`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChangeThresholds {
+    /// the relative change of the area that fires, e.g. 0.1 for 10 %
+    pub area: Option<f64>,
+    /// the number of vertices gained or lost that fires
+    pub vertices: Option<usize>,
+    /// the number of points between checks, as every check traces the hull
+    pub every: u64,
+}
+
+impl Default for ChangeThresholds {
+    /// A 10 % change of the area, checked every 1000 points.
+    fn default() -> Self {
+        ChangeThresholds {
+            area: Some(0.1),
+            vertices: None,
+            every: 1000,
+        }
+    }
+}
+
+/// A change of a [`StreamingHull`], as passed to its change callbacks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HullChange<'a> {
+    /// the hull now
+    pub hull: &'a [Point],
+    /// the area now
+    pub area: f64,
+    /// the area at the last time the callback fired, or 0 before that
+    pub previous_area: f64,
+    /// vertices not in the hull the callback last saw
+    pub added: usize,
+    /// vertices of the hull the callback last saw that are gone
+    pub removed: usize,
+}
+
+/// A change callback with the hull it last fired for.
+struct Watcher {
+    thresholds: ChangeThresholds,
+    callback: Box<dyn FnMut(&HullChange) + Send + Sync>,
+    /// the vertices of the last hull the callback fired for
+    vertices: HashSet<(u64, u64)>,
+    area: f64,
+}
+
+/// the change callbacks of a streaming hull; a clone starts without any
+#[derive(Default)]
+struct Watchers(Vec<Watcher>);
+
+impl Clone for Watchers {
+    fn clone(&self) -> Self {
+        Watchers::default()
+    }
+}
+
+impl fmt::Debug for Watchers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Watchers").field(&self.0.len()).finish()
+    }
+}
+
+/// An approximate concave hull of a point stream, in bounded memory.
+///
+/// Points are summarised in a sparse grid of cells as they arrive, and only the occupied
+/// cells are kept. Once more than `max_cells` cells are occupied, the cells are doubled in
+/// size and merged, so memory stays bounded however long the stream runs, at the cost of a
+/// coarser hull. [`StreamingHull::hull`] traces the occupied cells like
+/// [`crate::grid::grid_hull`] and can be called at any point of the stream.
+///
+/// Points can come from any iterator, including a channel fed by another thread:
+///
+/// ```
+/// let mut streaming = StreamingHull::new(1.0, 100_000);
+/// streaming.extend(receiver.iter().take(batch));
+/// let current = streaming.hull();
+/// ```
+///
+/// Instead of polling the hull, a monitor can register callbacks with
+/// [`StreamingHull::on_change`] that fire when the hull changes enough.
+#[derive(Debug, Clone)]
+pub struct StreamingHull {
+    /// side length of the cells
+    resolution: f64,
+    /// the most occupied cells kept before the grid is coarsened
+    max_cells: usize,
+    /// the corner shared by the cells of every resolution, set by the first point
+    origin: Option<(f64, f64)>,
+    /// the occupied cells by column and row
+    cells: HashMap<(i64, i64), Cell>,
+    /// points consumed so far
+    points: u64,
+    /// the change callbacks
+    watchers: Watchers,
+}
+
+impl StreamingHull {
+    /// An empty streaming hull.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolution` - initial side length of the grid cells.
+    /// * `max_cells` - the most occupied cells to keep; the grid is coarsened beyond that.
+    pub fn new(resolution: f64, max_cells: usize) -> Self {
+        StreamingHull {
+            resolution,
+            max_cells: max_cells.max(1),
+            origin: None,
+            cells: HashMap::new(),
+            points: 0,
+            watchers: Watchers::default(),
+        }
+    }
+
+    /// Registers a callback fired when the hull changes by more than `thresholds`.
+    ///
+    /// Every `thresholds.every` points the hull is traced and compared to the hull the
+    /// callback last fired for (an empty one at first). The callback fires if the area
+    /// changed by more than the `area` fraction or more than `vertices` vertices were gained
+    /// or lost, and the current hull becomes the one compared against. Clones of the
+    /// streaming hull start without callbacks.
+    pub fn on_change(
+        &mut self,
+        thresholds: ChangeThresholds,
+        callback: impl FnMut(&HullChange) + Send + Sync + 'static,
+    ) {
+        self.watchers.0.push(Watcher {
+            thresholds: ChangeThresholds {
+                every: thresholds.every.max(1),
+                ..thresholds
+            },
+            callback: Box::new(callback),
+            vertices: HashSet::new(),
+            area: 0.0,
+        });
+    }
+
+    /// Fires the callbacks that are due and whose thresholds the hull exceeds.
+    fn notify(&mut self) {
+        let points = self.points;
+        if self
+            .watchers
+            .0
+            .iter()
+            .all(|w| !points.is_multiple_of(w.thresholds.every))
+        {
+            return;
+        }
+        let hull = self.hull();
+        let ring = crate::open_ring(&hull);
+        let area = crate::buffer::signed_area(ring).abs();
+        let vertices: HashSet<(u64, u64)> = ring
+            .iter()
+            .map(|p| (p.x.to_bits(), p.y.to_bits()))
+            .collect();
+
+        for watcher in self.watchers.0.iter_mut() {
+            if !points.is_multiple_of(watcher.thresholds.every) {
+                continue;
+            }
+            let added = vertices.difference(&watcher.vertices).count();
+            let removed = watcher.vertices.difference(&vertices).count();
+            let area_changed = watcher
+                .thresholds
+                .area
+                .is_some_and(|fraction| (area - watcher.area).abs() > fraction * watcher.area);
+            let vertices_changed = watcher
+                .thresholds
+                .vertices
+                .is_some_and(|count| added + removed > count);
+            if area_changed || vertices_changed {
+                (watcher.callback)(&HullChange {
+                    hull: &hull,
+                    area,
+                    previous_area: watcher.area,
+                    added,
+                    removed,
+                });
+                watcher.vertices = vertices.clone();
+                watcher.area = area;
+            }
+        }
+    }
+
+    /// Adds a point of the stream; points with a non-finite coordinate are skipped.
+    pub fn push(&mut self, point: Point) {
+        if !(point.x.is_finite() && point.y.is_finite()) {
+            return;
+        }
+        let (ox, oy) = *self.origin.get_or_insert((point.x, point.y));
+        let key = (
+            ((point.x - ox) / self.resolution).floor() as i64,
+            ((point.y - oy) / self.resolution).floor() as i64,
+        );
+        self.cells
+            .entry(key)
+            .or_insert(Cell {
+                count: 0,
+                id: point.id,
+            })
+            .count += 1;
+        self.points += 1;
+
+        while self.cells.len() > self.max_cells {
+            self.coarsen();
+        }
+        if !self.watchers.0.is_empty() {
+            self.notify();
+        }
+    }
+
+    /// Doubles the cell size, merging each 2 x 2 block of cells.
+    fn coarsen(&mut self) {
+        let mut merged: HashMap<(i64, i64), Cell> = HashMap::with_capacity(self.cells.len() / 2);
+        for (&(i, j), cell) in self.cells.iter() {
+            merged
+                .entry((i.div_euclid(2), j.div_euclid(2)))
+                .and_modify(|m| m.count += cell.count)
+                .or_insert(*cell);
+        }
+        self.cells = merged;
+        self.resolution *= 2.0;
+    }
+
+    /// Side length of the cells, which grows as the grid is coarsened.
+    pub fn resolution(&self) -> f64 {
+        self.resolution
+    }
+
+    /// Number of points consumed so far.
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+
+    /// Number of occupied cells, at most `max_cells`.
+    pub fn cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The current approximate hull of the stream.
+    ///
+    /// # Returns
+    ///
+    /// Returns the outline of the occupied cells as [`crate::grid::grid_hull`] does, or an
+    /// empty vector before the first point.
+    pub fn hull(&self) -> Vec<Point> {
+        match self.origin {
+            Some(origin) => outline(
+                &self.cells,
+                origin,
+                self.resolution,
+                self.max_cells.saturating_mul(BOX_CELLS),
+            ),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Extend<Point> for StreamingHull {
+    fn extend<I: IntoIterator<Item = Point>>(&mut self, points: I) {
+        for point in points {
+            self.push(point);
+        }
+    }
+}
+
+/// An approximate concave hull of the last `capacity` points of a stream.
+///
+/// The window is a ring buffer allocated once, so memory is fixed. Each point is binned
+/// into a grid cell of a fixed size, and the point it replaces in the buffer is taken out of
+/// its cell, so a push costs O(1) however long the stream runs. [`WindowedHull::hull`]
+/// traces the occupied cells like [`StreamingHull::hull`].
+#[derive(Debug, Clone)]
+pub struct WindowedHull {
+    /// side length of the cells
+    resolution: f64,
+    /// the corner of the grid, set by the first point
+    origin: Option<(f64, f64)>,
+    /// the points of the window, with their cells
+    window: Vec<(Point, (i64, i64))>,
+    /// the slot of `window` the next point overwrites once it is full
+    next: usize,
+    /// the occupied cells by column and row
+    cells: HashMap<(i64, i64), Cell>,
+}
+
+impl WindowedHull {
+    /// An empty windowed hull.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolution` - side length of the grid cells.
+    /// * `capacity` - the number of most recent points the hull covers.
+    pub fn new(resolution: f64, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        WindowedHull {
+            resolution,
+            origin: None,
+            window: Vec::with_capacity(capacity),
+            next: 0,
+            cells: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Adds a point of the stream, dropping the oldest point if the window is full; points
+    /// with a non-finite coordinate are skipped.
+    pub fn push(&mut self, point: Point) {
+        if !(point.x.is_finite() && point.y.is_finite()) {
+            return;
+        }
+        let (ox, oy) = *self.origin.get_or_insert((point.x, point.y));
+        let key = (
+            ((point.x - ox) / self.resolution).floor() as i64,
+            ((point.y - oy) / self.resolution).floor() as i64,
+        );
+        let cell = self.cells.entry(key).or_insert(Cell { count: 0, id: 0 });
+        cell.count += 1;
+        cell.id = point.id;
+
+        if self.window.len() < self.window.capacity() {
+            self.window.push((point, key));
+            return;
+        }
+        let (_, old) = std::mem::replace(&mut self.window[self.next], (point, key));
+        self.next = (self.next + 1) % self.window.len();
+        if let Some(cell) = self.cells.get_mut(&old) {
+            cell.count -= 1;
+            if cell.count == 0 {
+                self.cells.remove(&old);
+            }
+        }
+    }
+
+    /// The points of the window, oldest first.
+    pub fn points(&self) -> impl Iterator<Item = &Point> {
+        let (newer, older) = self.window.split_at(self.next);
+        older.iter().chain(newer).map(|(p, _)| p)
+    }
+
+    /// Number of points in the window, at most `capacity`.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    /// Whether no point has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.window.is_empty()
+    }
+
+    /// The current approximate hull of the window.
+    ///
+    /// # Returns
+    ///
+    /// Returns the outline of the occupied cells as [`crate::grid::grid_hull`] does, whose
+    /// vertices carry the id of the latest point of their cell, or an empty vector before
+    /// the first point.
+    pub fn hull(&self) -> Vec<Point> {
+        match self.origin {
+            Some(origin) => outline(
+                &self.cells,
+                origin,
+                self.resolution,
+                self.window.capacity().saturating_mul(BOX_CELLS),
+            ),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Extend<Point> for WindowedHull {
+    fn extend<I: IntoIterator<Item = Point>>(&mut self, points: I) {
+        for point in points {
+            self.push(point);
+        }
+    }
+}
+
+/// the most cells of the box an outline is traced over, per cell kept
+const BOX_CELLS: usize = 16;
+
+/// the outline of the occupied `cells` of a grid with its corner at `origin`
+///
+/// The outline is traced over the box of the occupied cells. Cells far apart make a box of
+/// more than `max_box` cells, which are then merged in blocks of 2, 4, 8, ... cells a side
+/// until it fits, so the memory of the trace is bounded however far apart they are.
+fn outline(
+    cells: &HashMap<(i64, i64), Cell>,
+    origin: (f64, f64),
+    resolution: f64,
+    max_box: usize,
+) -> Vec<Point> {
+    let (ox, oy) = origin;
+    let (mut min_i, mut min_j) = (i64::MAX, i64::MAX);
+    let (mut max_i, mut max_j) = (i64::MIN, i64::MIN);
+    for &(i, j) in cells.keys() {
+        (min_i, min_j) = (min_i.min(i), min_j.min(j));
+        (max_i, max_j) = (max_i.max(i), max_j.max(j));
+    }
+
+    // the fewest halvings that fit the box, at most 63, which leave two cells a side
+    let span = |min: i64, max: i64, shift: u32| {
+        ((max >> shift) as i128 - (min >> shift) as i128 + 1) as u128
+    };
+    let shift = (0..63)
+        .find(|&shift| span(min_i, max_i, shift) * span(min_j, max_j, shift) <= max_box as u128)
+        .unwrap_or(63);
+    let merged: HashMap<(i64, i64), Cell>;
+    let cells = if shift == 0 {
+        cells
+    } else {
+        let mut blocks: HashMap<(i64, i64), Cell> = HashMap::with_capacity(cells.len());
+        for (&(i, j), cell) in cells.iter() {
+            blocks
+                .entry((i >> shift, j >> shift))
+                .and_modify(|m| m.count += cell.count)
+                .or_insert(*cell);
+        }
+        merged = blocks;
+        &merged
+    };
+    let resolution = resolution * 2f64.powi(shift as i32);
+    let (min_i, min_j, max_i, max_j) = (
+        min_i >> shift,
+        min_j >> shift,
+        max_i >> shift,
+        max_j >> shift,
+    );
+
+    let nx = (max_i - min_i + 1) as usize;
+    let ny = (max_j - min_j + 1) as usize;
+    let mut count = vec![0usize; nx * ny];
+    let mut ids = vec![0u64; nx * ny];
+    for (&(i, j), cell) in cells.iter() {
+        let c = (j - min_j) as usize * nx + (i - min_i) as usize;
+        count[c] = cell.count;
+        ids[c] = cell.id;
+    }
+    let origin = (
+        ox + min_i as f64 * resolution,
+        oy + min_j as f64 * resolution,
+    );
+    crate::grid::trace(&count, &mut ids, nx, ny, origin, resolution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prepared::PreparedHull;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_streaming_hull() {
+        let mut rng = Rng::new(3);
+        // an annulus sector: points with radius in [5, 10] and angle in [0, 3/2 pi]
+        let mut sample = move |id: u64| {
+            let r = 5.0 + 5.0 * rng.uniform();
+            let t = 1.5 * std::f64::consts::PI * rng.uniform();
+            Point {
+                x: r * t.cos(),
+                y: r * t.sin(),
+                id,
+            }
+        };
+
+        let mut streaming = StreamingHull::new(0.5, 300);
+        assert!(streaming.hull().is_empty());
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let producer = std::thread::spawn(move || {
+            for id in 0..20_000 {
+                sender.send(sample(id)).unwrap();
+            }
+        });
+        streaming.extend(receiver.iter());
+        producer.join().unwrap();
+
+        assert_eq!(streaming.points(), 20_000);
+        assert!(streaming.cells() <= 300);
+        assert!(streaming.resolution() > 0.5);
+
+        let hull = streaming.hull();
+        assert_eq!(hull.first(), hull.last());
+        let prepared = PreparedHull::new(&hull);
+        // the empty centre and the missing quadrant stay outside
+        assert!(!prepared.contains(&Point {
+            x: 0.0,
+            y: 0.0,
+            id: 0,
+        }));
+        assert!(!prepared.contains(&Point {
+            x: 6.0,
+            y: -6.0,
+            id: 0,
+        }));
+        assert!(prepared.contains(&Point {
+            x: -7.5,
+            y: 0.0,
+            id: 0,
+        }));
+    }
+
+    #[test]
+    fn test_streaming_hull_on_change() {
+        use std::sync::{Arc, Mutex};
+
+        // a square growing by one unit every 1000 points
+        let mut rng = Rng::new(9);
+        let mut streaming = StreamingHull::new(0.5, 10_000);
+        let areas = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&areas);
+        let thresholds = ChangeThresholds {
+            area: Some(0.5),
+            ..Default::default()
+        };
+        streaming.on_change(thresholds, move |change| {
+            assert!(change.added > 0);
+            seen.lock().unwrap().push(change.area);
+        });
+        streaming.extend((0..10_000).map(|id| {
+            let side = 1.0 + (id / 1000) as f64;
+            Point {
+                x: side * rng.uniform(),
+                y: side * rng.uniform(),
+                id,
+            }
+        }));
+
+        let areas = areas.lock().unwrap();
+        assert!(areas.len() >= 3 && areas.len() < 10);
+        assert!(areas.windows(2).all(|w| w[1] > 1.5 * w[0]));
+        assert!(format!("{:?}", streaming).contains("Watchers(1)"));
+        assert!(streaming.clone().watchers.0.is_empty());
+    }
+
+    #[test]
+    fn test_far_apart_points() {
+        // two fixes a billion cells apart, and fixes without a position
+        let fixes = [
+            (0.0, 0.0),
+            (1e9, 1e9),
+            (f64::INFINITY, 0.0),
+            (f64::NAN, 1.0),
+        ];
+        let fixes = fixes.iter().enumerate().map(|(id, &(x, y))| Point {
+            x,
+            y,
+            id: id as u64,
+        });
+        let mut streaming = StreamingHull::new(1.0, 100);
+        streaming.extend(fixes.clone());
+        assert_eq!((streaming.points(), streaming.cells()), (2, 2));
+        let mut windowed = WindowedHull::new(1.0, 100);
+        windowed.extend(fixes.chain([Point {
+            x: -1e300,
+            y: 1e300,
+            id: 4,
+        }]));
+        assert_eq!(windowed.len(), 3);
+
+        for hull in [streaming.hull(), windowed.hull()] {
+            assert_eq!(hull.first(), hull.last());
+            assert!(hull.iter().all(|p| p.x.is_finite() && p.y.is_finite()));
+        }
+        let hull = streaming.hull();
+        let prepared = PreparedHull::new(&hull);
+        assert!(prepared.contains(&Point {
+            x: 0.5,
+            y: 0.5,
+            id: 0,
+        }));
+    }
+
+    #[test]
+    fn test_windowed_hull() {
+        // a point drifting right along a unit-wide strip
+        let mut rng = Rng::new(5);
+        let mut windowed = WindowedHull::new(0.5, 500);
+        assert!(windowed.hull().is_empty());
+        windowed.extend((0..5000).map(|id| Point {
+            x: id as f64 / 100.0,
+            y: rng.uniform(),
+            id,
+        }));
+
+        assert_eq!(windowed.len(), 500);
+        let oldest: Vec<u64> = windowed.points().take(2).map(|p| p.id).collect();
+        assert_eq!(oldest, vec![4500, 4501]);
+        let hull = windowed.hull();
+        assert_eq!(hull.first(), hull.last());
+        assert!(hull.iter().all(|p| p.x >= 44.5 && p.x <= 50.5));
+        let prepared = PreparedHull::new(&hull);
+        assert!(prepared.contains(&Point {
+            x: 47.0,
+            y: 0.5,
+            id: 0,
+        }));
+        assert!(!prepared.contains(&Point {
+            x: 20.0,
+            y: 0.5,
+            id: 0,
+        }));
+    }
+}
@@ -0,0 +1,156 @@
+/// Built-in Neighbour Index
+///
+/// Builds without the `kd-tree` feature have no kd-tree to find neighbours in, and use this
+/// index instead: the points sorted along their first coordinate, searched outwards from
+/// the query until the gap along that axis exceeds the k-th distance found. It needs no
+/// dependency and little code to audit, at the cost of queries that scan a slab of the
+/// data rather than a cell of it.
+use std::cmp::Ordering;
+
+/// order of neighbours: by squared distance and then id
+fn by_distance(a: &(f64, u64), b: &(f64, u64)) -> Ordering {
+    a.0.total_cmp(&b.0).then(a.1.cmp(&b.1))
+}
+
+/// Points of any dimension `D` sorted for nearest neighbour queries.
+pub(crate) struct SweepIndex<const D: usize> {
+    /// coordinates and ids, sorted by first coordinate and then id
+    entries: Vec<([f64; D], u64)>,
+    /// whether each entry has been removed; removed entries stay in place to be added back
+    removed: Vec<bool>,
+}
+
+impl<const D: usize> SweepIndex<D> {
+    /// builds the index over `entries` of coordinates and ids
+    pub fn new(mut entries: Vec<([f64; D], u64)>) -> Self {
+        entries.sort_by(|a, b| a.0[0].total_cmp(&b.0[0]).then(a.1.cmp(&b.1)));
+        let removed = vec![false; entries.len()];
+        SweepIndex { entries, removed }
+    }
+
+    /// position of the first entry at or after `(x, id)` in sort order
+    fn position(&self, x: f64, id: u64) -> usize {
+        self.entries
+            .partition_point(|e| e.0[0].total_cmp(&x).then(e.1.cmp(&id)).is_lt())
+    }
+
+    /// the entry with these coordinates and id whose removal flag is `removed`
+    fn find(&self, coordinates: &[f64; D], id: u64, removed: bool) -> Option<usize> {
+        (self.position(coordinates[0], id)..self.entries.len())
+            .take_while(|&i| self.entries[i].0[0] == coordinates[0] && self.entries[i].1 == id)
+            .find(|&i| self.removed[i] == removed && self.entries[i].0 == *coordinates)
+    }
+
+    pub fn add(&mut self, coordinates: &[f64; D], id: u64) {
+        match self.find(coordinates, id, true) {
+            Some(i) => self.removed[i] = false,
+            None => {
+                let i = self.position(coordinates[0], id);
+                self.entries.insert(i, (*coordinates, id));
+                self.removed.insert(i, false);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, coordinates: &[f64; D], id: u64) {
+        if let Some(i) = self.find(coordinates, id, false) {
+            self.removed[i] = true;
+        }
+    }
+
+    /// squared distances and ids of the `k` entries nearest to `query`
+    ///
+    /// Neighbours are ordered by distance and then id, the same order as the kd-tree
+    /// queries of [`crate::index`] return planar neighbours in.
+    pub fn nearest(&self, query: &[f64; D], k: usize) -> Vec<(f64, u64)> {
+        let mut found: Vec<(f64, u64)> = Vec::with_capacity(k + 1);
+        if k == 0 {
+            return found;
+        }
+
+        // `below` is one past the next entry down the axis, `above` the next one up
+        let start = self.entries.partition_point(|e| e.0[0] < query[0]);
+        let (mut below, mut above) = (start, start);
+        loop {
+            let down = (below > 0).then(|| query[0] - self.entries[below - 1].0[0]);
+            let up = (above < self.entries.len()).then(|| self.entries[above].0[0] - query[0]);
+            let (i, gap) = match (down, up) {
+                (Some(down), up) if up.is_none_or(|up| down <= up) => {
+                    below -= 1;
+                    (below, down)
+                }
+                (_, Some(up)) => {
+                    above += 1;
+                    (above - 1, up)
+                }
+                _ => break,
+            };
+            // every entry further along either side is at least `gap` away
+            if found.len() == k && gap * gap > found[k - 1].0 {
+                break;
+            }
+            if self.removed[i] {
+                continue;
+            }
+
+            let (coordinates, id) = &self.entries[i];
+            let distance: f64 = coordinates
+                .iter()
+                .zip(query)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum();
+            let neighbour = (distance, *id);
+            let at = found.partition_point(|f| by_distance(f, &neighbour).is_lt());
+            if at < k {
+                found.insert(at, neighbour);
+                found.truncate(k);
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_sweep_index() {
+        let mut rng = Rng::new(4);
+        // a coarse lattice, so that many neighbours tie
+        let mut entries: Vec<([f64; 2], u64)> = (0..600)
+            .map(|id| {
+                let x = (rng.uniform() * 20.0).floor();
+                let y = (rng.uniform() * 20.0).floor();
+                ([x, y], id)
+            })
+            .collect();
+        let mut index = SweepIndex::new(entries.clone());
+        for (coordinates, id) in entries.iter().step_by(3) {
+            index.remove(coordinates, *id);
+        }
+        let (coordinates, id) = entries[3];
+        index.add(&coordinates, id);
+        index.add(&[25.0, 25.0], 600);
+        let mut live: Vec<([f64; 2], u64)> = entries
+            .drain(..)
+            .enumerate()
+            .filter(|&(i, _)| i % 3 != 0 || i == 3)
+            .map(|(_, entry)| entry)
+            .collect();
+        live.push(([25.0, 25.0], 600));
+
+        for query in [[10.0, 10.0], [0.0, 19.0], [10.5, 3.25], [30.0, -4.0]] {
+            let mut expected: Vec<(f64, u64)> = live
+                .iter()
+                .map(|(c, id)| ((c[0] - query[0]).powi(2) + (c[1] - query[1]).powi(2), *id))
+                .collect();
+            expected.sort_by(by_distance);
+            for k in [1, 6, 40] {
+                assert_eq!(index.nearest(&query, k), expected[..k]);
+            }
+        }
+        assert!(index.nearest(&[0.0, 0.0], 0).is_empty());
+    }
+}
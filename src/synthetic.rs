@@ -0,0 +1,135 @@
+/// Synthetic Point Clouds with a Known Boundary
+use crate::point::{BoundingBox, Point};
+use crate::prepared::PreparedHull;
+
+/// A small deterministic generator (SplitMix64), so samples are reproducible from a seed.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// uniform in [0, 1)
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// standard normal (Box-Muller)
+    pub fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.uniform();
+        let v = self.uniform();
+        (-2.0 * u.ln()).sqrt() * (2.0 * std::f64::consts::PI * v).cos()
+    }
+}
+
+/// Samples a point cloud whose true boundary is `polygon`.
+///
+/// Half of the points (rounded down) are spread uniformly by arc length along the boundary
+/// and displaced by isotropic Gaussian noise; the rest are uniform over the interior. A
+/// hull computed from the sample can then be scored against `polygon` as ground truth.
+///
+/// # Arguments
+///
+/// * `polygon` - the ground truth boundary, open or closed.
+/// * `n` - number of points to generate.
+/// * `noise` - standard deviation of the displacement of the boundary points.
+/// * `seed` - seed of the generator; equal seeds give equal samples.
+///
+/// # Returns
+///
+/// Returns the boundary points followed by the interior points, with ids `0..n`. A polygon
+/// with fewer than three vertices has no interior and gives an empty sample.
+pub fn sample_polygon_boundary_and_interior(
+    polygon: &[Point],
+    n: usize,
+    noise: f64,
+    seed: u64,
+) -> Vec<Point> {
+    let ring = crate::open_ring(polygon);
+    if ring.len() < 3 {
+        return Vec::new();
+    }
+    let mut rng = Rng::new(seed);
+    let mut points = Vec::with_capacity(n);
+
+    // cumulative edge lengths, for sampling by arc length
+    let mut lengths = Vec::with_capacity(ring.len());
+    let mut perimeter = 0.0;
+    for i in 0..ring.len() {
+        let (a, b) = (&ring[i], &ring[(i + 1) % ring.len()]);
+        perimeter += (b.x - a.x).hypot(b.y - a.y);
+        lengths.push(perimeter);
+    }
+
+    for _ in 0..n / 2 {
+        let s = rng.uniform() * perimeter;
+        let e = lengths.partition_point(|&l| l <= s).min(ring.len() - 1);
+        let (a, b) = (&ring[e], &ring[(e + 1) % ring.len()]);
+        let start = if e == 0 { 0.0 } else { lengths[e - 1] };
+        let t = (s - start) / (lengths[e] - start).max(f64::MIN_POSITIVE);
+        points.push(Point {
+            x: a.x + t * (b.x - a.x) + noise * rng.normal(),
+            y: a.y + t * (b.y - a.y) + noise * rng.normal(),
+            id: points.len() as u64,
+        });
+    }
+
+    // rejection sampling from the bounding box
+    let prepared = PreparedHull::new(ring);
+    let bbox = BoundingBox::from_points(ring);
+    while points.len() < n {
+        let candidate = Point {
+            x: bbox.min_x + rng.uniform() * bbox.width(),
+            y: bbox.min_y + rng.uniform() * bbox.height(),
+            id: points.len() as u64,
+        };
+        if prepared.contains(&candidate) {
+            points.push(candidate);
+        }
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_polygon_boundary_and_interior() {
+        let polygon: Vec<Point> = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0)]
+            .iter()
+            .map(|&(x, y)| Point { x, y, id: 0 })
+            .collect();
+        let prepared = PreparedHull::new(&polygon);
+
+        let points = sample_polygon_boundary_and_interior(&polygon, 1001, 0.0, 3);
+        assert_eq!(points.len(), 1001);
+        assert!(points.iter().enumerate().all(|(i, p)| p.id == i as u64));
+        // noise-free boundary points lie on the boundary, the rest inside
+        assert!(points[..500].iter().all(|p| prepared.distance(p) < 1E-9));
+        assert!(points[500..].iter().all(|p| prepared.contains(p)));
+
+        assert_eq!(
+            sample_polygon_boundary_and_interior(&polygon, 100, 0.1, 3),
+            sample_polygon_boundary_and_interior(&polygon, 100, 0.1, 3)
+        );
+        let noisy = sample_polygon_boundary_and_interior(&polygon, 2000, 0.1, 4);
+        let spread = noisy[..1000]
+            .iter()
+            .map(|p| prepared.distance(p).powi(2))
+            .sum::<f64>()
+            / 1000.0;
+        assert!(spread.sqrt() > 0.05 && spread.sqrt() < 0.15);
+        assert!(sample_polygon_boundary_and_interior(&polygon[..2], 10, 0.0, 0).is_empty());
+    }
+}
@@ -0,0 +1,181 @@
+/// Divide-and-Conquer Hulls over Tiles
+use crate::error::ConcaveHullError;
+use crate::point::{BoundingBox, Point};
+use crate::prepared::PreparedHull;
+
+use std::collections::HashSet;
+
+/// the most times the merge is repeated with the points the merged hull left out
+const MAX_REPAIRS: usize = 4;
+
+/// Computes an approximate concave hull of a massive dataset by hulling tiles and merging.
+///
+/// The bounding box is split into `tiles` x `tiles` equal tiles and the concave hull of
+/// each tile is computed separately, on all available threads with the `parallel` feature.
+/// Only the tile hull vertices, typically a small share of the points, go into the final
+/// concave hull, so a run that is intractable on the full dataset finishes on its
+/// boundary. Tiles whose hull fails, or which have too few points for one, contribute all
+/// their points.
+///
+/// The merged hull encloses every tile hull vertex, but can cut across the inside of a tile
+/// hull, since the merge doesn't see the points there. So it is checked against every
+/// point, and the points it leaves out join the merge, which is repeated up to four times;
+/// if points are still outside after that, the last merged hull is returned anyway.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud, with unique ids.
+/// * `tiles` - number of tiles along each side of the bounding box.
+/// * `k` - number of nearest neighbors, for the tiles and the merge.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+///
+/// # Returns
+///
+/// Returns the merged hull, or the error, as [`crate::concave_hull`] does.
+pub fn concave_hull_tiled(
+    dataset: &[Point],
+    tiles: usize,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Point>, ConcaveHullError> {
+    crate::check_input(dataset, k)?;
+    let tiles = tiles.max(1);
+    let bbox = BoundingBox::from_points(dataset);
+    let (width, height) = (
+        bbox.width().max(f64::MIN_POSITIVE),
+        bbox.height().max(f64::MIN_POSITIVE),
+    );
+
+    let mut binned: Vec<Vec<Point>> = vec![Vec::new(); tiles * tiles];
+    for p in dataset.iter() {
+        let i = (((p.x - bbox.min_x) / width * tiles as f64) as usize).min(tiles - 1);
+        let j = (((p.y - bbox.min_y) / height * tiles as f64) as usize).min(tiles - 1);
+        binned[j * tiles + i].push(p.clone());
+    }
+    binned.retain(|tile| !tile.is_empty());
+
+    let mut boundary: Vec<Point> = hull_tiles(binned, k, iterate)?
+        .into_iter()
+        .flatten()
+        .collect();
+    // closed rings repeat their first vertex
+    boundary.sort_by_key(|p| p.id);
+    boundary.dedup_by_key(|p| p.id);
+
+    let mut hull = crate::concave_hull(&mut boundary.clone(), k, iterate)?;
+    for _ in 0..MAX_REPAIRS {
+        let prepared = PreparedHull::new(&hull);
+        let vertices: HashSet<u64> = hull.iter().map(|p| p.id).collect();
+        let outside: Vec<Point> = dataset
+            .iter()
+            .filter(|p| !vertices.contains(&p.id) && !prepared.contains(p))
+            .cloned()
+            .collect();
+        if outside.is_empty() {
+            break;
+        }
+        boundary.extend(outside);
+        hull = crate::concave_hull(&mut boundary.clone(), k, iterate)?;
+    }
+    Ok(hull)
+}
+
+/// the hull vertices of each tile, or all of its points if it has too few for a hull or
+/// none of the `k` tried gave one
+fn hull_tile(tile: Vec<Point>, k: usize, iterate: bool) -> Result<Vec<Point>, ConcaveHullError> {
+    match crate::concave_hull(&mut tile.clone(), k, iterate) {
+        Err(
+            ConcaveHullError::InsufficientPoints { .. } | ConcaveHullError::NoValidHullFound { .. },
+        ) => Ok(tile),
+        result => result,
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+fn hull_tiles(
+    tiles: Vec<Vec<Point>>,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Vec<Point>>, ConcaveHullError> {
+    tiles
+        .into_iter()
+        .map(|tile| hull_tile(tile, k, iterate))
+        .collect()
+}
+
+#[cfg(feature = "parallel")]
+fn hull_tiles(
+    tiles: Vec<Vec<Point>>,
+    k: usize,
+    iterate: bool,
+) -> Result<Vec<Vec<Point>>, ConcaveHullError> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    // each thread takes every `threads`-th tile, so dense and sparse tiles mix
+    let mut shares: Vec<Vec<Vec<Point>>> = vec![Vec::new(); threads];
+    for (i, tile) in tiles.into_iter().enumerate() {
+        shares[i % threads].push(tile);
+    }
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = shares
+            .into_iter()
+            .map(|share| {
+                scope.spawn(move || {
+                    share
+                        .into_iter()
+                        .map(|tile| hull_tile(tile, k, iterate))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_concave_hull_tiled() {
+        // a 10 x 10 square with a 4 x 10 notch cut into its top
+        let mut rng = Rng::new(12);
+        let mut points = Vec::new();
+        while points.len() < 6000 {
+            let (x, y) = (10.0 * rng.uniform(), 10.0 * rng.uniform());
+            if !(3.0..7.0).contains(&x) || y < 4.0 {
+                points.push(Point {
+                    x,
+                    y,
+                    id: points.len() as u64,
+                });
+            }
+        }
+
+        let hull = concave_hull_tiled(&points, 4, 5, true).unwrap();
+        assert_eq!(hull.first(), hull.last());
+        let prepared = PreparedHull::new(&hull);
+        let outside = points
+            .iter()
+            .filter(|p| !prepared.contains(p) && hull.iter().all(|h| h.id != p.id))
+            .count();
+        assert_eq!(outside, 0);
+        assert!(!prepared.contains(&Point {
+            x: 5.0,
+            y: 8.0,
+            id: 0,
+        }));
+
+        assert_eq!(
+            concave_hull_tiled(&[], 4, 5, true),
+            Err(ConcaveHullError::InsufficientPoints {
+                required: 6,
+                found: 0
+            })
+        );
+    }
+}
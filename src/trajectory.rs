@@ -0,0 +1,134 @@
+/// Sliding Time-Window Hulls for Trajectories
+use crate::error::ConcaveHullError;
+use crate::pipeline::HullConfig;
+use crate::point::Point;
+
+/// The hull of the fixes of one time window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowHull {
+    /// start of the window, inclusive
+    pub start: f64,
+    /// end of the window, exclusive
+    pub end: f64,
+    /// number of fixes in the window
+    pub points: usize,
+    /// hull of the fixes, or why there is none, e.g. too few fixes
+    pub hull: Result<Vec<Point>, ConcaveHullError>,
+}
+
+/// Computes the hulls of a trajectory over sliding time windows.
+///
+/// Windows of length `window` start at the first timestamp and then every `stride`, until
+/// a window starts after the last timestamp. With `stride` equal to `window` the windows
+/// tile the trajectory, e.g. one home range per week; with a smaller stride they overlap
+/// and show how the range shifts.
+///
+/// # Arguments
+///
+/// * `points` - the fixes of the trajectory, in any order.
+/// * `times` - the timestamp of each fix, in any unit.
+/// * `window` - length of each window, in the unit of `times`.
+/// * `stride` - time between the starts of consecutive windows, in the unit of `times`.
+/// * `config` - parameters of the hull of each window.
+///
+/// # Returns
+///
+/// Returns one `WindowHull` per window, in time order. Returns an empty vector if the
+/// lengths of `points` and `times` differ, a timestamp is not finite, or `window` or
+/// `stride` is not positive, or `stride` is infinite.
+pub fn window_hulls(
+    points: &[Point],
+    times: &[f64],
+    window: f64,
+    stride: f64,
+    config: &HullConfig,
+) -> Vec<WindowHull> {
+    if points.len() != times.len()
+        || points.is_empty()
+        || !(window > 0.0 && stride > 0.0 && stride.is_finite())
+        || times.iter().any(|t| !t.is_finite())
+    {
+        return Vec::new();
+    }
+
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| times[a].total_cmp(&times[b]));
+    let (first, last) = (times[order[0]], times[order[order.len() - 1]]);
+
+    let mut hulls = Vec::new();
+    // the fixes of the current window are order[from..to]
+    let (mut from, mut to) = (0, 0);
+    let mut i = 0;
+    loop {
+        let start = first + i as f64 * stride;
+        if start > last {
+            break;
+        }
+        let end = start + window;
+        while from < order.len() && times[order[from]] < start {
+            from += 1;
+        }
+        to = to.max(from);
+        while to < order.len() && times[order[to]] < end {
+            to += 1;
+        }
+
+        let fixes: Vec<Point> = order[from..to].iter().map(|&f| points[f].clone()).collect();
+        hulls.push(WindowHull {
+            start,
+            end,
+            points: fixes.len(),
+            hull: config.hull(&fixes),
+        });
+        i += 1;
+    }
+    hulls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_window_hulls() {
+        // an animal ranging around (0, 0) for ten days, then around (100, 0) for ten more
+        let mut rng = Rng::new(11);
+        let (mut points, mut times) = (Vec::new(), Vec::new());
+        for id in 0..400 {
+            let time = id as f64 * 0.05;
+            let centre = if time < 10.0 { 0.0 } else { 100.0 };
+            points.push(Point {
+                x: centre + 10.0 * rng.uniform(),
+                y: 10.0 * rng.uniform(),
+                id,
+            });
+            times.push(time);
+        }
+        // out of order input
+        points.reverse();
+        times.reverse();
+
+        let hulls = window_hulls(&points, &times, 10.0, 5.0, &HullConfig::default());
+        assert_eq!(hulls.len(), 4);
+        assert!(ulps_eq(hulls[0].start, 0.0, DEFAULT_ULPS));
+        assert!(ulps_eq(hulls[0].end, 10.0, DEFAULT_ULPS));
+        assert_eq!(hulls[0].points, 200);
+        assert!(hulls[0].hull.as_ref().unwrap().iter().all(|p| p.x < 50.0));
+        assert!(hulls[2].hull.as_ref().unwrap().iter().all(|p| p.x > 50.0));
+        // the window straddling the move spans both ranges
+        assert!(hulls[1].hull.as_ref().unwrap().iter().any(|p| p.x < 50.0));
+        assert!(hulls[1].hull.as_ref().unwrap().iter().any(|p| p.x > 50.0));
+        assert_eq!(hulls[3].points, 100);
+
+        assert!(window_hulls(&points, &times[1..], 10.0, 5.0, &HullConfig::default()).is_empty());
+        assert!(
+            window_hulls(&points, &times, 10.0, f64::INFINITY, &HullConfig::default()).is_empty()
+        );
+        for time in [f64::NAN, f64::INFINITY] {
+            times[7] = time;
+            assert!(window_hulls(&points, &times, 10.0, 5.0, &HullConfig::default()).is_empty());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+/// Bulk Affine Transforms
+///
+/// Preprocessing before a hull, e.g. centring a survey or converting units, moves every
+/// point the same way. These apply one transform to a whole slice in a plain loop over the
+/// coordinates, which the compiler vectorises, and with the `parallel` feature split large
+/// slices among threads. Ids are left as they are.
+use crate::point::Point;
+
+/// below this many points a slice is transformed on the calling thread
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN: usize = 1 << 16;
+
+/// Applies `map` to every point.
+#[cfg(not(feature = "parallel"))]
+fn apply(points: &mut [Point], map: impl Fn(&mut Point) + Sync) {
+    points.iter_mut().for_each(map);
+}
+
+/// Applies `map` to every point, in contiguous chunks on several threads for large slices.
+#[cfg(feature = "parallel")]
+fn apply(points: &mut [Point], map: impl Fn(&mut Point) + Sync) {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if points.len() < PARALLEL_MIN || threads == 1 {
+        points.iter_mut().for_each(map);
+        return;
+    }
+    let map = &map;
+    std::thread::scope(|scope| {
+        for chunk in points.chunks_mut(points.len().div_ceil(threads)) {
+            scope.spawn(move || chunk.iter_mut().for_each(map));
+        }
+    });
+}
+
+/// Moves every point by `(dx, dy)`.
+pub fn translate(points: &mut [Point], dx: f64, dy: f64) {
+    apply(points, |p| {
+        p.x += dx;
+        p.y += dy;
+    });
+}
+
+/// Scales every point by `sx` along x and `sy` along y, about the origin.
+///
+/// Scale factors of a unit conversion, e.g. `0.3048` from feet to metres, go in both.
+pub fn scale(points: &mut [Point], sx: f64, sy: f64) {
+    apply(points, |p| {
+        p.x *= sx;
+        p.y *= sy;
+    });
+}
+
+/// Rotates every point counterclockwise about a centre.
+///
+/// # Arguments
+///
+/// * `points` - the points to rotate in place.
+/// * `angle` - the rotation in radians, counterclockwise for positive angles.
+/// * `centre` - the fixed point of the rotation as `(x, y)`.
+pub fn rotate(points: &mut [Point], angle: f64, centre: (f64, f64)) {
+    let (s, c) = angle.sin_cos();
+    let (cx, cy) = centre;
+    apply(points, |p| {
+        let (x, y) = (p.x - cx, p.y - cy);
+        p.x = cx + x * c - y * s;
+        p.y = cy + x * s + y * c;
+    });
+}
+
+/// Moves the points so that their mean is at the origin.
+///
+/// # Returns
+///
+/// Returns the mean the points had as `(x, y)`, to translate hulls of the centred points
+/// back by, or `(0, 0)` for no points, which are left as they are.
+pub fn centre(points: &mut [Point]) -> (f64, f64) {
+    if points.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = points.len() as f64;
+    let (sx, sy) = points
+        .iter()
+        .fold((0.0, 0.0), |(sx, sy), p| (sx + p.x, sy + p.y));
+    let mean = (sx / n, sy / n);
+    translate(points, -mean.0, -mean.1);
+    mean
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{ulps_eq, DEFAULT_ULPS};
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_transforms() {
+        // enough points to be split among threads with the `parallel` feature
+        let mut rng = Rng::new(21);
+        let original: Vec<Point> = (0..70_000)
+            .map(|id| Point {
+                x: 10.0 + rng.uniform(),
+                y: -5.0 + rng.uniform(),
+                id,
+            })
+            .collect();
+        let close = |a: &Point, b: &Point| {
+            a.id == b.id && (a.x - b.x).abs() < 1E-9 && (a.y - b.y).abs() < 1E-9
+        };
+
+        let mut points = original.clone();
+        translate(&mut points, 1.0, 2.0);
+        scale(&mut points, 2.0, 0.5);
+        let expected = original.iter().map(|p| Point {
+            x: 2.0 * (p.x + 1.0),
+            y: 0.5 * (p.y + 2.0),
+            id: p.id,
+        });
+        assert!(points.iter().zip(expected).all(|(a, b)| close(a, &b)));
+
+        // a quarter turn about (10, -5) and back
+        let mut points = original.clone();
+        rotate(&mut points, std::f64::consts::FRAC_PI_2, (10.0, -5.0));
+        let p = &original[0];
+        let turned = Point {
+            x: 10.0 - (p.y + 5.0),
+            y: -5.0 + (p.x - 10.0),
+            id: p.id,
+        };
+        assert!(close(&points[0], &turned));
+        rotate(&mut points, -std::f64::consts::FRAC_PI_2, (10.0, -5.0));
+        assert!(points.iter().zip(original.iter()).all(|(a, b)| close(a, b)));
+
+        let mut points = original.clone();
+        let (mx, my) = centre(&mut points);
+        assert!((mx - 10.5).abs() < 0.01 && (my + 4.5).abs() < 0.01);
+        let (cx, cy) = centre(&mut points);
+        assert!(cx.abs() < 1E-9 && cy.abs() < 1E-9);
+        let (cx, cy) = centre(&mut []);
+        assert!(ulps_eq(cx, 0.0, DEFAULT_ULPS) && ulps_eq(cy, 0.0, DEFAULT_ULPS));
+    }
+}
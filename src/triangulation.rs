@@ -0,0 +1,452 @@
+/// Delaunay and Polygon Triangulation
+use crate::point::{BoundingBox, Point};
+
+/// A triangulation of a point set.
+///
+/// Triangles are counter-clockwise index triples into `points`. Edge `i` of a triangle is
+/// the edge opposite its vertex `i`, and `adjacent[t][i]` is the triangle on the other side
+/// of that edge, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangulation {
+    /// the triangulated points, in input order
+    pub points: Vec<Point>,
+    /// counter-clockwise vertex indices of every triangle
+    pub triangles: Vec<[usize; 3]>,
+    /// neighbouring triangle across the edge opposite each vertex
+    pub adjacent: Vec<[Option<usize>; 3]>,
+}
+
+impl Triangulation {
+    /// the two endpoints of edge `i` of triangle `t`, counter-clockwise
+    pub fn edge(&self, t: usize, i: usize) -> (usize, usize) {
+        let tri = self.triangles[t];
+        (tri[(i + 1) % 3], tri[(i + 2) % 3])
+    }
+
+    /// every undirected edge once, as (smaller, larger) index pairs
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = Vec::with_capacity(self.triangles.len() * 3 / 2 + 1);
+        for t in 0..self.triangles.len() {
+            for i in 0..3 {
+                let (a, b) = self.edge(t, i);
+                // interior edges are seen from both sides; keep the one with a < b
+                if a < b || self.adjacent[t][i].is_none() {
+                    edges.push((a.min(b), a.max(b)));
+                }
+            }
+        }
+        edges
+    }
+
+    /// circumcentre of triangle `t`
+    pub fn circumcenter(&self, t: usize) -> Point {
+        let [a, b, c] = self.triangles[t].map(|i| &self.points[i]);
+        circumcenter(a, b, c)
+    }
+}
+
+/// Computes the Delaunay triangulation of `points` (Bowyer-Watson).
+///
+/// Points are inserted in Hilbert-curve order and located by walking from the previous
+/// insertion, so the expected cost stays close to O(n log n). Exactly duplicated points are
+/// only triangulated once; the copies are left out of every triangle.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+///
+/// # Returns
+///
+/// Returns the `Triangulation`, which has no triangles for fewer than 3 distinct,
+/// non-collinear points.
+pub fn delaunay(points: &[Point]) -> Triangulation {
+    let n = points.len();
+    let bbox = BoundingBox::from_points(points);
+    let size = bbox.width().max(bbox.height()).max(1E-9);
+    let (cx, cy) = (
+        (bbox.min_x + bbox.max_x) / 2.0,
+        (bbox.min_y + bbox.max_y) / 2.0,
+    );
+
+    // the working vertex list ends with a super triangle enclosing everything
+    let mut vertices: Vec<(f64, f64)> = points.iter().map(|p| (p.x, p.y)).collect();
+    let big = 1E3 * size;
+    vertices.push((cx - big, cy - big));
+    vertices.push((cx + big, cy - big));
+    vertices.push((cx, cy + big));
+
+    let mut triangles = vec![[n, n + 1, n + 2]];
+    let mut adjacent: Vec<[Option<usize>; 3]> = vec![[None; 3]];
+    let mut last = 0;
+
+    // scratch space reused between insertions
+    let mut bad: Vec<usize> = Vec::new();
+    let mut is_bad: Vec<bool> = vec![false];
+    let mut boundary: Vec<(usize, usize, Option<usize>)> = Vec::new();
+
+    for i in hilbert_order(points, &bbox) {
+        let p = vertices[i];
+        let t = locate(&vertices, &triangles, &adjacent, last, p);
+        if triangles[t].iter().any(|&v| vertices[v] == p) {
+            continue;
+        }
+
+        // grow the cavity of triangles whose circumcircle holds p
+        bad.clear();
+        bad.push(t);
+        is_bad[t] = true;
+        let mut next = 0;
+        while next < bad.len() {
+            let b = bad[next];
+            next += 1;
+            for neighbour in adjacent[b].iter().flatten() {
+                let [u, v, w] = triangles[*neighbour];
+                if !is_bad[*neighbour] && in_circle(vertices[u], vertices[v], vertices[w], p) > 0.0
+                {
+                    is_bad[*neighbour] = true;
+                    bad.push(*neighbour);
+                }
+            }
+        }
+
+        boundary.clear();
+        for &b in bad.iter() {
+            for e in 0..3 {
+                let outside = adjacent[b][e];
+                if outside.is_none_or(|o| !is_bad[o]) {
+                    let tri = triangles[b];
+                    boundary.push((tri[(e + 1) % 3], tri[(e + 2) % 3], outside));
+                }
+            }
+        }
+
+        // fan the cavity boundary around p, reusing the freed slots first
+        let mut created = Vec::with_capacity(boundary.len());
+        for (j, &(a, b, outside)) in boundary.iter().enumerate() {
+            let slot = if j < bad.len() {
+                is_bad[bad[j]] = false;
+                bad[j]
+            } else {
+                triangles.push([0; 3]);
+                adjacent.push([None; 3]);
+                is_bad.push(false);
+                triangles.len() - 1
+            };
+            triangles[slot] = [a, b, i];
+            adjacent[slot] = [None, None, outside];
+            if let Some(o) = outside {
+                let e = (0..3)
+                    .find(|&e| triangles[o][(e + 1) % 3] == b && triangles[o][(e + 2) % 3] == a)
+                    .expect("neighbour shares the cavity edge");
+                adjacent[o][e] = Some(slot);
+            }
+            created.push(slot);
+        }
+        for &t in created.iter() {
+            let b = triangles[t][1];
+            let next = *created
+                .iter()
+                .find(|&&u| triangles[u][0] == b)
+                .expect("cavity boundary is a closed loop");
+            adjacent[t][0] = Some(next);
+            adjacent[next][1] = Some(t);
+        }
+        last = created[0];
+    }
+
+    // drop every triangle touching the super triangle and renumber the rest
+    let mut remap = vec![None; triangles.len()];
+    let mut kept = 0;
+    for (t, tri) in triangles.iter().enumerate() {
+        if tri.iter().all(|&v| v < n) {
+            remap[t] = Some(kept);
+            kept += 1;
+        }
+    }
+    let mut result = Triangulation {
+        points: points.to_vec(),
+        triangles: Vec::with_capacity(kept),
+        adjacent: Vec::with_capacity(kept),
+    };
+    for (t, tri) in triangles.iter().enumerate() {
+        if remap[t].is_some() {
+            result.triangles.push(*tri);
+            result
+                .adjacent
+                .push(adjacent[t].map(|a| a.and_then(|a| remap[a])));
+        }
+    }
+
+    result
+}
+
+/// Splits a simple polygon, such as a concave hull, into triangles (ear clipping).
+///
+/// Repeatedly cuts off an ear, a convex corner whose triangle holds no other vertex, so
+/// the triangles cover exactly the polygon: they can be rendered directly, or picked in
+/// proportion to their area for uniform sampling. Collinear vertices are dropped without a
+/// triangle. The cost is O(n^2) in the number of vertices.
+///
+/// # Arguments
+///
+/// * `hull` - the polygon, open or closed, in either orientation.
+///
+/// # Returns
+///
+/// Returns `n - 2` counter-clockwise triangles for a polygon of `n` vertices, fewer if
+/// collinear vertices were dropped, and none for fewer than 3 vertices.
+pub fn triangulate(hull: &[Point]) -> Vec<[Point; 3]> {
+    let ring = crate::open_ring(hull);
+    let xy = |i: usize| (ring[i].x, ring[i].y);
+    let mut remaining: Vec<usize> = (0..ring.len()).collect();
+    if crate::buffer::signed_area(ring) < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    // corners looked at since the last one was cut off
+    let (mut i, mut misses) = (0, 0);
+    while remaining.len() >= 3 {
+        let n = remaining.len();
+        i %= n;
+        let (a, b, c) = (
+            remaining[(i + n - 1) % n],
+            remaining[i],
+            remaining[(i + 1) % n],
+        );
+        let turn = orient(xy(a), xy(b), xy(c));
+        // a corner is an ear if no other vertex lies in or on its triangle; copies of its
+        // own vertices, where the polygon touches itself, don't count
+        let ear = turn > 0.0
+            && remaining.iter().all(|&v| {
+                let p = xy(v);
+                p == xy(a)
+                    || p == xy(b)
+                    || p == xy(c)
+                    || orient(xy(a), xy(b), p) < 0.0
+                    || orient(xy(b), xy(c), p) < 0.0
+                    || orient(xy(c), xy(a), p) < 0.0
+            });
+        // a polygon that is not quite simple may have no ear left; cut the corner anyway
+        if turn == 0.0 || ear || misses >= n {
+            if turn > 0.0 {
+                triangles.push([ring[a].clone(), ring[b].clone(), ring[c].clone()]);
+            }
+            remaining.remove(i);
+            i = i.saturating_sub(1);
+            misses = 0;
+        } else {
+            i += 1;
+            misses += 1;
+        }
+    }
+    triangles
+}
+
+/// Finds a triangle containing `p` by walking across edges that face it.
+fn locate(
+    vertices: &[(f64, f64)],
+    triangles: &[[usize; 3]],
+    adjacent: &[[Option<usize>; 3]],
+    start: usize,
+    p: (f64, f64),
+) -> usize {
+    let mut t = start;
+    // walks can cycle on degenerate input, so bound them and fall back to a scan
+    for _ in 0..triangles.len() {
+        let tri = triangles[t];
+        let step = (0..3).find(|&e| {
+            let (a, b) = (vertices[tri[(e + 1) % 3]], vertices[tri[(e + 2) % 3]]);
+            orient(a, b, p) < 0.0
+        });
+        match step.map(|e| adjacent[t][e]) {
+            None => return t,
+            Some(Some(next)) => t = next,
+            Some(None) => break,
+        }
+    }
+
+    (0..triangles.len())
+        .find(|&t| {
+            let [a, b, c] = triangles[t].map(|v| vertices[v]);
+            orient(a, b, p) >= 0.0 && orient(b, c, p) >= 0.0 && orient(c, a, p) >= 0.0
+        })
+        .unwrap_or(t)
+}
+
+/// Orders point indices along a Hilbert curve over their bounding box.
+fn hilbert_order(points: &[Point], bbox: &BoundingBox) -> Vec<usize> {
+    const SIDE: f64 = 65535.0;
+    let scale_x = SIDE / bbox.width().max(f64::MIN_POSITIVE);
+    let scale_y = SIDE / bbox.height().max(f64::MIN_POSITIVE);
+
+    let mut keyed: Vec<(u64, usize)> = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let x = ((p.x - bbox.min_x) * scale_x) as u32;
+            let y = ((p.y - bbox.min_y) * scale_y) as u32;
+            (hilbert_index(x, y), i)
+        })
+        .collect();
+    keyed.sort_unstable();
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Position of cell (x, y) along a 2^16 x 2^16 Hilbert curve.
+fn hilbert_index(mut x: u32, mut y: u32) -> u64 {
+    let mut d = 0u64;
+    let mut s = 1u32 << 15;
+    while s > 0 {
+        let rx = u32::from(x & s > 0);
+        let ry = u32::from(y & s > 0);
+        d += u64::from(s) * u64::from(s) * u64::from((3 * rx) ^ ry);
+        if ry == 0 {
+            if rx == 1 {
+                x = 0xFFFF - x;
+                y = 0xFFFF - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s >>= 1;
+    }
+    d
+}
+
+/// Twice the signed area of (a, b, c): positive when counter-clockwise.
+fn orient(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Positive when `p` lies inside the circumcircle of the counter-clockwise (a, b, c).
+fn in_circle(a: (f64, f64), b: (f64, f64), c: (f64, f64), p: (f64, f64)) -> f64 {
+    let (adx, ady) = (a.0 - p.0, a.1 - p.1);
+    let (bdx, bdy) = (b.0 - p.0, b.1 - p.1);
+    let (cdx, cdy) = (c.0 - p.0, c.1 - p.1);
+
+    (adx * adx + ady * ady) * (bdx * cdy - cdx * bdy)
+        - (bdx * bdx + bdy * bdy) * (adx * cdy - cdx * ady)
+        + (cdx * cdx + cdy * cdy) * (adx * bdy - bdx * ady)
+}
+
+/// Centre of the circle through `a`, `b` and `c`.
+pub(crate) fn circumcenter(a: &Point, b: &Point, c: &Point) -> Point {
+    let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+    let (a2, b2, c2) = (
+        a.x * a.x + a.y * a.y,
+        b.x * b.x + b.y * b.y,
+        c.x * c.x + c.y * c.y,
+    );
+    Point {
+        x: (a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y)) / d,
+        y: (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d,
+        id: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delaunay() {
+        let mut seed = 17u64;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let mut points: Vec<Point> = (0..400)
+            .map(|i| Point {
+                x: next(),
+                y: next(),
+                id: i,
+            })
+            .collect();
+        points.push(points[0].clone());
+
+        let tri = delaunay(&points);
+        let xy = |i: usize| (points[i].x, points[i].y);
+
+        // at most 2n - 5 triangles, all counter-clockwise, none with a point in its circumcircle
+        assert!(tri.triangles.len() > 400 && tri.triangles.len() <= 2 * 400 - 5);
+        for t in tri.triangles.iter() {
+            assert!(orient(xy(t[0]), xy(t[1]), xy(t[2])) > 0.0);
+            assert!(t.iter().all(|&v| v < 400));
+            for p in 0..400 {
+                if !t.contains(&p) {
+                    assert!(in_circle(xy(t[0]), xy(t[1]), xy(t[2]), xy(p)) <= 1E-12);
+                }
+            }
+        }
+
+        // adjacency is symmetric, and every edge is shared by at most two triangles
+        for t in 0..tri.triangles.len() {
+            for i in 0..3 {
+                if let Some(u) = tri.adjacent[t][i] {
+                    let (a, b) = tri.edge(t, i);
+                    assert!((0..3).any(|j| tri.edge(u, j) == (b, a)));
+                }
+            }
+        }
+        let edges = tri.edges();
+        let mut unique = edges.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), edges.len());
+
+        assert!(delaunay(&points[..2]).triangles.is_empty());
+    }
+
+    #[test]
+    fn test_triangulate() {
+        // an E shape, clockwise and closed
+        let mut hull: Vec<Point> = [
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 2.0),
+            (3.0, 2.0),
+            (3.0, 3.0),
+            (1.0, 3.0),
+            (1.0, 4.0),
+            (3.0, 4.0),
+            (3.0, 5.0),
+            (0.0, 5.0),
+            // collinear
+            (0.0, 2.5),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(id, &(x, y))| Point {
+            x,
+            y,
+            id: id as u64,
+        })
+        .collect();
+        hull.reverse();
+        hull.push(hull[0].clone());
+
+        let triangles = triangulate(&hull);
+        assert!(triangles.len() <= 13 - 2);
+        let area: f64 = triangles
+            .iter()
+            .map(|[a, b, c]| orient((a.x, a.y), (b.x, b.y), (c.x, c.y)) / 2.0)
+            .sum();
+        assert!((area - 11.0).abs() < 1E-12);
+        // every triangle is counter-clockwise and inside the E
+        let prepared = crate::prepared::PreparedHull::new(&hull);
+        for [a, b, c] in triangles.iter() {
+            assert!(orient((a.x, a.y), (b.x, b.y), (c.x, c.y)) > 0.0);
+            assert!(prepared.contains(&Point {
+                x: (a.x + b.x + c.x) / 3.0,
+                y: (a.y + b.y + c.y) / 3.0,
+                id: 0,
+            }));
+        }
+
+        assert!(triangulate(&hull[..2]).is_empty());
+    }
+}
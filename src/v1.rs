@@ -0,0 +1,415 @@
+/// Stable API, Version 1
+///
+/// This module is the part of the crate to depend on in production. Everything it exports
+/// follows semantic versioning: nothing here is removed, renamed or changed in meaning
+/// outside a major release, while the free functions and modules elsewhere in the crate
+/// keep evolving. Deprecated items stay available, with a `#[deprecated]` note pointing to
+/// their replacement, until the next major release.
+///
+/// To leave room for additions, [`HullConfig`] and [`Error`] are `#[non_exhaustive]`:
+/// configurations are built from [`HullConfig::default`] with the `with_*` methods, and
+/// matches on errors need a wildcard arm.
+///
+/// The re-exported types are defined in other modules but kept to the same promise, which
+/// covers their public fields, variants, methods and trait implementations as they are now;
+/// their modules may only add to them. [`Point`] and [`BoundingBox`] keep their fields. So
+/// that variants and fields can be added, [`Metric`] is `#[non_exhaustive]`, and so are
+/// [`AreaMoments`] and [`BoundaryDistance`], which are read but not built outside the
+/// crate. [`Crs`] and [`PreparedHull`] have private fields and are used through their
+/// methods.
+///
+/// ```
+/// use concave_hull::v1::{compute_hull, HullConfig};
+///
+/// let hull = compute_hull(&points, &HullConfig::default().with_k(5))?;
+/// println!("{} vertices, area {}", hull.vertices().len(), hull.area());
+/// ```
+pub use crate::boundary::BoundaryDistance;
+pub use crate::crs::Crs;
+pub use crate::metric::Metric;
+pub use crate::moments::AreaMoments;
+pub use crate::point::{BoundingBox, Point};
+pub use crate::prepared::PreparedHull;
+
+use crate::error::ConcaveHullError;
+use crate::multi::MultiHull;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Parameters of a hull computation.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct HullConfig {
+    /// number of nearest neighbours considered at each step, at least 3
+    pub k: usize,
+    /// whether to raise `k` until a hull enclosing every point is found
+    pub iterate: bool,
+    /// how distances and angles between points are measured
+    pub metric: Metric,
+    /// the coordinate reference system of the points, carried into the hull
+    pub crs: Option<Crs>,
+}
+
+impl Default for HullConfig {
+    /// `k` of 3, iterating, Euclidean distances, no coordinate reference system.
+    fn default() -> Self {
+        HullConfig {
+            k: 3,
+            iterate: true,
+            metric: Metric::Euclidean,
+            crs: None,
+        }
+    }
+}
+
+impl HullConfig {
+    /// The configuration with `k` nearest neighbours, raised to 3 if smaller.
+    pub fn with_k(mut self, k: usize) -> Self {
+        self.k = k.max(3);
+        self
+    }
+
+    /// The configuration with `iterate` set.
+    pub fn with_iterate(mut self, iterate: bool) -> Self {
+        self.iterate = iterate;
+        self
+    }
+
+    /// The configuration with `metric` set.
+    pub fn with_metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// The configuration for points in the coordinate reference system `crs`.
+    pub fn with_crs(mut self, crs: Crs) -> Self {
+        self.crs = Some(crs);
+        self
+    }
+}
+
+/// Why no hull was computed.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// there are not more points than the `k` nearest neighbours looked at
+    TooFewPoints {
+        /// the least number of points the configuration needs
+        required: usize,
+        /// the number of points given
+        found: usize,
+    },
+    /// no `k` up to `max_k` gave a hull enclosing every point
+    NotFound {
+        /// the largest `k` tried
+        max_k: usize,
+    },
+    /// a point has a NaN or infinite coordinate
+    InvalidCoordinate {
+        /// the id of the point
+        id: u64,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::TooFewPoints { required, found } => write!(
+                f,
+                "too few points for a hull: {} needed, {} given",
+                required, found
+            ),
+            Error::NotFound { max_k } => {
+                write!(f, "no hull enclosing every point for k up to {}", max_k)
+            }
+            Error::InvalidCoordinate { id } => {
+                write!(f, "point {} has a non-finite coordinate", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A computed concave hull.
+///
+/// The vertices never change once computed, so the derived properties are computed on
+/// first use and kept: asking a hull for its area, perimeter, bounding box, moments or
+/// prepared index again costs nothing.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hull {
+    /// the boundary, each vertex once
+    vertices: Vec<Point>,
+    /// the number of nearest neighbours that produced it
+    k: usize,
+    /// the coordinate reference system of the points it was computed from
+    crs: Option<Crs>,
+    /// the derived properties computed so far
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cache: HullCache,
+}
+
+/// The properties of a [`Hull`] derived from its vertices, each computed on first use.
+#[derive(Clone, Default)]
+struct HullCache {
+    area: OnceLock<f64>,
+    perimeter: OnceLock<f64>,
+    bounding_box: OnceLock<BoundingBox>,
+    moments: OnceLock<Option<AreaMoments>>,
+    prepared: OnceLock<PreparedHull>,
+}
+
+impl fmt::Debug for HullCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HullCache")
+            .field("area", &self.area.get())
+            .field("perimeter", &self.perimeter.get())
+            .field("prepared", &self.prepared.get().is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for HullCache {
+    /// What has been computed so far makes no difference to a hull.
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Hull {
+    /// The boundary vertices in order, each once; the last connects back to the first.
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// The boundary as a closed ring, its first vertex repeated at the end, as
+    /// [`crate::concave_hull`] returns it.
+    pub fn to_ring(&self) -> Vec<Point> {
+        let mut ring = self.vertices.clone();
+        if let Some(first) = ring.first().cloned() {
+            ring.push(first);
+        }
+        ring
+    }
+
+    /// Consumes the hull, returning its boundary vertices as [`Hull::vertices`] does.
+    pub fn into_vertices(self) -> Vec<Point> {
+        self.vertices
+    }
+
+    /// The number of nearest neighbours the hull was computed with.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The coordinate reference system of the points, as given by [`HullConfig::crs`].
+    pub fn crs(&self) -> Option<&Crs> {
+        self.crs.as_ref()
+    }
+
+    /// The enclosed area, in squared coordinate units.
+    pub fn area(&self) -> f64 {
+        *self
+            .cache
+            .area
+            .get_or_init(|| crate::buffer::signed_area(&self.vertices).abs())
+    }
+
+    /// The centroid and second moments of the enclosed area, `None` if it is empty.
+    pub fn moments(&self) -> Option<AreaMoments> {
+        self.cache
+            .moments
+            .get_or_init(|| crate::moments::area_moments(&self.vertices))
+            .clone()
+    }
+
+    /// The centroid of the enclosed area as `(x, y)`, `None` if it is empty.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        self.moments().map(|moments| (moments.cx, moments.cy))
+    }
+
+    /// The length of the boundary, in coordinate units.
+    pub fn perimeter(&self) -> f64 {
+        *self.cache.perimeter.get_or_init(|| {
+            let n = self.vertices.len();
+            if n < 2 {
+                return 0.0;
+            }
+            (0..n)
+                .map(|i| {
+                    let (a, b) = (&self.vertices[i], &self.vertices[(i + 1) % n]);
+                    (b.x - a.x).hypot(b.y - a.y)
+                })
+                .sum()
+        })
+    }
+
+    /// The length of the boundary from `a` to `b` both ways round, see
+    /// [`crate::boundary::boundary_distance`].
+    pub fn boundary_distance(&self, a: &Point, b: &Point) -> Option<BoundaryDistance> {
+        crate::boundary::boundary_distance(&self.vertices, a, b)
+    }
+
+    /// The axis-aligned bounding box of the boundary.
+    pub fn bounding_box(&self) -> BoundingBox {
+        *self
+            .cache
+            .bounding_box
+            .get_or_init(|| BoundingBox::from_points(&self.vertices))
+    }
+
+    /// Whether `point` lies inside the hull.
+    ///
+    /// The first query prepares the hull, see [`Hull::prepared`]; the rest reuse it.
+    pub fn contains(&self, point: &Point) -> bool {
+        self.prepared().contains(point)
+    }
+
+    /// The hull with an edge index for repeated membership and distance queries, built on
+    /// first use and kept with the hull.
+    pub fn prepared(&self) -> &PreparedHull {
+        self.cache
+            .prepared
+            .get_or_init(|| PreparedHull::new(&self.vertices))
+    }
+
+    /// A copy of [`Hull::prepared`] to keep apart from the hull.
+    pub fn prepare(&self) -> PreparedHull {
+        self.prepared().clone()
+    }
+}
+
+impl From<Hull> for MultiHull {
+    /// The hull as one polygon without holes, in the coordinate reference system of the hull.
+    fn from(hull: Hull) -> Self {
+        MultiHull {
+            crs: hull.crs,
+            ..MultiHull::from(hull.vertices)
+        }
+    }
+}
+
+/// Computes the concave hull of `points`.
+///
+/// # Arguments
+///
+/// * `points` - 2D point cloud.
+/// * `config` - the hull parameters.
+///
+/// # Returns
+///
+/// Returns the hull, or why none was found. Without `config.iterate`, only `config.k` is
+/// tried.
+pub fn compute_hull(points: &[Point], config: &HullConfig) -> Result<Hull, Error> {
+    let k = config.k.max(3);
+    if points.len() <= k {
+        return Err(Error::TooFewPoints {
+            required: k + 1,
+            found: points.len(),
+        });
+    }
+
+    match crate::concave_hull_with_metric(&mut points.to_vec(), k, config.iterate, config.metric) {
+        Ok((hull, stats)) => Ok(Hull {
+            vertices: crate::open_ring(&hull).to_vec(),
+            k: stats.k.unwrap_or(k),
+            crs: config.crs.clone(),
+            cache: HullCache::default(),
+        }),
+        Err(ConcaveHullError::InvalidCoordinate { id }) => Err(Error::InvalidCoordinate { id }),
+        Err(ConcaveHullError::NoValidHullFound { max_k_tried }) => {
+            Err(Error::NotFound { max_k: max_k_tried })
+        }
+        // the length is checked above, and nothing cancels the search
+        Err(_) => Err(Error::NotFound { max_k: k }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_hull() {
+        let points: Vec<Point> = (0..100)
+            .map(|i| Point {
+                x: (i % 10) as f64,
+                y: (i / 10) as f64 + 0.01 * (i % 3) as f64,
+                id: i,
+            })
+            .collect();
+
+        let hull = compute_hull(&points, &HullConfig::default()).unwrap();
+        assert_eq!(
+            hull.to_ring(),
+            crate::concave_hull(&mut points.clone(), 3, true).unwrap()
+        );
+        assert!(hull.k() >= 3);
+        assert!(hull.area() > 0.0);
+        assert!(hull.perimeter() > 35.0);
+        assert!((hull.moments().unwrap().area - hull.area()).abs() < 1E-9);
+        let (cx, cy) = hull.centroid().unwrap();
+        assert!((cx - 4.5).abs() < 0.1 && (cy - 4.5).abs() < 0.1);
+        // cached properties are the ones computed afresh, and don't tell hulls apart
+        let fresh = hull.clone();
+        assert_eq!(
+            hull.area(),
+            crate::buffer::signed_area(hull.vertices()).abs()
+        );
+        assert!(std::ptr::eq(hull.prepared(), hull.prepared()));
+        assert_eq!(hull, fresh);
+        assert!(format!("{:?}", hull).contains("prepared: true"));
+        assert_eq!(hull.bounding_box(), BoundingBox::from_points(&points));
+        assert!(hull.contains(&Point {
+            x: 4.5,
+            y: 4.5,
+            id: 0,
+        }));
+
+        // the system of the points stays with the hull, and keeps apart hulls in others
+        assert_eq!(hull.crs(), None);
+        let utm = compute_hull(&points, &HullConfig::default().with_crs(Crs::epsg(32633))).unwrap();
+        assert_eq!(utm.crs(), Some(&Crs::epsg(32633)));
+        let wgs84 =
+            compute_hull(&points, &HullConfig::default().with_crs(Crs::epsg(4326))).unwrap();
+        let merged = MultiHull::from(utm.clone()).merge(MultiHull::from(hull.clone()));
+        assert_eq!(merged.unwrap().crs, Some(Crs::epsg(32633)));
+        assert!(MultiHull::from(utm).merge(MultiHull::from(wgs84)).is_err());
+
+        let config = HullConfig::default().with_k(2).with_iterate(false);
+        assert_eq!(config.k, 3);
+        assert_eq!(
+            compute_hull(&points[..3], &config),
+            Err(Error::TooFewPoints {
+                required: 4,
+                found: 3
+            })
+        );
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_hull_serde() {
+        let points: Vec<Point> = (0..20)
+            .map(|i| Point {
+                x: (i % 5) as f64,
+                y: (i / 5) as f64 + 0.01 * (i % 3) as f64,
+                id: i,
+            })
+            .collect();
+        let hull = compute_hull(&points, &HullConfig::default()).unwrap();
+
+        let json = serde_json::to_string(&hull).unwrap();
+        assert_eq!(serde_json::from_str::<Hull>(&json).unwrap(), hull);
+        let point: Point = serde_json::from_str(r#"{"x": 1.5, "y": -2.0, "id": 7}"#).unwrap();
+        assert_eq!(
+            point,
+            Point {
+                x: 1.5,
+                y: -2.0,
+                id: 7
+            }
+        );
+    }
+}
@@ -0,0 +1,226 @@
+/// Voronoi Diagrams
+use crate::point::{BoundingBox, Point};
+use crate::prepared::PreparedHull;
+use crate::triangulation::delaunay;
+
+use std::collections::HashMap;
+
+/// Computes the Voronoi cells of `points`, clipped to `bounds`.
+///
+/// Each cell is the intersection of the half-planes between its site and the site's Delaunay
+/// neighbours. Cells are counter-clockwise open rings whose vertices carry the site's id;
+/// points sharing coordinates share a cell.
+///
+/// # Arguments
+///
+/// * `points` - the Voronoi sites.
+/// * `bounds` - the box every cell is clipped to.
+///
+/// # Returns
+///
+/// Returns a map from each site's id to its cell.
+pub fn voronoi(points: &[Point], bounds: &BoundingBox) -> HashMap<u64, Vec<Point>> {
+    let frame = vec![
+        corner(bounds.min_x, bounds.min_y),
+        corner(bounds.max_x, bounds.min_y),
+        corner(bounds.max_x, bounds.max_y),
+        corner(bounds.min_x, bounds.max_y),
+    ];
+    cells(points, &frame)
+}
+
+/// Computes the Voronoi cells of `points`, clipped to a (possibly concave) hull.
+///
+/// This is the usual input to density analysis: every point gets the part of the hull's
+/// area that is closer to it than to any other point. Cells of sites far from the
+/// boundary are used as they are; only cells that reach the boundary are clipped.
+///
+/// # Arguments
+///
+/// * `points` - the Voronoi sites, normally the points the hull was computed from.
+/// * `hull` - the clipping polygon, open or closed.
+///
+/// # Returns
+///
+/// Returns a map from each site's id to its clipped cell. Cells entirely outside the hull
+/// are empty.
+pub fn voronoi_clipped(points: &[Point], hull: &[Point]) -> HashMap<u64, Vec<Point>> {
+    let ring = crate::open_ring(hull).to_vec();
+    let prepared = PreparedHull::new(&ring);
+
+    let mut bounds = BoundingBox::from_points(&ring);
+    let pad = (bounds.width() + bounds.height()).max(1.0);
+    let sites = BoundingBox::from_points(points);
+    bounds.min_x = bounds.min_x.min(sites.min_x) - pad;
+    bounds.min_y = bounds.min_y.min(sites.min_y) - pad;
+    bounds.max_x = bounds.max_x.max(sites.max_x) + pad;
+    bounds.max_y = bounds.max_y.max(sites.max_y) + pad;
+
+    let unclipped = voronoi(points, &bounds);
+    let mut clipped = HashMap::with_capacity(unclipped.len());
+    for site in points.iter() {
+        let cell = &unclipped[&site.id];
+        let reach = cell
+            .iter()
+            .map(|v| (v.x - site.x).hypot(v.y - site.y))
+            .fold(0.0, f64::max);
+
+        // a cell inside a disc that lies inside the hull needs no clipping
+        let inside = prepared.contains(site) && prepared.distance(site) >= reach;
+        let polygon = if inside {
+            cell.clone()
+        } else {
+            let mut polygon = ring.clone();
+            for i in 0..cell.len() {
+                let (a, b) = (&cell[i], &cell[(i + 1) % cell.len()]);
+                polygon = clip(&polygon, |p| cross(a, b, p));
+            }
+            for v in polygon.iter_mut() {
+                v.id = site.id;
+            }
+            polygon
+        };
+        clipped.insert(site.id, polygon);
+    }
+
+    clipped
+}
+
+/// Builds every cell by clipping `frame` with the bisectors towards the site's neighbours.
+fn cells(points: &[Point], frame: &[Point]) -> HashMap<u64, Vec<Point>> {
+    let triangulation = delaunay(points);
+
+    let mut neighbours = vec![Vec::new(); points.len()];
+    for (a, b) in triangulation.edges() {
+        neighbours[a].push(b);
+        neighbours[b].push(a);
+    }
+    let triangulated = !triangulation.triangles.is_empty();
+
+    // duplicates are left out of the triangulation, so they borrow their twin's neighbours
+    let mut twins: HashMap<(u64, u64), usize> = HashMap::new();
+    for (i, p) in points.iter().enumerate() {
+        if !neighbours[i].is_empty() {
+            twins.insert((p.x.to_bits(), p.y.to_bits()), i);
+        }
+    }
+
+    let mut result = HashMap::with_capacity(points.len());
+    for (i, site) in points.iter().enumerate() {
+        let owner = twins
+            .get(&(site.x.to_bits(), site.y.to_bits()))
+            .copied()
+            .unwrap_or(i);
+        let candidates: Vec<usize> = if triangulated {
+            neighbours[owner].clone()
+        } else {
+            // collinear or tiny inputs have no triangles; every other site is a neighbour
+            (0..points.len()).collect()
+        };
+
+        let mut cell = frame.to_vec();
+        for &j in candidates.iter() {
+            let other = &points[j];
+            if other.x == site.x && other.y == site.y {
+                continue;
+            }
+            let (mx, my) = ((site.x + other.x) / 2.0, (site.y + other.y) / 2.0);
+            let (dx, dy) = (other.x - site.x, other.y - site.y);
+            cell = clip(&cell, |p| -((p.x - mx) * dx + (p.y - my) * dy));
+        }
+        for v in cell.iter_mut() {
+            v.id = site.id;
+        }
+        result.insert(site.id, cell);
+    }
+
+    result
+}
+
+/// Keeps the part of `polygon` where `side` is non-negative (Sutherland-Hodgman, one plane).
+fn clip(polygon: &[Point], side: impl Fn(&Point) -> f64) -> Vec<Point> {
+    let mut out = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let (a, b) = (&polygon[i], &polygon[(i + 1) % polygon.len()]);
+        let (sa, sb) = (side(a), side(b));
+        if sa >= 0.0 {
+            out.push(a.clone());
+        }
+        if (sa >= 0.0) != (sb >= 0.0) {
+            let t = sa / (sa - sb);
+            out.push(Point {
+                x: a.x + t * (b.x - a.x),
+                y: a.y + t * (b.y - a.y),
+                id: a.id,
+            });
+        }
+    }
+    out
+}
+
+/// Positive when `p` lies left of the directed line `a`-`b`.
+fn cross(a: &Point, b: &Point, p: &Point) -> f64 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+fn corner(x: f64, y: f64) -> Point {
+    Point { x, y, id: 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(ring: &[Point]) -> f64 {
+        (0..ring.len())
+            .map(|i| {
+                let (a, b) = (&ring[i], &ring[(i + 1) % ring.len()]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f64>()
+            / 2.0
+    }
+
+    #[test]
+    fn test_voronoi() {
+        let sites: Vec<Point> = [(0.5, 0.5), (1.5, 0.5), (0.5, 1.5), (1.5, 1.5), (1.0, 1.0)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| Point {
+                x,
+                y,
+                id: 10 + i as u64,
+            })
+            .collect();
+        let bounds = BoundingBox {
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 2.0,
+            max_y: 2.0,
+        };
+
+        let cells = voronoi(&sites, &bounds);
+        assert_eq!(cells.len(), 5);
+        // the centre site owns the diamond between the four others
+        assert!((area(&cells[&14]) - 0.5).abs() < 1E-12);
+        assert!((area(&cells[&10]) - 0.875).abs() < 1E-12);
+        assert!(cells[&12].iter().all(|v| v.id == 12));
+
+        // an L-shaped hull: clipped cells tile it exactly
+        let hull: Vec<Point> = [
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 2.0),
+            (0.0, 2.0),
+        ]
+        .iter()
+        .map(|&(x, y)| corner(x, y))
+        .collect();
+        let clipped = voronoi_clipped(&sites, &hull);
+        let total: f64 = clipped.values().map(|c| area(c)).sum();
+        assert!((total - 3.0).abs() < 1E-12);
+        assert!(area(&clipped[&13]).abs() < 1E-12);
+    }
+}
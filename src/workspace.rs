@@ -0,0 +1,175 @@
+/// Reusable Scratch Space
+///
+/// Repeated hulls, e.g. one per frame of a sensor stream, spend much of their time
+/// allocating the same buffers again. A [`Workspace`] keeps most of them between calls to
+/// [`concave_hull_with_workspace`]:
+///
+/// ```
+/// use concave_hull::workspace::{concave_hull_with_workspace, Workspace};
+///
+/// let mut workspace = Workspace::new();
+/// for frame in frames {
+///     let hull = concave_hull_with_workspace(&frame, 5, true, &mut workspace)?;
+///     draw(hull);
+/// }
+/// ```
+use crate::error::ConcaveHullError;
+use crate::point::Point;
+use crate::Options;
+
+/// Scratch buffers of the hull computation, kept between calls.
+///
+/// The buffers grow to the largest input seen and are never shrunk, so once a workspace
+/// has seen a dataset of a given size, the walk's copy of the points, its neighbour and
+/// candidate lists, the vertex positions and the returned hull are not allocated again for
+/// datasets up to that size. This saves most allocations, not all of them: the neighbour
+/// index is built once per call, its queries return their neighbours in new vectors, and
+/// the containment check of large hulls indexes their edges.
+#[derive(Default)]
+pub struct Workspace {
+    /// the walk's copy of the points, with their positions as ids
+    pub(crate) positions: Vec<Point>,
+    /// the neighbours of the current step
+    pub(crate) nearest: Vec<Neighbour>,
+    /// the ids of the neighbours of the current step by turning angle
+    pub(crate) candidates: Vec<u32>,
+    /// sorted positions of the hull vertices
+    pub(crate) vertices: Vec<usize>,
+    /// sorted positions of the vertices of rejected hulls
+    pub(crate) dropped: Vec<usize>,
+    /// the last hull
+    hull: Vec<Point>,
+}
+
+/// A neighbour of the current point of the walk.
+pub(crate) struct Neighbour {
+    /// its id in the walk's index: its position, or the closing id of the first point
+    pub(crate) id: u32,
+    /// distance from the current point
+    pub(crate) distance: f64,
+    /// turn from the previous edge, in [0, 2π)
+    pub(crate) angle: f64,
+}
+
+impl Workspace {
+    /// An empty workspace; its buffers grow with the first call.
+    pub fn new() -> Self {
+        Workspace::default()
+    }
+
+    /// A workspace whose buffers already fit datasets of `points` points at `k` neighbours,
+    /// so the first call doesn't grow them either.
+    pub fn with_capacity(points: usize, k: usize) -> Self {
+        Workspace {
+            positions: Vec::with_capacity(points),
+            nearest: Vec::with_capacity(k),
+            candidates: Vec::with_capacity(k),
+            vertices: Vec::with_capacity(points),
+            dropped: Vec::with_capacity(points),
+            hull: Vec::with_capacity(points + 1),
+        }
+    }
+}
+
+impl std::fmt::Debug for Workspace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Workspace")
+            .field("capacity", &self.positions.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Computes the concave hull of a given set of points in the buffers of `workspace`.
+///
+/// This works like [`crate::concave_hull_borrowed`], but returns the hull borrowed from
+/// `workspace`, where it stays until the next call.
+///
+/// # Arguments
+///
+/// * `dataset` -  2D point cloud; it is left unchanged.
+/// * `k` - number of nearest neighbors.
+/// * `iterate` - A boolean flag that, when set to `false`, stops the iteration
+///   as soon as the algorithm succeeds.
+/// * `workspace` - the buffers to compute in.
+///
+/// # Returns
+///
+/// Returns the hull as [`crate::concave_hull`] does, or a [`ConcaveHullError`].
+pub fn concave_hull_with_workspace<'a>(
+    dataset: &[Point],
+    k: usize,
+    iterate: bool,
+    workspace: &'a mut Workspace,
+) -> Result<&'a [Point], ConcaveHullError> {
+    crate::check_input(dataset, k)?;
+
+    let options = Options::default();
+    let mut positions = std::mem::take(&mut workspace.positions);
+    positions.clear();
+    positions.extend(dataset.iter().enumerate().map(|(i, p)| Point {
+        x: p.x,
+        y: p.y,
+        id: i as u64,
+    }));
+    let mut tree = crate::index::build_index(&positions, &options);
+    let mut hull = std::mem::take(&mut workspace.hull);
+    let found = crate::search_in(
+        dataset,
+        &positions,
+        tree.as_mut(),
+        k,
+        iterate,
+        usize::MAX,
+        &options,
+        false,
+        &mut hull,
+        workspace,
+    );
+    workspace.positions = positions;
+    workspace.hull = hull;
+    found.map(|_| &workspace.hull[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::Rng;
+
+    #[test]
+    fn test_concave_hull_with_workspace() {
+        let mut rng = Rng::new(3);
+        let points: Vec<Point> = (0..400)
+            .map(|id| Point {
+                x: rng.uniform(),
+                y: rng.uniform(),
+                id,
+            })
+            .collect();
+
+        let mut workspace = Workspace::new();
+        let hull = concave_hull_with_workspace(&points, 3, true, &mut workspace).unwrap();
+        assert_eq!(
+            Ok(hull.to_vec()),
+            crate::concave_hull_borrowed(&points, 3, true)
+        );
+        let buffer = hull.as_ptr();
+        let capacity = workspace.positions.capacity();
+
+        // warm, the same buffers are used again
+        let hull = concave_hull_with_workspace(&points[..300], 3, true, &mut workspace).unwrap();
+        assert_eq!(
+            Ok(hull.to_vec()),
+            crate::concave_hull_borrowed(&points[..300], 3, true)
+        );
+        assert_eq!(hull.as_ptr(), buffer);
+        assert_eq!(workspace.positions.capacity(), capacity);
+
+        assert_eq!(
+            concave_hull_with_workspace(&points[..3], 3, true, &mut workspace),
+            Err(ConcaveHullError::InsufficientPoints {
+                required: 4,
+                found: 3
+            })
+        );
+    }
+}